@@ -1,6 +1,7 @@
 use std::{path::PathBuf, str::FromStr};
 
 use clap::{Parser, Subcommand, ValueEnum};
+use reqwest::Url;
 use serde::{Deserialize, Serialize};
 use tracing::level_filters::LevelFilter;
 
@@ -39,6 +40,18 @@ pub struct Cli {
     #[clap(help_heading = "GLOBAL OPTIONS", global = true)]
     pub store_path: Option<PathBuf>,
 
+    /// Read the dependency graph from a file instead of running `cargo metadata`
+    ///
+    /// The file is either the JSON output of `cargo metadata
+    /// --format-version=1`, or a Cargo.lock for projects which aren't built
+    /// with cargo, told apart by their contents. A Cargo.lock doesn't say how
+    /// packages depend on each other, so every package in it is treated as a
+    /// root. Feature, platform and workspace settings aren't applied to the
+    /// graph read from the file.
+    #[clap(long, value_name = "PATH", parse(from_os_str))]
+    #[clap(help_heading = "GLOBAL OPTIONS", global = true)]
+    pub metadata_file: Option<PathBuf>,
+
     /// Don't use --all-features
     ///
     /// We default to passing --all-features to `cargo metadata`
@@ -57,6 +70,17 @@ pub struct Cli {
     #[clap(help_heading = "GLOBAL OPTIONS", global = true)]
     pub features: Vec<String>,
 
+    /// Only include dependencies used when building for the given target
+    /// triple. It can be specified multiple times for multiple targets.
+    ///
+    /// This is passed through to `cargo metadata`, and overrides the
+    /// `filter-platforms` list in config.toml.
+    ///
+    /// Example: `--filter-platform=x86_64-unknown-linux-gnu`
+    #[clap(long, action, value_name = "TRIPLE")]
+    #[clap(help_heading = "GLOBAL OPTIONS", global = true)]
+    pub filter_platform: Vec<String>,
+
     /// Do not fetch new imported audits.
     #[clap(long, action)]
     #[clap(help_heading = "GLOBAL OPTIONS", global = true)]
@@ -116,6 +140,15 @@ pub struct Cli {
     #[clap(help_heading = "GLOBAL OPTIONS", global = true)]
     pub cache_dir: Option<PathBuf>,
 
+    /// Base URL of a mirror of the crates.io API to use instead of
+    /// `https://crates.io/api/`
+    ///
+    /// Package downloads and crates.io API requests are made relative to this
+    /// URL, e.g. `{mirror}/v1/crates/{name}/{version}/download`.
+    #[clap(long, action, env = "CARGO_VET_CRATES_IO_MIRROR")]
+    #[clap(help_heading = "GLOBAL OPTIONS", global = true)]
+    pub crates_io_mirror: Option<Url>,
+
     /// The date and time to use as now.
     #[clap(long, action, hide = true)]
     #[clap(help_heading = "GLOBAL OPTIONS", global = true)]
@@ -236,6 +269,37 @@ pub enum Commands {
     #[clap(disable_version_flag = true)]
     Suggest(SuggestArgs),
 
+    /// Explain how a package was vetted
+    ///
+    /// For each criteria the package is required to satisfy (or does satisfy),
+    /// this prints the path of audits, imports, wildcard audits, trusted
+    /// publishers and exemptions which connect the package's version to a full
+    /// audit, along with each entry's file, auditors and notes.
+    ///
+    /// If no such path exists, this prints the versions which could be
+    /// reached from a full audit and from the package's version, as the
+    /// missing audit needs to connect the two.
+    ///
+    /// If no version is given, every version of the package in the
+    /// dependency graph is explained.
+    #[clap(disable_version_flag = true)]
+    Explain(ExplainArgs),
+
+    /// Explain why a package requires the criteria it does
+    ///
+    /// For each of the criteria the package is required to satisfy, this
+    /// prints the roots and policy entries (`criteria`, `dependency-criteria`
+    /// and `dev-criteria`) which introduce the requirement, along with the
+    /// shortest dependency path from each of them to the package.
+    ///
+    /// This can help decide whether a policy should be changed rather than
+    /// auditing the package for stronger criteria.
+    ///
+    /// If no version is given, every version of the package in the
+    /// dependency graph is explained.
+    #[clap(disable_version_flag = true)]
+    Why(WhyArgs),
+
     /// Initialize cargo-vet for your project
     ///
     /// This will add `exemptions` and `audit-as-crates-io = false` for all packages that
@@ -247,6 +311,35 @@ pub enum Commands {
     #[clap(disable_version_flag = true)]
     Init(InitArgs),
 
+    /// Export a software bill of materials annotated with vet status
+    ///
+    /// Every package in the dependency graph is listed, and each third-party
+    /// package is annotated with the criteria it satisfies, the audits,
+    /// imports, trusted publishers or exemptions which vetted it, and the
+    /// names of its auditors.
+    ///
+    /// Packages which fail to vet are still listed, and are annotated with
+    /// the criteria they're missing.
+    #[clap(disable_version_flag = true)]
+    Sbom(SbomArgs),
+
+    /// Summarize the changes to the supply-chain between two revisions
+    ///
+    /// Each side is either a git revision of the repository containing the
+    /// store, or a directory containing a copy of the store's files. If only
+    /// one side is given, it is compared against the store on disk.
+    ///
+    /// Rather than a textual diff of the store's files, this lists the audits
+    /// and violations which were added or removed, the exemptions which were
+    /// added, removed, broadened or narrowed, the imports which were added or
+    /// removed, the policy criteria which changed, and the trusted publishers
+    /// which were added or removed.
+    ///
+    /// With `--output-format=json` the changes are printed as JSON, and with
+    /// `--markdown` they are formatted for use in a pull request comment.
+    #[clap(disable_version_flag = true)]
+    StoreDiff(StoreDiffArgs),
+
     // Fetch Commands
     /// Fetch the source of a package
     ///
@@ -340,8 +433,9 @@ pub enum Commands {
     /// tests and have an audit that only claims safe-to-run, we won't mention it.
     ///
     /// When a violation *does* cause an integrity error, it's up to you and your
-    /// peers to figure out what to do about it. There isn't yet a mechanism for
-    /// dealing with disagreements with a peer's published violations.
+    /// peers to figure out what to do about it. If you disagree with a violation
+    /// published by a peer, you can add a `disputes` entry to your config.toml to
+    /// stop it from being enforced.
     #[clap(disable_version_flag = true)]
     RecordViolation(RecordViolationArgs),
 
@@ -405,10 +499,11 @@ pub enum Commands {
     #[clap(disable_version_flag = true)]
     Gc(GcArgs),
 
-    /// Renew wildcard audit expirations
+    /// Renew wildcard audit and exemption expirations
     ///
-    /// This will set a wildcard audit expiration to be one year in the future from when it is run.
-    /// It can optionally do this for all audits which are expiring soon.
+    /// This will set a wildcard audit or exemption expiration to be one year in the future from
+    /// when it is run. It can optionally do this for all audits and exemptions which are expiring
+    /// soon. Exemptions without an `end` date never expire, and are left alone.
     #[clap(disable_version_flag = true)]
     Renew(RenewArgs),
 }
@@ -451,11 +546,73 @@ pub enum RegenerateSubcommands {
 }
 
 #[derive(clap::Args)]
-pub struct CheckArgs {}
+pub struct CheckArgs {
+    /// Only fail for packages which newly fail to vet since a baseline
+    ///
+    /// The baseline is either a path to the `Cargo.lock` of a checkout of the
+    /// baseline, or a git revision to check out. The dependency graph of the
+    /// baseline is resolved against its own store, and third-party packages
+    /// which already failed to vet for the same criteria there are ignored, so
+    /// that CI only fails for the gaps introduced by the current change.
+    #[clap(long, action)]
+    pub baseline: Option<String>,
+}
 
 #[derive(clap::Args)]
 pub struct InitArgs {}
 
+#[derive(clap::Args)]
+pub struct ExplainArgs {
+    /// The package to explain
+    #[clap(action)]
+    pub package: PackageName,
+    /// The version to explain
+    #[clap(action)]
+    pub version: Option<VetVersion>,
+}
+
+#[derive(clap::Args)]
+pub struct WhyArgs {
+    /// The package to explain
+    #[clap(action)]
+    pub package: PackageName,
+    /// The version to explain
+    #[clap(action)]
+    pub version: Option<VetVersion>,
+}
+
+#[derive(clap::Args)]
+pub struct SbomArgs {
+    /// The SBOM document format to produce.
+    #[clap(long, value_enum, action)]
+    #[clap(default_value_t = SbomFormat::Cyclonedx)]
+    pub format: SbomFormat,
+}
+
+#[derive(clap::Args)]
+pub struct StoreDiffArgs {
+    /// The old revision of the store (a git revision or a directory)
+    #[clap(action)]
+    pub old: String,
+    /// The new revision of the store (a git revision or a directory)
+    ///
+    /// Defaults to the store on disk.
+    #[clap(action)]
+    pub new: Option<String>,
+    /// Format the human-readable output as markdown, for use in a pull request
+    /// comment.
+    #[clap(long, action)]
+    pub markdown: bool,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, ValueEnum)]
+pub enum SbomFormat {
+    /// A CycloneDX 1.5 JSON document.
+    Cyclonedx,
+    /// An SPDX 2.3 JSON document.
+    Spdx,
+}
+
 /// Inspect a crate at a specific version
 #[derive(clap::Args)]
 pub struct InspectArgs {
@@ -495,6 +652,10 @@ pub struct DiffArgs {
     /// This option is ignored if a git version is passed.
     #[clap(long, action)]
     pub mode: Option<FetchMode>,
+    /// Show a local diff with `git diff` in a pager, rather than the built-in
+    /// viewer
+    #[clap(long, action)]
+    pub git_diff: bool,
 }
 
 /// Certifies a package as audited
@@ -573,10 +734,27 @@ pub struct ImportArgs {
     pub name: ImportName,
     /// The URL(s) of the peer's audits.toml file(s).
     ///
+    /// `file://` URLs and git sources such as
+    /// `git+https://example.com/repo.git#path=supply-chain/audits.toml&rev=main`
+    /// are also supported.
+    ///
     /// If a URL is not provided, a peer with the given name will be looked up
     /// in the cargo-vet registry to determine the import URL(s).
     #[clap(action)]
     pub url: Vec<String>,
+    /// minisign public key(s) which the peer's audits.toml must be signed with.
+    ///
+    /// The signature will be fetched from the audits.toml URL with `.minisig`
+    /// appended.
+    #[clap(long, action)]
+    pub public_key: Vec<String>,
+    /// Re-fetch an existing import and move its sha256 or rev pin forward.
+    ///
+    /// The resulting changes to imports.lock are printed for review. If the
+    /// import wasn't already pinned, it will be pinned to its current contents,
+    /// or to the current commits of its URLs if they are all git sources.
+    #[clap(long, action)]
+    pub update: bool,
 }
 
 /// Trust a crate's publisher
@@ -746,7 +924,7 @@ pub struct GcArgs {
 #[derive(clap::Args)]
 pub struct RenewArgs {
     // Change this doc string if the WILDCARD_AUDIT_EXPIRATION_STRING changes.
-    /// Renew all wildcard audits which will have expired six weeks from now.
+    /// Renew all wildcard audits and exemptions which will have expired six weeks from now.
     #[clap(long, action, conflicts_with("crate-name"))]
     pub expiring: bool,
 
@@ -766,6 +944,12 @@ pub struct DumpGraphArgs {
     #[clap(long, value_enum, action)]
     #[clap(default_value_t = DumpGraphDepth::FirstParty)]
     pub depth: DumpGraphDepth,
+
+    /// Print the graph as `cargo metadata` JSON, which can be read back with
+    /// `--metadata-file`. Combined with `--filter-graph`, this produces a
+    /// reduced graph which reproduces an issue without the original project.
+    #[clap(long, action)]
+    pub metadata: bool,
 }
 
 #[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, ValueEnum)]
@@ -802,6 +986,10 @@ pub enum OutputFormat {
     Human,
     /// Print output in a machine-readable form with minimal extra context.
     Json,
+    /// Print problems as a SARIF log, for code scanning tools.
+    Sarif,
+    /// Print a JUnit XML report with a test case for each third-party package.
+    Junit,
 }
 
 #[derive(Clone, Debug)]
@@ -2,19 +2,27 @@
 
 use std::fmt;
 
-use crate::format::{
-    CriteriaEntry, CriteriaName, CriteriaStr, FastMap, SortedMap, SAFE_TO_DEPLOY, SAFE_TO_RUN,
+use crate::{
+    errors::CriteriaDefinitionError,
+    format::{
+        CriteriaEntry, CriteriaName, CriteriaStr, FastMap, SortedMap, SAFE_TO_DEPLOY, SAFE_TO_RUN,
+    },
 };
 
-/// Set of booleans, 64 should be Enough For Anyone (but abstracting in case not).
+/// Set of booleans, one for each criteria known to a [`CriteriaMapper`].
 ///
 /// Note that this intentionally doesn't implement Default to allow the implementation
 /// to require the CriteriaMapper to provide the count of items at construction time.
-/// Which will be useful if we ever decide to give it ~infinite capacity and wrap
-/// a BitSet.
+///
+/// The first 64 criteria are stored inline, as almost every project has fewer
+/// than that, so the common case never allocates. Any further criteria are
+/// stored in `overflow`, 64 to a word.
 #[derive(Clone)]
-pub struct CriteriaSet(u64);
-const MAX_CRITERIA: usize = u64::BITS as usize; // funnier this way
+pub struct CriteriaSet {
+    inline: u64,
+    overflow: Vec<u64>,
+}
+const WORD_BITS: usize = u64::BITS as usize;
 
 /// A processed version of config.toml's criteria definitions, for mapping
 /// lists of criteria names to CriteriaSets.
@@ -29,7 +37,21 @@ pub struct CriteriaMapper {
 }
 
 impl CriteriaMapper {
+    /// Build a mapper for criteria definitions which are known to be valid,
+    /// such as those from a store which has passed validation.
     pub fn new(criteria: &SortedMap<CriteriaName, CriteriaEntry>) -> CriteriaMapper {
+        match Self::try_new(criteria) {
+            Ok(mapper) => mapper,
+            Err(err) => panic!("{err}"),
+        }
+    }
+
+    /// Build a mapper for criteria definitions, reporting an error if they
+    /// shadow a built-in criteria, imply an unknown criteria, or imply
+    /// themselves.
+    pub fn try_new(
+        criteria: &SortedMap<CriteriaName, CriteriaEntry>,
+    ) -> Result<CriteriaMapper, CriteriaDefinitionError> {
         // Fixed indices for built-in criteria
         const SAFE_TO_RUN_IDX: usize = 0;
         const SAFE_TO_DEPLOY_IDX: usize = 1;
@@ -46,8 +68,7 @@ impl CriteriaMapper {
         let mut index = FastMap::with_capacity(names.len());
         for (idx, name) in names.iter().enumerate() {
             if index.insert(name.clone(), idx).is_some() {
-                // XXX: Consider producing a better error here?
-                panic!("Cannot specify multiple criteria with the name '{name}'");
+                return Err(CriteriaDefinitionError::Duplicate(name.clone()));
             }
         }
 
@@ -58,7 +79,13 @@ impl CriteriaMapper {
         for (name, entry) in criteria {
             let idx = index[name];
             for implied in &entry.implies {
-                direct_implies[idx].set_criteria(index[&**implied]);
+                let Some(&implied_idx) = index.get(&**implied) else {
+                    return Err(CriteriaDefinitionError::UnknownImplied {
+                        criteria: name.clone(),
+                        implied: implied.to_string(),
+                    });
+                };
+                direct_implies[idx].set_criteria(implied_idx);
             }
         }
 
@@ -84,19 +111,18 @@ impl CriteriaMapper {
                 let mut implied = CriteriaSet::none(names.len());
                 recurse_implies(&mut implied, &direct_implies, idx);
                 if implied.has_criteria(idx) {
-                    // XXX: Consider producing a better error here?
-                    panic!("criteria '{}' implies itself", names[idx]);
+                    return Err(CriteriaDefinitionError::ImpliesItself(names[idx].clone()));
                 }
                 implied.set_criteria(idx);
-                implied
+                Ok(implied)
             })
-            .collect();
+            .collect::<Result<_, _>>()?;
 
-        CriteriaMapper {
+        Ok(CriteriaMapper {
             index,
             names,
             implied_criteria,
-        }
+        })
     }
 
     /// Builds a CriteriaSet from a list of criteria.
@@ -191,57 +217,105 @@ impl CriteriaMapper {
 
 impl CriteriaSet {
     pub fn none(count: usize) -> Self {
-        assert!(
-            count <= MAX_CRITERIA,
-            "{MAX_CRITERIA} was not Enough For Everyone ({count} criteria)"
-        );
-        CriteriaSet(0)
+        CriteriaSet {
+            inline: 0,
+            overflow: vec![0; count.saturating_sub(1) / WORD_BITS],
+        }
     }
     pub fn all(count: usize) -> Self {
-        assert!(
-            count <= MAX_CRITERIA,
-            "{MAX_CRITERIA} was not Enough For Everyone ({count} criteria)"
-        );
-        CriteriaSet((1u64 << count).wrapping_sub(1))
+        let mut set = Self::none(count);
+        // Set every bit below `count` in each word.
+        let word_mask = |word_idx: usize| {
+            let bits = count.saturating_sub(word_idx * WORD_BITS).min(WORD_BITS);
+            u64::MAX.checked_shr((WORD_BITS - bits) as u32).unwrap_or(0)
+        };
+        set.inline = word_mask(0);
+        for (idx, word) in set.overflow.iter_mut().enumerate() {
+            *word = word_mask(idx + 1);
+        }
+        set
     }
     pub fn set_criteria(&mut self, idx: usize) {
-        self.0 |= 1 << idx;
+        *self.word_mut(idx / WORD_BITS) |= 1 << (idx % WORD_BITS);
     }
     pub fn clear_criteria(&mut self, other: &CriteriaSet) {
-        self.0 &= !other.0;
+        self.inline &= !other.inline;
+        for (word, other) in self.overflow.iter_mut().zip(&other.overflow) {
+            *word &= !other;
+        }
     }
     pub fn has_criteria(&self, idx: usize) -> bool {
-        (self.0 & (1 << idx)) != 0
+        (self.word(idx / WORD_BITS) & (1 << (idx % WORD_BITS))) != 0
     }
-    pub fn _intersected_with(&mut self, other: &CriteriaSet) {
-        self.0 &= other.0;
+    pub fn intersected_with(&mut self, other: &CriteriaSet) {
+        self.inline &= other.inline;
+        for (idx, word) in self.overflow.iter_mut().enumerate() {
+            *word &= other.word(idx + 1);
+        }
     }
     pub fn unioned_with(&mut self, other: &CriteriaSet) {
-        self.0 |= other.0;
+        self.inline |= other.inline;
+        for (idx, &other) in other.overflow.iter().enumerate() {
+            if other != 0 {
+                *self.word_mut(idx + 1) |= other;
+            }
+        }
     }
     pub fn contains(&self, other: &CriteriaSet) -> bool {
-        (self.0 & other.0) == other.0
+        (self.inline & other.inline) == other.inline
+            && other
+                .overflow
+                .iter()
+                .enumerate()
+                .all(|(idx, &other)| (self.word(idx + 1) & other) == other)
     }
     pub fn is_empty(&self) -> bool {
-        self.0 == 0
+        self.inline == 0 && self.overflow.iter().all(|&word| word == 0)
     }
     pub fn indices(&self) -> impl Iterator<Item = usize> + '_ {
         // Yield all the offsets that are set by repeatedly getting the lowest 1 and clearing it
-        let mut raw = self.0;
-        std::iter::from_fn(move || {
-            if raw == 0 {
-                None
-            } else {
-                let next = raw.trailing_zeros() as usize;
-                raw &= !(1 << next);
-                Some(next)
-            }
-        })
+        std::iter::once(self.inline)
+            .chain(self.overflow.iter().copied())
+            .enumerate()
+            .flat_map(|(word_idx, mut raw)| {
+                std::iter::from_fn(move || {
+                    if raw == 0 {
+                        None
+                    } else {
+                        let next = raw.trailing_zeros() as usize;
+                        raw &= !(1 << next);
+                        Some(word_idx * WORD_BITS + next)
+                    }
+                })
+            })
+    }
+
+    /// The word containing the given word index, treating words past the end
+    /// of the set as empty.
+    fn word(&self, word_idx: usize) -> u64 {
+        match word_idx {
+            0 => self.inline,
+            _ => self.overflow.get(word_idx - 1).copied().unwrap_or(0),
+        }
+    }
+
+    /// The word containing the given word index, growing the set if needed.
+    fn word_mut(&mut self, word_idx: usize) -> &mut u64 {
+        if word_idx == 0 {
+            return &mut self.inline;
+        }
+        if self.overflow.len() < word_idx {
+            self.overflow.resize(word_idx, 0);
+        }
+        &mut self.overflow[word_idx - 1]
     }
 }
 
 impl fmt::Debug for CriteriaSet {
     fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
-        write!(fmt, "{:08b}", self.0)
+        for word in self.overflow.iter().rev() {
+            write!(fmt, "{word:064b}_")?;
+        }
+        write!(fmt, "{:08b}", self.inline)
     }
 }
@@ -0,0 +1,516 @@
+//! A built-in viewer for reviewing the changes between two versions of a
+//! package, used by `cargo vet diff --mode=local`.
+//!
+//! When attached to a terminal, the viewer shows one file at a time, a page at
+//! a time, and lets the user jump between files and mark them as reviewed.
+//! Which files have been reviewed is persisted in the cache, so that long
+//! reviews can be resumed later.
+
+use std::{fs, io, ops::Range, path::Path};
+
+use console::Key;
+use similar::{ChangeTag, TextDiff};
+
+use crate::{
+    format::{Delta, DiffStat, FastSet, PackageStr, SortedSet},
+    out::Out,
+};
+
+/// Files with more changed lines than this start out collapsed.
+const HUGE_FILE_LINES: u64 = 1000;
+/// Number of leading lines to search for a marker that a file is generated.
+const GENERATED_MARKER_LINES: usize = 5;
+/// Number of unchanged lines shown around each change.
+const CONTEXT_LINES: usize = 3;
+
+const HELP: &str = "\
+space/enter  open file, next page    b  previous page    j/k  scroll, select file
+n/p          next/previous file      u  next unreviewed  l    file list
+r            toggle reviewed         e  expand/collapse  q    quit";
+
+/// A single file which differs between the two versions of a package.
+pub struct DiffFile {
+    /// The path of the file relative to the package root, using `/` as a
+    /// separator on all platforms.
+    pub path: String,
+    /// The contents of each side, or `None` if the file doesn't exist on that
+    /// side or isn't valid UTF-8.
+    old: Option<String>,
+    new: Option<String>,
+    /// Whether either side of the file isn't text.
+    binary: bool,
+    pub stat: DiffStat,
+    /// Whether the file appears to be generated, or is too large to show
+    /// without being asked to.
+    pub collapsed_by_default: bool,
+}
+
+impl DiffFile {
+    /// Load the file from the pair of paths returned by
+    /// `Cache::diffstat_package`. A side which doesn't exist is named
+    /// `/dev/null`.
+    pub fn load(root1: &Path, root2: &Path, from: &Path, to: &Path) -> io::Result<DiffFile> {
+        fn read(path: &Path) -> io::Result<Option<Result<String, ()>>> {
+            if path == Path::new("/dev/null") {
+                return Ok(None);
+            }
+            let bytes = fs::read(path)?;
+            Ok(Some(
+                String::from_utf8(bytes)
+                    .map(|text| text.replace("\r\n", "\n"))
+                    .map_err(|_| ()),
+            ))
+        }
+
+        let rel_path = to
+            .strip_prefix(root2)
+            .or_else(|_| from.strip_prefix(root1))
+            .unwrap_or(to);
+        let path = rel_path
+            .components()
+            .map(|c| c.as_os_str().to_string_lossy())
+            .collect::<Vec<_>>()
+            .join("/");
+
+        let (old, new) = (read(from)?, read(to)?);
+        let binary = matches!(old, Some(Err(_))) || matches!(new, Some(Err(_)));
+        Ok(DiffFile::new(
+            path,
+            old.and_then(Result::ok),
+            new.and_then(Result::ok),
+            binary,
+        ))
+    }
+
+    pub fn new(path: String, old: Option<String>, new: Option<String>, binary: bool) -> DiffFile {
+        let mut stat = DiffStat {
+            insertions: 0,
+            deletions: 0,
+            files_changed: 1,
+        };
+        if !binary {
+            let (old_text, new_text) = (old.as_deref().unwrap_or(""), new.as_deref().unwrap_or(""));
+            for change in TextDiff::from_lines(old_text, new_text).iter_all_changes() {
+                match change.tag() {
+                    ChangeTag::Insert => stat.insertions += 1,
+                    ChangeTag::Delete => stat.deletions += 1,
+                    ChangeTag::Equal => {}
+                }
+            }
+        }
+
+        // Files are commonly marked with `@generated` near the top when they
+        // were produced by a tool, such as `Cargo.lock`.
+        let generated = [&old, &new].into_iter().flatten().any(|text| {
+            text.lines()
+                .take(GENERATED_MARKER_LINES)
+                .any(|line| line.contains("@generated"))
+        });
+
+        DiffFile {
+            path,
+            old,
+            new,
+            binary,
+            collapsed_by_default: generated || stat.count() > HUGE_FILE_LINES,
+            stat,
+        }
+    }
+
+    /// A short description of the size of the change to this file.
+    fn stat_summary(&self) -> String {
+        if self.binary {
+            "binary".to_owned()
+        } else {
+            format!("+{} -{}", self.stat.insertions, self.stat.deletions)
+        }
+    }
+
+    /// Render the changes to this file as styled unified diff lines.
+    fn render(&self, out: &dyn Out) -> Vec<String> {
+        let style = out.style();
+        let mut lines = Vec::new();
+        match (&self.old, &self.new) {
+            (None, _) if !self.binary => {
+                lines.push(style.clone().dim().apply_to("new file").to_string())
+            }
+            (_, None) if !self.binary => {
+                lines.push(style.clone().dim().apply_to("deleted file").to_string())
+            }
+            _ => {}
+        }
+        if self.binary {
+            lines.push("binary files differ".to_owned());
+            return lines;
+        }
+
+        let old = self.old.as_deref().unwrap_or("");
+        let new = self.new.as_deref().unwrap_or("");
+        let diff = TextDiff::from_lines(old, new);
+        for group in diff.grouped_ops(CONTEXT_LINES) {
+            let (first, last) = (&group[0], &group[group.len() - 1]);
+            let old_range = first.old_range().start..last.old_range().end;
+            let new_range = first.new_range().start..last.new_range().end;
+            lines.push(
+                style
+                    .clone()
+                    .cyan()
+                    .apply_to(format!(
+                        "@@ -{},{} +{},{} @@",
+                        hunk_start(&old_range),
+                        old_range.len(),
+                        hunk_start(&new_range),
+                        new_range.len()
+                    ))
+                    .to_string(),
+            );
+            for op in &group {
+                for change in diff.iter_changes(op) {
+                    let text = change.value().trim_end_matches('\n');
+                    let line = match change.tag() {
+                        ChangeTag::Equal => format!(" {text}"),
+                        ChangeTag::Delete => {
+                            style.clone().red().apply_to(format!("-{text}")).to_string()
+                        }
+                        ChangeTag::Insert => style
+                            .clone()
+                            .green()
+                            .apply_to(format!("+{text}"))
+                            .to_string(),
+                    };
+                    lines.push(line);
+                }
+            }
+        }
+        lines
+    }
+}
+
+type OnReviewed<'a> = Box<dyn FnMut(&str, bool) -> io::Result<()> + 'a>;
+
+/// The 1-based line a hunk starts at, or the line before it when the hunk is
+/// empty on that side, as in `diff -u`.
+fn hunk_start(range: &Range<usize>) -> usize {
+    if range.is_empty() {
+        range.start
+    } else {
+        range.start + 1
+    }
+}
+
+/// Which screen the viewer is currently showing.
+#[derive(Clone, Copy)]
+enum Screen {
+    List { selected: usize },
+    File { idx: usize, offset: usize },
+}
+
+pub struct DiffViewer<'a> {
+    out: &'a dyn Out,
+    package: PackageStr<'a>,
+    delta: Delta,
+    files: Vec<DiffFile>,
+    reviewed: SortedSet<String>,
+    /// Files whose collapsed state has been toggled from their default.
+    toggled: FastSet<usize>,
+    /// Called to persist each change to whether a file has been reviewed.
+    on_reviewed: OnReviewed<'a>,
+}
+
+impl<'a> DiffViewer<'a> {
+    pub fn new(
+        out: &'a dyn Out,
+        package: PackageStr<'a>,
+        delta: Delta,
+        files: Vec<DiffFile>,
+        reviewed: SortedSet<String>,
+        on_reviewed: impl FnMut(&str, bool) -> io::Result<()> + 'a,
+    ) -> Self {
+        DiffViewer {
+            out,
+            package,
+            delta,
+            files,
+            reviewed,
+            toggled: FastSet::new(),
+            on_reviewed: Box::new(on_reviewed),
+        }
+    }
+
+    /// Write out every file's changes without prompting, for when the user
+    /// isn't at a terminal.
+    pub fn print_all(&self) {
+        self.print_list(None);
+        for file in &self.files {
+            writeln!(self.out);
+            writeln!(
+                self.out,
+                "{}",
+                self.out
+                    .style()
+                    .bold()
+                    .apply_to(format!("diff {}", file.path))
+            );
+            for line in file.render(self.out) {
+                writeln!(self.out, "{line}");
+            }
+        }
+    }
+
+    /// Interactively review the files, reacting to each keypress, until the
+    /// user quits or there's no longer a user to respond.
+    pub fn run(&mut self) -> io::Result<()> {
+        let mut screen = Screen::List { selected: 0 };
+        let mut message = None;
+        loop {
+            self.out.clear_screen()?;
+            let footer = message
+                .take()
+                .unwrap_or_else(|| self.out.style().dim().apply_to("? for help").to_string());
+            let page_len = self.page_len(footer.lines().count());
+            match screen {
+                Screen::List { selected } => self.print_list(Some(selected)),
+                Screen::File { idx, offset } => self.print_page(idx, offset, page_len),
+            }
+            // Leave the cursor at the end of the footer, so that the whole page
+            // stays on the screen.
+            writeln!(self.out);
+            write!(self.out, "{footer}");
+
+            let key = match self.out.read_key() {
+                Ok(key) => key,
+                Err(err) if err.kind() == io::ErrorKind::UnexpectedEof => return Ok(()),
+                Err(err) => return Err(err),
+            };
+            let current = match screen {
+                Screen::List { .. } => None,
+                Screen::File { idx, .. } => Some(idx),
+            };
+            screen = match (screen, key) {
+                (_, Key::Char('q')) => return Ok(()),
+                (_, Key::Char('?' | 'h')) => {
+                    message = Some(HELP.to_owned());
+                    screen
+                }
+
+                // Moving around the file list.
+                (Screen::List { selected }, Key::Char('j') | Key::ArrowDown) => Screen::List {
+                    selected: (selected + 1).min(self.files.len().saturating_sub(1)),
+                },
+                (Screen::List { selected }, Key::Char('k') | Key::ArrowUp) => Screen::List {
+                    selected: selected.saturating_sub(1),
+                },
+                (Screen::List { selected }, Key::Enter | Key::Char(' ')) => {
+                    if self.files.is_empty() {
+                        screen
+                    } else {
+                        self.open(selected)
+                    }
+                }
+
+                // Moving around within a file.
+                (Screen::File { idx, offset }, Key::Char(' ') | Key::Enter | Key::PageDown) => {
+                    if offset + page_len < self.lines(idx).len() {
+                        Screen::File {
+                            idx,
+                            offset: offset + page_len,
+                        }
+                    } else if idx + 1 < self.files.len() {
+                        self.open(idx + 1)
+                    } else {
+                        self.list(idx)
+                    }
+                }
+                (Screen::File { idx, offset }, Key::Char('b') | Key::PageUp) => Screen::File {
+                    idx,
+                    offset: offset.saturating_sub(page_len),
+                },
+                (Screen::File { idx, offset }, Key::Char('j') | Key::ArrowDown) => Screen::File {
+                    idx,
+                    offset: (offset + 1).min(self.lines(idx).len().saturating_sub(page_len)),
+                },
+                (Screen::File { idx, offset }, Key::Char('k') | Key::ArrowUp) => Screen::File {
+                    idx,
+                    offset: offset.saturating_sub(1),
+                },
+                (Screen::File { idx, .. }, Key::Char('l') | Key::Escape) => self.list(idx),
+
+                // Jumping between files.
+                (_, Key::Char('n') | Key::ArrowRight) => match current {
+                    Some(idx) if idx + 1 >= self.files.len() => self.list(idx),
+                    Some(idx) => self.open(idx + 1),
+                    None if self.files.is_empty() => screen,
+                    None => self.open(0),
+                },
+                (_, Key::Char('p') | Key::ArrowLeft) => match current {
+                    Some(0) | None => self.list(0),
+                    Some(idx) => self.open(idx - 1),
+                },
+                (_, Key::Char('u')) => match self.next_unreviewed(current) {
+                    Some(idx) => self.open(idx),
+                    None => {
+                        message = Some("every file has been reviewed".to_owned());
+                        screen
+                    }
+                },
+
+                // Changing the state of the current file, or the selected one
+                // in the list.
+                (Screen::List { selected }, Key::Char('r')) if selected < self.files.len() => {
+                    self.toggle_reviewed(selected)?;
+                    screen
+                }
+                (Screen::File { idx, .. }, Key::Char('r')) => {
+                    self.toggle_reviewed(idx)?;
+                    screen
+                }
+                (Screen::List { selected }, Key::Char('e')) if selected < self.files.len() => {
+                    self.toggle_collapsed(selected);
+                    screen
+                }
+                (Screen::File { idx, .. }, Key::Char('e')) => {
+                    self.toggle_collapsed(idx);
+                    self.open(idx)
+                }
+
+                _ => screen,
+            };
+        }
+    }
+
+    fn list(&self, selected: usize) -> Screen {
+        Screen::List { selected }
+    }
+
+    fn open(&self, idx: usize) -> Screen {
+        Screen::File { idx, offset: 0 }
+    }
+
+    /// Number of diff lines shown on each page, leaving room for the header
+    /// and a footer of the given number of lines.
+    fn page_len(&self, footer_lines: usize) -> usize {
+        let (rows, _) = self.out.size();
+        (rows as usize).saturating_sub(3 + footer_lines).max(1)
+    }
+
+    fn is_collapsed(&self, idx: usize) -> bool {
+        self.files[idx].collapsed_by_default != self.toggled.contains(&idx)
+    }
+
+    fn is_reviewed(&self, idx: usize) -> bool {
+        self.reviewed.contains(&self.files[idx].path)
+    }
+
+    /// The first file after `current` which hasn't been reviewed, wrapping
+    /// around to the start of the list.
+    fn next_unreviewed(&self, current: Option<usize>) -> Option<usize> {
+        let start = current.map_or(0, |idx| idx + 1);
+        (start..self.files.len())
+            .chain(0..start)
+            .find(|&idx| !self.is_reviewed(idx))
+    }
+
+    fn toggle_collapsed(&mut self, idx: usize) {
+        if !self.toggled.remove(&idx) {
+            self.toggled.insert(idx);
+        }
+    }
+
+    fn toggle_reviewed(&mut self, idx: usize) -> io::Result<()> {
+        let path = &self.files[idx].path;
+        let reviewed = !self.reviewed.remove(path);
+        if reviewed {
+            self.reviewed.insert(path.clone());
+        }
+        (self.on_reviewed)(path, reviewed)
+    }
+
+    /// The lines to show for the given file, respecting whether it's collapsed.
+    fn lines(&self, idx: usize) -> Vec<String> {
+        let file = &self.files[idx];
+        if self.is_collapsed(idx) {
+            vec![self
+                .out
+                .style()
+                .dim()
+                .apply_to(format!(
+                    "collapsed ({}), press e to expand",
+                    file.stat_summary()
+                ))
+                .to_string()]
+        } else {
+            file.render(self.out)
+        }
+    }
+
+    /// Print the list of files, with a marker next to the `selected` one.
+    fn print_list(&self, selected: Option<usize>) {
+        let total = self.files.iter().fold(
+            DiffStat {
+                insertions: 0,
+                deletions: 0,
+                files_changed: 0,
+            },
+            |mut total, file| {
+                total.insertions += file.stat.insertions;
+                total.deletions += file.stat.deletions;
+                total.files_changed += file.stat.files_changed;
+                total
+            },
+        );
+        let reviewed = (0..self.files.len())
+            .filter(|&idx| self.is_reviewed(idx))
+            .count();
+        writeln!(
+            self.out,
+            "{} {}: {total} ({reviewed}/{} reviewed)",
+            self.out.style().bold().apply_to(self.package),
+            match &self.delta.from {
+                Some(from) => format!("{from} -> {}", self.delta.to),
+                None => self.delta.to.to_string(),
+            },
+            self.files.len()
+        );
+        writeln!(self.out);
+
+        let width = self.files.iter().map(|f| f.path.len()).max().unwrap_or(0);
+        for (idx, file) in self.files.iter().enumerate() {
+            writeln!(
+                self.out,
+                "{}{:>3} [{}] {:width$}  {}{}",
+                if selected == Some(idx) { ">" } else { " " },
+                idx + 1,
+                if self.is_reviewed(idx) { "x" } else { " " },
+                file.path,
+                file.stat_summary(),
+                if self.is_collapsed(idx) {
+                    " (collapsed)"
+                } else {
+                    ""
+                },
+            );
+        }
+    }
+
+    fn print_page(&self, idx: usize, offset: usize, page_len: usize) {
+        let file = &self.files[idx];
+        let lines = self.lines(idx);
+        let end = (offset + page_len).min(lines.len());
+        writeln!(
+            self.out,
+            "{} [{}/{}] {}  {}  lines {}-{} of {}",
+            if self.is_reviewed(idx) { "[x]" } else { "[ ]" },
+            idx + 1,
+            self.files.len(),
+            self.out.style().bold().apply_to(&file.path),
+            file.stat_summary(),
+            offset + 1,
+            end,
+            lines.len()
+        );
+        writeln!(self.out);
+        for line in &lines[offset..end] {
+            writeln!(self.out, "{line}");
+        }
+    }
+}
@@ -13,7 +13,8 @@ use thiserror::Error;
 
 use crate::{
     format::{
-        CriteriaName, ForeignCriteriaName, ImportName, PackageName, StoreVersion, VetVersion,
+        CriteriaName, FeatureConfigName, ForeignCriteriaName, ImportName, PackageName,
+        StoreVersion, VetVersion,
     },
     network::PayloadEncoding,
     serialization::spanned::Spanned,
@@ -134,6 +135,22 @@ impl From<cargo_metadata::Error> for MetadataAcquireError {
     }
 }
 
+#[derive(Debug, Error, Diagnostic)]
+#[error("feature configuration '{feature_config}' lists packages which aren't workspace members: {}", members.join(", "))]
+#[diagnostic(help("`members` must only name members of the workspaces being vetted"))]
+pub struct UnknownFeatureConfigMembersError {
+    pub feature_config: FeatureConfigName,
+    pub members: Vec<PackageName>,
+}
+
+#[derive(Debug, Error, Diagnostic)]
+#[error("the audit graph for '{package}' refers to an entry which isn't in the store: {origin}")]
+#[diagnostic(help("this is a bug in cargo-vet"))]
+pub struct MissingEdgeOriginError {
+    pub package: PackageName,
+    pub origin: String,
+}
+
 ///////////////////////////////////////////////////////////
 // AuditAsErrors
 ///////////////////////////////////////////////////////////
@@ -452,11 +469,41 @@ pub enum StoreValidateError {
     #[diagnostic(transparent)]
     #[error(transparent)]
     BadWildcardEndDate(BadWildcardEndDateError),
+    #[diagnostic(transparent)]
+    #[error(transparent)]
+    BadExemptionEndDate(BadExemptionEndDateError),
+    #[diagnostic(transparent)]
+    #[error(transparent)]
+    CriteriaDefinition(CriteriaDefinitionError),
+    #[diagnostic(transparent)]
+    #[error(transparent)]
+    UnknownDisputeImport(UnknownDisputeImportError),
+    #[error("diff-weights must be non-negative numbers, found {weight}")]
+    BadDiffWeight { weight: f64 },
+    #[error("the dispute of a violation of {package} has no notes")]
+    #[diagnostic(help("disputes must explain why the violation is being disputed"))]
+    EmptyDisputeNotes { package: PackageName },
     #[error("imports.lock is out-of-date with respect to configuration")]
     #[diagnostic(help("run `cargo vet` without --locked to update imports"))]
     ImportsLockOutdated,
 }
 
+#[derive(Debug, Error, Diagnostic)]
+#[non_exhaustive]
+pub enum CriteriaDefinitionError {
+    #[error("cannot specify multiple criteria with the name '{0}'")]
+    #[diagnostic(help("custom criteria cannot shadow safe-to-run or safe-to-deploy"))]
+    Duplicate(CriteriaName),
+    #[error("criteria '{criteria}' implies unknown criteria '{implied}'")]
+    UnknownImplied {
+        criteria: CriteriaName,
+        implied: CriteriaName,
+    },
+    #[error("criteria '{0}' implies itself")]
+    #[diagnostic(help("the criteria's `implies` must not form a cycle"))]
+    ImpliesItself(CriteriaName),
+}
+
 #[derive(Debug, Error, Diagnostic)]
 #[error("'{invalid}' is not a valid criteria name")]
 #[diagnostic(help("the possible criteria are {:?}", valid_names))]
@@ -488,6 +535,29 @@ pub struct BadWildcardEndDateError {
     pub max: chrono::NaiveDate,
 }
 
+#[derive(Debug, Error, Diagnostic)]
+#[error("'{date}' is more than a year in the future")]
+#[diagnostic(help("exemptions must end at most a year in the future ({max})"))]
+pub struct BadExemptionEndDateError {
+    #[source_code]
+    pub source_code: SourceFile,
+    #[label]
+    pub span: SourceSpan,
+    pub date: chrono::NaiveDate,
+    pub max: chrono::NaiveDate,
+}
+
+#[derive(Debug, Error, Diagnostic)]
+#[error("'{import}' is not a configured import")]
+#[diagnostic(help("disputes must name the import which published the violation"))]
+pub struct UnknownDisputeImportError {
+    #[source_code]
+    pub source_code: SourceFile,
+    #[label]
+    pub span: SourceSpan,
+    pub import: ImportName,
+}
+
 //////////////////////////////////////////////////////////
 // CacheErrors
 /////////////////////////////////////////////////////////
@@ -629,6 +699,12 @@ pub enum FetchError {
         #[source]
         error: url::ParseError,
     },
+    #[error("The registry at {index} has no download URL for {package}:{version}")]
+    NoDownloadUrl {
+        package: PackageName,
+        version: semver::Version,
+        index: Box<reqwest::Url>,
+    },
     #[error("Running as --frozen but needed to fetch {package}:{version}")]
     Frozen {
         package: PackageName,
@@ -661,6 +737,9 @@ pub enum FetchError {
     #[error(transparent)]
     #[diagnostic(transparent)]
     Download(#[from] DownloadError),
+    #[error(transparent)]
+    #[diagnostic(transparent)]
+    RegistryInfo(#[from] CrateInfoError),
 }
 
 #[derive(Debug, Error, Diagnostic)]
@@ -760,6 +839,87 @@ pub enum FetchAuditError {
     #[diagnostic(transparent)]
     #[error(transparent)]
     Json(#[from] LoadJsonError),
+    #[diagnostic(transparent)]
+    #[error(transparent)]
+    Source(#[from] ImportSourceError),
+    #[error("couldn't verify the signature of {import_name} @ {import_url}")]
+    #[diagnostic(help(
+        "the imported audits.toml must be signed with one of the public keys in config.toml"
+    ))]
+    BadSignature {
+        import_name: ImportName,
+        import_url: String,
+        #[source]
+        error: SignatureError,
+    },
+    #[error("{import_name} @ {import_url} doesn't match its pinned sha256")]
+    #[diagnostic(help(
+        "expected {expected}, found {actual}; run `cargo vet import --update {import_name}` to review and accept the changes"
+    ))]
+    PinMismatch {
+        import_name: ImportName,
+        import_url: String,
+        expected: String,
+        actual: String,
+    },
+    #[error("{import_name} has {pins} {kind} pins, but {urls} URLs")]
+    #[diagnostic(help("specify one {kind} for each URL, in the same order"))]
+    PinCount {
+        import_name: ImportName,
+        kind: &'static str,
+        pins: usize,
+        urls: usize,
+    },
+    #[error("{import_name} @ {import_url} is pinned to a rev, but isn't a git source")]
+    #[diagnostic(help("only `git+` URLs can be pinned to a rev; use a sha256 pin instead"))]
+    RevPinNotGit {
+        import_name: ImportName,
+        import_url: String,
+    },
+}
+
+#[derive(Debug, Error, Diagnostic)]
+#[non_exhaustive]
+pub enum ImportSourceError {
+    #[diagnostic(transparent)]
+    #[error(transparent)]
+    Download(#[from] DownloadError),
+    #[error("failed to fetch {rev} from the git repository {repo}")]
+    GitFetch {
+        repo: String,
+        rev: String,
+        #[source]
+        error: CommandError,
+    },
+    #[error("failed to read {path} @ {rev} from the git repository {repo}")]
+    Git {
+        repo: String,
+        rev: String,
+        path: String,
+        #[source]
+        error: CommandError,
+    },
+    #[error("invalid revision '{rev}' for the git repository {repo}")]
+    InvalidRev { repo: String, rev: String },
+    #[error("git imports are unavailable without a cache")]
+    NoCache,
+}
+
+#[derive(Debug, Error, Diagnostic)]
+#[non_exhaustive]
+pub enum SignatureError {
+    #[error("failed to fetch signature")]
+    Missing(#[source] Box<ImportSourceError>),
+    #[error("invalid public key '{public_key}'")]
+    InvalidPublicKey {
+        public_key: String,
+        #[source]
+        error: minisign_verify::Error,
+    },
+    #[error("malformed signature")]
+    Malformed(#[source] minisign_verify::Error),
+    #[error("signature does not match any trusted public key")]
+    Untrusted,
 }
 
 #[derive(Debug, Error, Diagnostic)]
@@ -787,6 +947,9 @@ pub enum CrateInfoError {
     Json(#[from] LoadJsonError),
     #[error("Cannot fetch crate information, '{name}' does not exist.")]
     DoesNotExist { name: PackageName },
+    #[error("Cannot locate the registry index for '{name}'")]
+    #[help("Only packages from sparse registries can be fetched")]
+    UnknownRegistryIndex { name: PackageName },
 }
 
 //////////////////////////////////////////////////////////
@@ -841,6 +1004,12 @@ pub enum DownloadError {
         #[source]
         error: std::io::Error,
     },
+    #[error("failed to read {}", target.display())]
+    FailedToReadFile {
+        target: std::path::PathBuf,
+        #[source]
+        error: std::io::Error,
+    },
     #[error("download wasn't valid utf8: {url}")]
     InvalidText {
         url: Box<reqwest::Url>,
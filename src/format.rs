@@ -4,6 +4,7 @@ use crate::cli::FetchMode;
 use crate::errors::{StoreVersionParseError, VersionParseError};
 use crate::resolver::{DiffRecommendation, ViolationConflict};
 use crate::serialization::{spanned::Spanned, Tidyable};
+use crate::string_format::FormatShortList;
 use crate::{flock::Filesystem, serialization};
 use core::{cmp, fmt};
 use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
@@ -27,6 +28,8 @@ pub type PackageName = String;
 pub type PackageStr<'a> = &'a str;
 pub type ImportName = String;
 pub type ImportStr<'a> = &'a str;
+pub type RegistryName = String;
+pub type FeatureConfigName = String;
 pub type CratesUserId = u64;
 
 // newtype VersionReq so that we can implement PartialOrd on it.
@@ -303,6 +306,8 @@ pub struct AuditEntry {
     pub kind: AuditKind,
     pub importable: bool,
     pub notes: Option<String>,
+    /// The date the audit was recorded, if known.
+    pub date: Option<chrono::NaiveDate>,
     /// Chain of sources this audit was aggregated from, most recent last.
     pub aggregated_from: Vec<Spanned<String>>,
     /// A non-serialized member which indicates whether this audit is a "fresh"
@@ -313,6 +318,12 @@ pub struct AuditEntry {
     /// import handling code.
     #[serde(skip)]
     pub is_fresh_import: bool,
+    /// A non-serialized member which indicates whether this is a full audit
+    /// which is too old to satisfy the criteria in the `stale-audits` policy.
+    ///
+    /// This is set by [`crate::storage::Store::mark_stale_audits`].
+    #[serde(skip)]
+    pub is_stale: bool,
 }
 
 impl AuditEntry {
@@ -383,6 +394,9 @@ impl AuditEntry {
             });
         }
 
+        // The collapsed audit is only as recent as the oldest audit it covers.
+        new_entry.date = self.date.zip(other.date).map(|(a, b)| a.min(b));
+
         // Rederive `importable` based on the versions.
         new_entry.importable = new_entry.kind.default_importable();
 
@@ -584,16 +598,82 @@ pub struct ConfigFile {
     #[serde(skip_serializing_if = "is_default_criteria")]
     pub default_criteria: CriteriaName,
 
+    /// What to do when imported audits or crates.io publisher information
+    /// can't be fetched. If unspecified, this defaults to "error".
+    #[serde(rename = "on-fetch-failure")]
+    #[serde(default)]
+    #[serde(skip_serializing_if = "FetchFailurePolicy::is_default")]
+    pub on_fetch_failure: FetchFailurePolicy,
+
+    /// How much changed lines in each file count towards the estimated cost
+    /// of an audit in `cargo vet suggest`.
+    #[serde(rename = "diff-weights")]
+    #[serde(default)]
+    #[serde(skip_serializing_if = "DiffWeights::is_default")]
+    pub diff_weights: DiffWeights,
+
+    /// When old full audits should stop satisfying criteria.
+    #[serde(rename = "stale-audits")]
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub stale_audits: Option<StaleAuditPolicy>,
+
     /// Remote audits.toml's that we trust and want to import.
     #[serde(skip_serializing_if = "SortedMap::is_empty")]
     #[serde(default)]
     pub imports: SortedMap<ImportName, RemoteImport>,
 
+    /// Registries other than crates.io whose packages should be audited as
+    /// third-party code.
+    #[serde(skip_serializing_if = "SortedMap::is_empty")]
+    #[serde(default)]
+    pub registries: SortedMap<RegistryName, AlternateRegistry>,
+
+    /// Other Cargo workspaces which share this store, as paths relative to
+    /// the store to their Cargo.toml or the directory containing it. Their
+    /// dependency graphs are merged with the current workspace's.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    #[serde(default)]
+    pub workspaces: Vec<String>,
+
+    /// Target triples to pass to `cargo metadata --filter-platform`, so that
+    /// dependencies only used by other platforms aren't vetted. Overridden
+    /// by `--filter-platform` on the command line.
+    #[serde(rename = "filter-platforms")]
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    #[serde(default)]
+    pub filter_platforms: Vec<String>,
+
+    /// Criteria required of dependencies which are only used on a particular
+    /// platform, keyed by the target triple or `cfg(...)` expression their
+    /// dependents specify them under.
+    ///
+    /// Any dependency edge without a matching platform defaults to the
+    /// criteria of the dependent.
+    #[serde(rename = "platform-criteria")]
+    #[serde(skip_serializing_if = "CriteriaMap::is_empty")]
+    #[serde(with = "serialization::criteria_map")]
+    #[serde(default)]
+    pub platform_criteria: CriteriaMap,
+
+    /// Named sets of features to build the dependency graph with, instead of
+    /// a single graph with the features given on the command line.
+    #[serde(rename = "feature-configs")]
+    #[serde(skip_serializing_if = "SortedMap::is_empty")]
+    #[serde(default)]
+    pub feature_configs: SortedMap<FeatureConfigName, FeatureConfig>,
+
     /// A table of policies for crates.
     #[serde(skip_serializing_if = "Policy::is_empty")]
     #[serde(default)]
     pub policy: Policy,
 
+    /// Violations published by imported peers which we disagree with, and
+    /// which shouldn't be enforced on our supply-chain.
+    #[serde(skip_serializing_if = "SortedMap::is_empty")]
+    #[serde(default)]
+    pub disputes: SortedMap<PackageName, Vec<ViolationDispute>>,
+
     /// All of the "foreign" dependencies that we rely on but haven't audited yet.
     /// Foreign dependencies are just "things on crates.io", everything else
     /// (paths, git, etc) is assumed to be "under your control" and therefore implicitly trusted.
@@ -620,6 +700,179 @@ fn is_default_criteria(val: &CriteriaName) -> bool {
     val == DEFAULT_CRITERIA
 }
 
+/// How to handle a failure to fetch remote information which is recorded in
+/// imports.lock.
+#[derive(Debug, serde::Serialize, serde::Deserialize, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub enum FetchFailurePolicy {
+    /// Fail with an error.
+    #[default]
+    Error,
+    /// Warn, and use the information already recorded in imports.lock, if any.
+    UseLock,
+}
+
+impl FetchFailurePolicy {
+    fn is_default(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+/// A policy for re-reviewing crates whose full audits have grown old.
+///
+/// A full audit recorded more than `max-age-years` ago no longer satisfies
+/// `criteria` if the newest version of the crate in use isn't
+/// semver-compatible with the audited version, unless a delta audit from the
+/// audited version has been recorded since.
+#[derive(Debug, serde::Serialize, serde::Deserialize, Clone, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+#[serde(deny_unknown_fields)]
+pub struct StaleAuditPolicy {
+    pub max_age_years: u32,
+    #[serde(with = "serialization::string_or_vec")]
+    pub criteria: Vec<Spanned<CriteriaName>>,
+}
+
+/// Weights applied to the lines changed by a diff when estimating the cost of
+/// auditing it. Every changed line counts as a single line by default.
+#[derive(Debug, serde::Serialize, serde::Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "kebab-case")]
+pub struct DiffWeights {
+    /// Multiplier for added lines in Rust files which use `unsafe`.
+    #[serde(rename = "unsafe")]
+    #[serde(default = "default_weight")]
+    #[serde(skip_serializing_if = "is_default_weight")]
+    pub unsafe_lines: f64,
+    /// Multiplier for every line changed in a proc-macro crate.
+    #[serde(default = "default_weight")]
+    #[serde(skip_serializing_if = "is_default_weight")]
+    pub proc_macro: f64,
+    /// Multipliers for the lines changed in matching files. The first rule
+    /// which matches a file is used.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub files: Vec<DiffWeightRule>,
+}
+
+impl Default for DiffWeights {
+    fn default() -> Self {
+        DiffWeights {
+            unsafe_lines: default_weight(),
+            proc_macro: default_weight(),
+            files: Vec::new(),
+        }
+    }
+}
+
+fn default_weight() -> f64 {
+    1.0
+}
+fn is_default_weight(val: &f64) -> bool {
+    *val == default_weight()
+}
+
+impl DiffWeights {
+    fn is_default(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// The weight for lines changed in the file at the given path, relative to
+    /// the package root.
+    pub fn file_weight(&self, path: &str) -> f64 {
+        self.files
+            .iter()
+            .find(|rule| rule.matches(path))
+            .map_or(default_weight(), |rule| rule.weight)
+    }
+
+    /// Estimate the cost of auditing the given diff, in weighted lines.
+    pub fn weigh(&self, diff: &PackageDiff) -> u64 {
+        let package_weight = if diff.proc_macro {
+            self.proc_macro
+        } else {
+            default_weight()
+        };
+        let total: f64 = diff
+            .files
+            .iter()
+            .map(|file| {
+                let unsafe_lines = file.unsafe_lines.min(file.insertions) as f64;
+                let other_lines = file.count() as f64 - unsafe_lines;
+                (other_lines + unsafe_lines * self.unsafe_lines)
+                    * self.file_weight(&file.path)
+                    * package_weight
+            })
+            .sum();
+        total.round() as u64
+    }
+}
+
+/// A rule matching files whose changed lines should be weighted differently.
+/// A file matches if it matches any of the paths or extensions.
+#[derive(Debug, serde::Serialize, serde::Deserialize, Clone, PartialEq)]
+pub struct DiffWeightRule {
+    /// Glob patterns for paths relative to the package root, such as
+    /// `tests/**` or `src/*_tables.rs`.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub paths: Vec<String>,
+    /// File extensions, without the leading `.`.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub extensions: Vec<String>,
+    /// Multiplier for changed lines in matching files. A weight of 0 excludes
+    /// them entirely.
+    pub weight: f64,
+}
+
+impl DiffWeightRule {
+    fn matches(&self, path: &str) -> bool {
+        let extension = path
+            .rsplit('/')
+            .next()
+            .and_then(|name| name.rsplit_once('.'))
+            .map(|(_, ext)| ext);
+        self.extensions
+            .iter()
+            .any(|ext| Some(&ext[..]) == extension)
+            || self.paths.iter().any(|pattern| glob_matches(pattern, path))
+    }
+}
+
+/// Match a `/`-separated path against a glob pattern, where `*` matches
+/// within a single path component, `?` matches a single character, and `**`
+/// matches any number of components.
+fn glob_matches(pattern: &str, path: &str) -> bool {
+    fn component_matches(pattern: &[u8], name: &[u8]) -> bool {
+        match (pattern.split_first(), name.split_first()) {
+            (None, None) => true,
+            (Some((b'*', rest)), _) => {
+                component_matches(rest, name)
+                    || (!name.is_empty() && component_matches(pattern, &name[1..]))
+            }
+            (Some((b'?', rest)), Some((_, name_rest))) => component_matches(rest, name_rest),
+            (Some((p, rest)), Some((n, name_rest))) => p == n && component_matches(rest, name_rest),
+            _ => false,
+        }
+    }
+    fn components_match(pattern: &[&str], path: &[&str]) -> bool {
+        match (pattern.split_first(), path.split_first()) {
+            (None, None) => true,
+            (Some((&"**", rest)), _) => {
+                components_match(rest, path)
+                    || (!path.is_empty() && components_match(pattern, &path[1..]))
+            }
+            (Some((p, rest)), Some((n, path_rest))) => {
+                component_matches(p.as_bytes(), n.as_bytes()) && components_match(rest, path_rest)
+            }
+            _ => false,
+        }
+    }
+    let pattern: Vec<&str> = pattern.split('/').collect();
+    let path: Vec<&str> = path.split('/').collect();
+    components_match(&pattern, &path)
+}
+
 /// The table of crate policies.
 #[derive(Debug, serde::Serialize, serde::Deserialize, Clone, Default)]
 #[serde(try_from = "serialization::policy::AllPolicies")]
@@ -704,7 +957,7 @@ impl Policy {
     }
 
     /// Return an iterator over defined policies.
-    pub fn iter(&self) -> PolicyIter {
+    pub fn iter(&self) -> PolicyIter<'_> {
         PolicyIter {
             iter: self.package.iter(),
             versioned: None,
@@ -862,6 +1115,20 @@ pub struct RemoteImport {
     /// URL(s) of the foreign audits.toml
     #[serde(with = "serialization::string_or_vec")]
     pub url: Vec<String>,
+    /// sha256 digests of the foreign audits.toml file(s), one for each URL.
+    /// While an import is pinned, upstream changes are ignored until the pin is
+    /// moved forward with `cargo vet import --update`.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    #[serde(with = "serialization::string_or_vec")]
+    #[serde(default)]
+    pub sha256: Vec<String>,
+    /// git commit ids to fetch the foreign audits.toml file(s) from, one for
+    /// each URL, overriding the `rev` in the URL. Only git sources can be
+    /// pinned to a rev.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    #[serde(with = "serialization::string_or_vec")]
+    #[serde(default)]
+    pub rev: Vec<String>,
     /// A list of crates for which no audits or violations should be imported.
     #[serde(skip_serializing_if = "Vec::is_empty")]
     #[serde(default)]
@@ -872,6 +1139,151 @@ pub struct RemoteImport {
     #[serde(with = "serialization::criteria_map")]
     #[serde(default)]
     pub criteria_map: CriteriaMap,
+    /// minisign public keys trusted to sign the foreign audits.toml. When
+    /// specified, a detached signature must be published at `{url}.minisig`.
+    #[serde(rename = "public-key")]
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    #[serde(with = "serialization::string_or_vec")]
+    #[serde(default)]
+    pub public_key: Vec<String>,
+    /// The strongest local criteria that entries from this import may grant.
+    /// Imported entries only certify the criteria they share with these (and
+    /// the criteria these imply). When empty, all criteria may be granted.
+    #[serde(rename = "max-criteria")]
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    #[serde(with = "serialization::string_or_vec")]
+    #[serde(default)]
+    pub max_criteria: Vec<Spanned<CriteriaName>>,
+    /// The auditors whose audits should be imported, matching either the full
+    /// `who` string or the email address within it. When empty, audits by
+    /// anyone are imported.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    #[serde(with = "serialization::string_or_vec")]
+    #[serde(default)]
+    pub auditors: Vec<String>,
+    /// Whether wildcard audits should be left out of the import.
+    #[serde(rename = "exclude-wildcard-audits")]
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    #[serde(default)]
+    pub exclude_wildcard_audits: bool,
+    /// Whether trusted entries should be left out of the import.
+    #[serde(rename = "exclude-trusted")]
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    #[serde(default)]
+    pub exclude_trusted: bool,
+}
+
+impl RemoteImport {
+    /// Whether an audit entry from this import should be kept, based on the
+    /// `auditors` allowlist.
+    pub fn allows_audit_by(&self, who: &[Spanned<String>]) -> bool {
+        if self.auditors.is_empty() {
+            return true;
+        }
+        who.iter().any(|who| {
+            let email = who
+                .rsplit_once('<')
+                .and_then(|(_, rest)| rest.strip_suffix('>'));
+            self.auditors
+                .iter()
+                .any(|auditor| **who == *auditor || email == Some(auditor.as_str()))
+        })
+    }
+
+    /// Whether this import sets any limits on the entries which are imported
+    /// from it, beyond excluded crates.
+    pub fn filters_entries(&self) -> bool {
+        !self.auditors.is_empty() || self.exclude_wildcard_audits || self.exclude_trusted
+    }
+}
+
+/// A registry other than crates.io whose packages are treated as third-party.
+///
+/// Packages from these registries are identified by their qualified name
+/// (`{registry}:{package}`), so they can't be confused with crates.io packages
+/// which happen to share the same name.
+#[derive(Debug, serde::Serialize, serde::Deserialize, Clone)]
+pub struct AlternateRegistry {
+    /// The index URL of the registry, as it is written in `Cargo.lock`
+    /// (e.g. `sparse+https://my-registry.example.com/index/`).
+    pub index: String,
+}
+
+impl AlternateRegistry {
+    /// Whether a package's `source` in cargo metadata is this registry.
+    pub fn matches_source(&self, source: &str) -> bool {
+        fn normalize(url: &str) -> &str {
+            url.strip_prefix("registry+")
+                .unwrap_or(url)
+                .trim_end_matches('/')
+        }
+        normalize(&self.index) == normalize(source)
+    }
+}
+
+/// A named set of features to build the workspace with.
+///
+/// The dependency graph of each configuration is resolved separately, and a
+/// package must meet the strongest criteria required of it by any of them.
+#[derive(Debug, serde::Serialize, serde::Deserialize, Clone)]
+pub struct FeatureConfig {
+    /// The workspace members built in this configuration. If empty, all of
+    /// them are.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub members: Vec<PackageName>,
+    /// Features to activate, which may be qualified by the workspace member
+    /// they belong to (`member/feature`).
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub features: Vec<String>,
+    /// Whether to activate all features.
+    #[serde(rename = "all-features")]
+    #[serde(default)]
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub all_features: bool,
+    /// Whether to activate the `default` feature (defaults true).
+    #[serde(rename = "default-features")]
+    #[serde(default = "get_default_features")]
+    #[serde(skip_serializing_if = "is_default_features")]
+    pub default_features: bool,
+    /// Criteria required of the workspace members built in this
+    /// configuration, in place of the default criteria for roots.
+    #[serde(default)]
+    #[serde(with = "serialization::string_or_vec_or_none")]
+    pub criteria: Option<Vec<Spanned<CriteriaName>>>,
+    /// Freeform notes
+    pub notes: Option<String>,
+}
+
+impl FeatureConfig {
+    /// Whether the workspace member `name` is built in this configuration.
+    pub fn includes(&self, name: PackageStr<'_>) -> bool {
+        self.members.is_empty() || self.members.iter().any(|member| member == name)
+    }
+}
+
+fn get_default_features() -> bool {
+    true
+}
+fn is_default_features(val: &bool) -> bool {
+    *val
+}
+
+/// The separator between the registry and package name in the qualified name
+/// of a package from an alternate registry. This can never appear in a crate
+/// name.
+pub const REGISTRY_SEPARATOR: char = ':';
+
+/// The name used by vet for a package from an alternate registry.
+pub fn qualified_package_name(registry: &str, package: PackageStr<'_>) -> PackageName {
+    format!("{registry}{REGISTRY_SEPARATOR}{package}")
+}
+
+/// Split a qualified package name into the registry and the name of the
+/// package within that registry. Returns `None` for crates.io packages.
+pub fn split_qualified_package_name(package: PackageStr<'_>) -> Option<(&str, &str)> {
+    package.split_once(REGISTRY_SEPARATOR)
 }
 
 /// Translations of foreign criteria to local criteria.
@@ -899,8 +1311,53 @@ pub struct ExemptedDependency {
     #[serde(default = "get_default_exemptions_suggest")]
     #[serde(skip_serializing_if = "is_default_exemptions_suggest")]
     pub suggest: bool,
+    /// The date after which this exemption no longer applies. Exemptions
+    /// without an end date never expire.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub end: Option<Spanned<chrono::NaiveDate>>,
     /// Freeform notes, put whatever you want here. Just more stable/reliable than comments.
     pub notes: Option<String>,
+    /// A non-serialized member which indicates whether the exemption's end
+    /// date has passed, so that it no longer applies.
+    ///
+    /// This is set by [`crate::storage::Store::mark_expired_exemptions`].
+    #[serde(skip)]
+    pub is_expired: bool,
+}
+
+impl ExemptedDependency {
+    /// Whether the exemption has an end date before `date`.
+    pub fn expires_before(&self, date: chrono::NaiveDate) -> bool {
+        self.end.as_ref().is_some_and(|end| **end < date)
+    }
+}
+
+/// A dispute of a violation published by an imported peer. The violation
+/// entry from that import for the crate with the exact same version
+/// requirement is ignored.
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ViolationDispute {
+    /// The import which published the violation.
+    pub import: Spanned<ImportName>,
+    /// The version requirement of the disputed violation.
+    pub violation: VersionReq,
+    /// Why the violation is disputed. This is required, so that the reasoning
+    /// can be revisited later.
+    pub notes: String,
+}
+
+impl ViolationDispute {
+    /// Whether this dispute applies to a violation entry from `import`.
+    pub fn matches(&self, import: &str, entry: &AuditEntry) -> bool {
+        match &entry.kind {
+            AuditKind::Violation { violation } => {
+                *self.import == import && *violation == self.violation
+            }
+            _ => false,
+        }
+    }
 }
 
 static DEFAULT_EXEMPTIONS_SUGGEST: bool = true;
@@ -1103,9 +1560,9 @@ pub struct UnpublishedEntry {
 #[derive(Serialize, Deserialize, Clone)]
 #[serde(tag = "version")]
 pub enum DiffCache {
-    #[serde(rename = "2")]
-    V2 {
-        diffs: SortedMap<PackageName, SortedMap<Delta, DiffStat>>,
+    #[serde(rename = "4")]
+    V4 {
+        diffs: SortedMap<PackageName, SortedMap<Delta, PackageDiff>>,
     },
 }
 
@@ -1115,7 +1572,7 @@ impl Tidyable for DiffCache {
 
 impl Default for DiffCache {
     fn default() -> Self {
-        DiffCache::V2 {
+        DiffCache::V4 {
             diffs: SortedMap::new(),
         }
     }
@@ -1134,6 +1591,106 @@ impl DiffStat {
     }
 }
 
+/// The changes between two versions of a package, with enough detail to weight
+/// the cost of auditing them.
+#[derive(Serialize, Deserialize, Debug, Clone, Eq, PartialEq)]
+pub struct PackageDiff {
+    pub diffstat: DiffStat,
+    pub files: Vec<FileDiffStat>,
+    /// Whether the newer version is a proc-macro crate.
+    #[serde(default)]
+    pub proc_macro: bool,
+    /// Patterns worth reviewing first which were introduced by the changes.
+    #[serde(default)]
+    pub risks: Vec<RiskIndicator>,
+}
+
+/// A kind of change which reviewers may want to look at first.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[serde(rename_all = "kebab-case")]
+pub enum RiskKind {
+    /// Added lines using `unsafe`.
+    Unsafe,
+    /// Changes to the package's build script.
+    BuildScript,
+    /// Added lines using `std::process::Command`.
+    ProcessSpawn,
+    /// Added lines using `std::net`.
+    Network,
+    /// Added lines declaring `extern "C"` or `extern "system"` items.
+    Ffi,
+    /// Added lines using `include_bytes!`.
+    IncludeBytes,
+    /// Added or changed binary files.
+    Binary,
+}
+
+impl RiskKind {
+    fn description(self) -> &'static str {
+        match self {
+            RiskKind::Unsafe => "unsafe code",
+            RiskKind::BuildScript => "build script",
+            RiskKind::ProcessSpawn => "process spawning",
+            RiskKind::Network => "networking",
+            RiskKind::Ffi => "FFI",
+            RiskKind::IncludeBytes => "include_bytes!",
+            RiskKind::Binary => "binary files",
+        }
+    }
+}
+
+impl fmt::Display for RiskKind {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.description())
+    }
+}
+
+/// The occurrences of one kind of risky change within a diff.
+#[derive(Serialize, Deserialize, Debug, Clone, Eq, PartialEq)]
+pub struct RiskIndicator {
+    pub kind: RiskKind,
+    /// The number of lines, or files for binary files.
+    pub occurrences: u64,
+    /// The files the occurrences are in, relative to the package root.
+    pub files: Vec<String>,
+}
+
+impl fmt::Display for RiskIndicator {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let unit = match self.kind {
+            RiskKind::Binary => "file",
+            RiskKind::BuildScript => "changed line",
+            _ => "new line",
+        };
+        write!(
+            f,
+            "{}: {} {unit}{} in {}",
+            self.kind,
+            self.occurrences,
+            if self.occurrences == 1 { "" } else { "s" },
+            FormatShortList::new(self.files.iter().map(|f| &f[..]).collect())
+        )
+    }
+}
+
+/// The lines changed in a single file of a [`PackageDiff`].
+#[derive(Serialize, Deserialize, Debug, Clone, Eq, PartialEq)]
+pub struct FileDiffStat {
+    /// The path relative to the package root, using `/` as a separator.
+    pub path: String,
+    pub insertions: u64,
+    pub deletions: u64,
+    /// The number of added lines which use `unsafe`.
+    #[serde(default)]
+    pub unsafe_lines: u64,
+}
+
+impl FileDiffStat {
+    pub fn count(&self) -> u64 {
+        self.insertions + self.deletions
+    }
+}
+
 impl fmt::Display for DiffStat {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         write!(f, "{} files changed", self.files_changed)?;
@@ -1171,7 +1728,7 @@ pub enum FetchCommand {
 }
 
 impl FetchCommand {
-    pub fn package(&self) -> PackageStr {
+    pub fn package(&self) -> PackageStr<'_> {
         match self {
             FetchCommand::Inspect { package, .. } => package,
             FetchCommand::Diff { package, .. } => package,
@@ -1184,6 +1741,10 @@ pub struct CommandHistory {
     #[serde(flatten)]
     pub last_fetch: Option<FetchCommand>,
     pub last_fetch_mode: Option<FetchMode>,
+    /// Files marked as reviewed in the built-in diff viewer, so that reviews
+    /// can be resumed.
+    #[serde(default, skip_serializing_if = "SortedMap::is_empty")]
+    pub reviewed_files: SortedMap<PackageName, SortedMap<Delta, SortedSet<String>>>,
 }
 
 ////////////////////////////////////////////////////////////////////////////////////
@@ -1300,6 +1861,34 @@ pub struct RegistryEntry {
     pub url: Vec<String>,
 }
 
+////////////////////////////////////////////////////////////////////////////////////
+//                                                                                //
+//                                                                                //
+//                                                                                //
+//                                  Cargo.lock                                    //
+//                                                                                //
+//                                                                                //
+//                                                                                //
+////////////////////////////////////////////////////////////////////////////////////
+
+// NOTE: This is the subset of a Cargo.lock needed by `check --baseline` and
+// `--metadata-file`.
+
+#[derive(Deserialize, Debug, Clone, Default)]
+pub struct CargoLockFile {
+    #[serde(default)]
+    pub package: Vec<CargoLockPackage>,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct CargoLockPackage {
+    pub name: PackageName,
+    pub version: semver::Version,
+    /// Where the package comes from, or `None` for path dependencies.
+    #[serde(default)]
+    pub source: Option<String>,
+}
+
 ////////////////////////////////////////////////////////////////////////////////////
 //                                                                                //
 //                                                                                //
@@ -1322,6 +1911,16 @@ pub struct RegistryEntry {
 pub struct JsonReport {
     #[serde(flatten)]
     pub conclusion: JsonReportConclusion,
+    /// Violations published by peers which aren't enforced because they've
+    /// been disputed, for each package in the graph.
+    #[serde(skip_serializing_if = "SortedMap::is_empty")]
+    #[serde(default)]
+    pub disputed_violations: SortedMap<PackageName, Vec<DisputedViolation>>,
+    /// With `check --baseline`, the packages which were ignored because they
+    /// already failed to vet in the baseline's `Cargo.lock`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default)]
+    pub baseline_failures: Option<Vec<JsonVetFailure>>,
 }
 
 /// The conclusion of running `check` or `suggest`
@@ -1377,6 +1976,8 @@ pub struct JsonSuggest {
     pub suggest_by_criteria: SortedMap<String, Vec<JsonSuggestItem>>,
     /// The total number of lines you would need to review to resolve this
     pub total_lines: u64,
+    /// The total number of lines, weighted by the store's `diff-weights`
+    pub total_weighted_lines: u64,
 }
 
 /// This specific package needed the following criteria but doesn't have them!
@@ -1403,6 +2004,8 @@ pub struct JsonSuggestItem {
     /// The diff (or full version) we recommend auditing
     // FIXME(SCHEMA): we probably shouldn't expose this internal type
     pub suggested_diff: DiffRecommendation,
+    /// Patterns worth reviewing first which the suggested diff introduces
+    pub risk_indicators: Vec<RiskIndicator>,
 }
 
 /// A string of the form "package:version"
@@ -1417,6 +2020,541 @@ pub struct JsonPackage {
     pub version: VetVersion,
 }
 
+/// cargo-vet's `--output-format=json` for `explain`, describing how each
+/// matching version of a package was (or wasn't) vetted.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JsonExplain {
+    pub packages: Vec<JsonExplainPackage>,
+}
+
+/// How a single version of a package was vetted
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JsonExplainPackage {
+    /// The name of the package
+    pub name: PackageName,
+    /// The version of the package
+    pub version: VetVersion,
+    /// Whether the package is third-party. First-party packages aren't vetted.
+    pub third_party: bool,
+    /// Whether the package has violation conflicts, in which case it wasn't
+    /// searched for audits.
+    pub violation_conflict: bool,
+    /// The criteria the package is required to satisfy
+    pub required_criteria: Vec<CriteriaName>,
+    /// The search results for the strongest criteria the package satisfies,
+    /// and for the strongest required criteria it's missing
+    pub criteria: Vec<JsonExplainCriteria>,
+    /// Violations published by peers for this version which aren't enforced
+    /// because they've been disputed
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    #[serde(default)]
+    pub disputed_violations: Vec<DisputedViolation>,
+}
+
+/// The result of searching for a path of audits for one criteria
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JsonExplainCriteria {
+    /// The criteria which was searched for
+    pub criteria: CriteriaName,
+    /// Whether the package is required to satisfy this criteria
+    pub required: bool,
+    #[serde(flatten)]
+    pub result: JsonExplainResult,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "result")]
+pub enum JsonExplainResult {
+    /// The criteria is satisfied by this path of entries, starting from the
+    /// root (an empty version) and ending at the package's version.
+    #[serde(rename = "vetted")]
+    Vetted { path: Vec<JsonExplainEdge> },
+    /// No path exists. These are the versions which could be reached from the
+    /// root and from the package's version, where `null` is the root.
+    #[serde(rename = "unvetted")]
+    Unvetted {
+        reachable_from_root: Vec<Option<VetVersion>>,
+        reachable_from_target: Vec<Option<VetVersion>>,
+    },
+}
+
+/// A single audit, exemption or other entry along a path of audits
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JsonExplainEdge {
+    /// The kind of entry: `audit`, `wildcard-audit`, `trusted`, `exemption`
+    /// or `unpublished`
+    pub kind: String,
+    /// The version this edge starts at, or `null` for the root
+    pub from: Option<VetVersion>,
+    /// The version this edge ends at
+    pub to: VetVersion,
+    /// The supply-chain file this entry is stored in
+    pub file: String,
+    /// The peer this entry was imported from, if any
+    pub import: Option<ImportName>,
+    /// The criteria the entry certifies
+    pub criteria: Vec<CriteriaName>,
+    /// The criteria the entry would have certified if its import's
+    /// `max-criteria` hadn't capped it
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    #[serde(default)]
+    pub capped_from: Vec<CriteriaName>,
+    /// The people who performed the audit
+    pub who: Vec<String>,
+    /// The entry's notes
+    pub notes: Option<String>,
+}
+
+/// A violation published by a peer which has been disputed in the config
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DisputedViolation {
+    /// The peer which published the violation
+    pub import: ImportName,
+    /// The versions the violation applies to
+    pub violation: VersionReq,
+    /// The criteria the violation forbids
+    pub criteria: Vec<CriteriaName>,
+    /// Why the violation is disputed
+    pub notes: String,
+}
+
+/// cargo-vet's `--output-format=json` for `why`, describing where the
+/// criteria required of each matching version of a package come from.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JsonWhy {
+    pub packages: Vec<JsonWhyPackage>,
+}
+
+/// Why a single version of a package requires its criteria
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JsonWhyPackage {
+    /// The name of the package
+    pub name: PackageName,
+    /// The version of the package
+    pub version: VetVersion,
+    /// The criteria the package is required to satisfy
+    pub required_criteria: Vec<CriteriaName>,
+    /// The origins of each of the strongest required criteria
+    pub criteria: Vec<JsonWhyCriteria>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JsonWhyCriteria {
+    /// The required criteria
+    pub criteria: CriteriaName,
+    /// Each place this requirement is introduced, along with the shortest
+    /// dependency path from there to the package
+    pub origins: Vec<JsonWhyOrigin>,
+}
+
+/// A root or policy entry which introduces a requirement
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JsonWhyOrigin {
+    /// How the requirement is introduced: `root` (the default criteria for
+    /// roots), `criteria`, `dependency-criteria`, `dev-criteria` (from a
+    /// policy entry), `platform-criteria`, or `default-dev-criteria`
+    pub reason: String,
+    /// The package which is a root, or whose policy introduces the
+    /// requirement
+    pub package: JsonPackage,
+    /// The criteria the origin requires
+    pub criteria: Vec<CriteriaName>,
+    /// The dependency path from `package` (exclusive) to the explained
+    /// package (inclusive)
+    pub path: Vec<JsonWhyStep>,
+    /// The feature configuration the requirement is introduced in, if any are
+    /// configured
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub feature_config: Option<FeatureConfigName>,
+}
+
+/// A single dependency edge along a dependency path
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JsonWhyStep {
+    /// The name of the dependency
+    pub name: PackageName,
+    /// The version of the dependency
+    pub version: VetVersion,
+    /// The kind of dependency edge: `normal`, `build` or `dev`
+    pub kind: String,
+}
+
+/// cargo-vet's `--output-format=json` for `store-diff`, listing the changes
+/// to the supply-chain between two revisions of the store.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct JsonStoreDiff {
+    /// Audits, violations and wildcard audits in `audits.toml`
+    pub audits: Vec<JsonStoreDiffAudit>,
+    /// Exemptions in `config.toml`
+    pub exemptions: Vec<JsonStoreDiffExemption>,
+    /// Imports in `config.toml`
+    pub imports: Vec<JsonStoreDiffImport>,
+    /// Policy criteria in `config.toml`
+    pub policy: Vec<JsonStoreDiffPolicy>,
+    /// Trusted publishers in `audits.toml`
+    pub trusted: Vec<JsonStoreDiffTrusted>,
+}
+
+impl JsonStoreDiff {
+    pub fn is_empty(&self) -> bool {
+        self.audits.is_empty()
+            && self.exemptions.is_empty()
+            && self.imports.is_empty()
+            && self.policy.is_empty()
+            && self.trusted.is_empty()
+    }
+}
+
+/// How an entry in the store changed between two revisions
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum StoreDiffChange {
+    Added,
+    Removed,
+    /// The entry now covers more criteria than it used to, or an import is
+    /// now trusted for more than it used to be
+    Broadened,
+    /// The entry now covers fewer criteria than it used to, or an import is
+    /// now trusted for less than it used to be
+    Narrowed,
+    Changed,
+}
+
+/// An audit which was added or removed
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JsonStoreDiffAudit {
+    pub change: StoreDiffChange,
+    /// The name of the audited package
+    pub name: PackageName,
+    /// The kind of audit: `full`, `delta`, `violation` or `wildcard`
+    pub kind: String,
+    /// The audited version, delta (`from -> to`) or violated version
+    /// requirement, or the crates.io user id for a wildcard audit
+    pub target: String,
+    /// The criteria the audit is for
+    pub criteria: Vec<CriteriaName>,
+    /// Who performed the audit
+    pub who: Vec<String>,
+}
+
+/// An exemption which was added, removed, or which now covers different
+/// criteria
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JsonStoreDiffExemption {
+    pub change: StoreDiffChange,
+    /// The name of the exempted package
+    pub name: PackageName,
+    /// The exempted version
+    pub version: VetVersion,
+    /// The criteria the exemption used to cover (empty if it was added)
+    pub old_criteria: Vec<CriteriaName>,
+    /// The criteria the exemption now covers (empty if it was removed)
+    pub new_criteria: Vec<CriteriaName>,
+}
+
+/// An import which was added or removed, or a field of an import which changed
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JsonStoreDiffImport {
+    pub change: StoreDiffChange,
+    /// The name of the import
+    pub name: ImportName,
+    /// The URLs the import is fetched from
+    pub url: Vec<String>,
+    /// The field which changed, if the import was neither added nor removed:
+    /// `url`, `sha256`, `rev`, `exclude`, `criteria-map`, `max-criteria`,
+    /// `auditors`, `public-key`, `exclude-wildcard-audits` or `exclude-trusted`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub field: Option<String>,
+    /// The foreign criteria a `criteria-map` change applies to
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub key: Option<String>,
+    /// The value the field used to have
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub old_value: Option<Vec<String>>,
+    /// The value the field now has
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub new_value: Option<Vec<String>>,
+}
+
+/// A policy field whose criteria changed
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JsonStoreDiffPolicy {
+    pub change: StoreDiffChange,
+    /// The name of the package the policy applies to
+    pub name: PackageName,
+    /// The version the policy applies to, if it's versioned
+    pub version: Option<VetVersion>,
+    /// The field which changed: `criteria`, `dev-criteria` or
+    /// `dependency-criteria`
+    pub field: String,
+    /// The dependency a `dependency-criteria` change applies to
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub dependency: Option<String>,
+    /// The criteria the field used to have, if it was set
+    pub old_criteria: Option<Vec<CriteriaName>>,
+    /// The criteria the field now has, if it's set
+    pub new_criteria: Option<Vec<CriteriaName>>,
+}
+
+/// A trusted publisher which was added or removed
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JsonStoreDiffTrusted {
+    pub change: StoreDiffChange,
+    /// The name of the package the publisher is trusted for
+    pub name: PackageName,
+    /// The crates.io user id of the trusted publisher
+    pub user_id: CratesUserId,
+    /// The criteria the publisher is trusted for
+    pub criteria: Vec<CriteriaName>,
+}
+
+////////////////////////////////////////////////////////////////////////////////////
+//                                                                                //
+//                                                                                //
+//                                                                                //
+//                             <sarif report output>                              //
+//                                                                                //
+//                                                                                //
+//                                                                                //
+////////////////////////////////////////////////////////////////////////////////////
+
+/// cargo-vet's `--output-format=sarif` for `check` and `suggest`, a SARIF 2.1.0
+/// log with one result per problem, for use by code scanning tools.
+///
+/// Only the subset of the SARIF schema which cargo-vet produces is modelled.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SarifLog {
+    #[serde(rename = "$schema")]
+    pub schema: String,
+    pub version: String,
+    pub runs: Vec<SarifRun>,
+}
+
+/// A single run of cargo-vet
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SarifRun {
+    pub tool: SarifTool,
+    pub results: Vec<SarifResult>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SarifTool {
+    pub driver: SarifDriver,
+}
+
+/// Information about cargo-vet and the kinds of problems it reports
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SarifDriver {
+    pub name: String,
+    pub version: String,
+    pub information_uri: String,
+    pub rules: Vec<SarifRule>,
+}
+
+/// A kind of problem, referenced by `SarifResult::rule_id`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SarifRule {
+    pub id: String,
+    pub short_description: SarifMessage,
+    pub help_uri: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SarifMessage {
+    pub text: String,
+}
+
+/// A single problem found by cargo-vet
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SarifResult {
+    pub rule_id: String,
+    pub level: String,
+    pub message: SarifMessage,
+    pub locations: Vec<SarifLocation>,
+    /// Other places involved in the problem, such as the audit a violation
+    /// conflicts with
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    #[serde(default)]
+    pub related_locations: Vec<SarifLocation>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SarifLocation {
+    pub physical_location: SarifPhysicalLocation,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default)]
+    pub message: Option<SarifMessage>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SarifPhysicalLocation {
+    pub artifact_location: SarifArtifactLocation,
+    /// The region of the file, if it could be determined
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default)]
+    pub region: Option<SarifRegion>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SarifArtifactLocation {
+    /// The path of the file, relative to the workspace root where possible
+    pub uri: String,
+}
+
+/// A range of a file. Lines and columns are 1-based, and the end column is
+/// exclusive.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct SarifRegion {
+    pub start_line: usize,
+    pub start_column: usize,
+    pub end_line: usize,
+    pub end_column: usize,
+}
+
+////////////////////////////////////////////////////////////////////////////////////
+//                                                                                //
+//                                                                                //
+//                                                                                //
+//                                 <sbom output>                                  //
+//                                                                                //
+//                                                                                //
+//                                                                                //
+////////////////////////////////////////////////////////////////////////////////////
+
+/// A CycloneDX 1.5 JSON document, as produced by `cargo vet sbom`.
+///
+/// Only the subset of the CycloneDX schema which cargo-vet produces is
+/// modelled. Vet status is recorded in each component's `properties`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CycloneDxBom {
+    pub bom_format: String,
+    pub spec_version: String,
+    pub version: u32,
+    pub metadata: CycloneDxMetadata,
+    pub components: Vec<CycloneDxComponent>,
+    pub dependencies: Vec<CycloneDxDependency>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CycloneDxMetadata {
+    pub timestamp: String,
+    pub tools: CycloneDxTools,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CycloneDxTools {
+    pub components: Vec<CycloneDxComponent>,
+}
+
+/// A single package in the dependency graph
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CycloneDxComponent {
+    #[serde(rename = "type")]
+    pub kind: String,
+    #[serde(rename = "bom-ref")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default)]
+    pub bom_ref: Option<String>,
+    pub name: String,
+    pub version: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default)]
+    pub purl: Option<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    #[serde(default)]
+    pub properties: Vec<CycloneDxProperty>,
+}
+
+/// A `cargo-vet:*` annotation on a component
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CycloneDxProperty {
+    pub name: String,
+    pub value: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CycloneDxDependency {
+    #[serde(rename = "ref")]
+    pub bom_ref: String,
+    pub depends_on: Vec<String>,
+}
+
+/// An SPDX 2.3 JSON document, as produced by `cargo vet sbom --format=spdx`.
+///
+/// Only the subset of the SPDX schema which cargo-vet produces is modelled.
+/// Vet status is recorded in each package's `annotations`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SpdxDocument {
+    pub spdx_version: String,
+    pub data_license: String,
+    #[serde(rename = "SPDXID")]
+    pub spdx_id: String,
+    pub name: String,
+    pub document_namespace: String,
+    pub creation_info: SpdxCreationInfo,
+    pub packages: Vec<SpdxPackage>,
+    pub relationships: Vec<SpdxRelationship>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SpdxCreationInfo {
+    pub created: String,
+    pub creators: Vec<String>,
+}
+
+/// A single package in the dependency graph
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SpdxPackage {
+    #[serde(rename = "SPDXID")]
+    pub spdx_id: String,
+    pub name: String,
+    pub version_info: String,
+    pub download_location: String,
+    pub files_analyzed: bool,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    #[serde(default)]
+    pub external_refs: Vec<SpdxExternalRef>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    #[serde(default)]
+    pub annotations: Vec<SpdxAnnotation>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SpdxExternalRef {
+    pub reference_category: String,
+    pub reference_type: String,
+    pub reference_locator: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SpdxAnnotation {
+    pub annotation_date: String,
+    pub annotation_type: String,
+    pub annotator: String,
+    pub comment: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SpdxRelationship {
+    pub spdx_element_id: String,
+    pub relationship_type: String,
+    pub related_spdx_element: String,
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
@@ -1454,4 +2592,72 @@ mod test {
             _ => panic!("expected unknown revision"),
         }
     }
+
+    #[test]
+    fn diff_weight_globs() {
+        assert!(glob_matches("build.rs", "build.rs"));
+        assert!(!glob_matches("build.rs", "src/build.rs"));
+        assert!(glob_matches("**/build.rs", "src/build.rs"));
+        assert!(glob_matches("**/build.rs", "build.rs"));
+        assert!(glob_matches("tests/**", "tests/a/b.rs"));
+        assert!(!glob_matches("tests/**", "src/tests.rs"));
+        assert!(glob_matches("src/*_tables.rs", "src/unicode_tables.rs"));
+        assert!(!glob_matches("src/*_tables.rs", "src/a/unicode_tables.rs"));
+        assert!(glob_matches("src/table?.rs", "src/table1.rs"));
+        assert!(!glob_matches("src/table?.rs", "src/table.rs"));
+    }
+
+    #[test]
+    fn diff_weight_cost() {
+        let file = |path: &str, insertions, deletions, unsafe_lines| FileDiffStat {
+            path: path.to_owned(),
+            insertions,
+            deletions,
+            unsafe_lines,
+        };
+        let mut diff = PackageDiff {
+            diffstat: DiffStat {
+                insertions: 0,
+                deletions: 0,
+                files_changed: 0,
+            },
+            files: vec![
+                file("src/lib.rs", 100, 20, 10),
+                file("tests/big.rs", 1000, 0, 0),
+                file("README.md", 50, 50, 0),
+                file("build.rs", 10, 0, 0),
+            ],
+            proc_macro: false,
+            risks: vec![],
+        };
+
+        // Without any weights, every changed line counts once.
+        let weights = DiffWeights::default();
+        assert_eq!(weights.weigh(&diff), 1230);
+
+        let weights: DiffWeights = toml::from_str(
+            r#"
+unsafe = 3.0
+proc-macro = 2.0
+
+[[files]]
+paths = ["tests/**"]
+weight = 0
+
+[[files]]
+extensions = ["md"]
+weight = 0.1
+
+[[files]]
+paths = ["build.rs"]
+weight = 5
+"#,
+        )
+        .unwrap();
+        // src/lib.rs: 110 + 10 * 3, README.md: 100 * 0.1, build.rs: 10 * 5
+        assert_eq!(weights.weigh(&diff), 140 + 10 + 50);
+
+        diff.proc_macro = true;
+        assert_eq!(weights.weigh(&diff), 400);
+    }
 }
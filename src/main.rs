@@ -35,13 +35,16 @@ use tracing::{error, info, trace, warn};
 
 use crate::cli::*;
 use crate::criteria::CriteriaMapper;
+use crate::diff_viewer::{DiffFile, DiffViewer};
 use crate::errors::{
     CommandError, DownloadError, FetchAndDiffError, FetchError, MetadataAcquireError, SourceFile,
+    UnknownFeatureConfigMembersError,
 };
 use crate::format::{
-    AuditEntry, AuditKind, AuditsFile, ConfigFile, CratesUserId, CriteriaEntry, ExemptedDependency,
-    FetchCommand, MetaConfig, MetaConfigInstance, PackageStr, SortedMap, StoreInfo, TrustEntry,
-    WildcardEntry,
+    AlternateRegistry, AuditEntry, AuditKind, AuditsFile, ConfigFile, CratesUserId, CriteriaEntry,
+    ExemptedDependency, FastMap, FastSet, FeatureConfig, FeatureConfigName, FetchCommand,
+    ImportsFile, MetaConfig, MetaConfigInstance, PackageDiff, PackageStr, RegistryName,
+    RiskIndicator, SortedMap, StoreInfo, TrustEntry, WildcardEntry,
 };
 use crate::git_tool::Pager;
 use crate::out::{indeterminate_spinner, Out, StderrLogWriter, MULTIPROGRESS};
@@ -49,6 +52,7 @@ use crate::storage::{Cache, Store};
 
 mod cli;
 mod criteria;
+mod diff_viewer;
 pub mod errors;
 mod flock;
 pub mod format;
@@ -58,6 +62,7 @@ mod out;
 pub mod resolver;
 mod serialization;
 pub mod storage;
+mod store_diff;
 mod string_format;
 #[cfg(test)]
 mod tests;
@@ -68,6 +73,12 @@ pub struct Config {
     pub metacfg: MetaConfig,
     /// `cargo metadata`
     pub metadata: Metadata,
+    /// `cargo metadata` for each of the store's feature configurations, if
+    /// they're in use. `metadata` is the union of these.
+    pub feature_metadata: SortedMap<FeatureConfigName, Metadata>,
+    /// The cfg values of each target the graph is filtered to, if the store
+    /// has `platform-criteria` to evaluate against them.
+    pub target_cfgs: resolver::TargetCfgs,
     /// Freestanding configuration values
     _rest: PartialConfig,
 }
@@ -102,6 +113,8 @@ impl Deref for Config {
 pub trait PackageExt {
     fn is_third_party(&self, policy: &Policy) -> bool;
     fn is_crates_io(&self) -> bool;
+    fn is_alternate_registry(&self) -> bool;
+    fn is_registry(&self) -> bool;
     fn policy_entry<'a>(&self, policy: &'a Policy) -> Option<&'a PolicyEntry>;
     fn git_rev(&self) -> Option<String>;
     fn vet_version(&self) -> VetVersion;
@@ -114,7 +127,7 @@ impl PackageExt for Package {
             .and_then(|policy| policy.audit_as_crates_io)
             .unwrap_or(false);
 
-        forced_third_party || self.is_crates_io()
+        forced_third_party || self.is_registry()
     }
 
     fn is_crates_io(&self) -> bool {
@@ -124,6 +137,16 @@ impl PackageExt for Package {
             .unwrap_or(false)
     }
 
+    fn is_alternate_registry(&self) -> bool {
+        // Only packages from registries listed in config.toml are given a
+        // qualified name, see `qualify_registry_packages`.
+        format::split_qualified_package_name(&self.name).is_some()
+    }
+
+    fn is_registry(&self) -> bool {
+        self.is_crates_io() || self.is_alternate_registry()
+    }
+
     fn policy_entry<'a>(&self, policy: &'a Policy) -> Option<&'a PolicyEntry> {
         policy.get(&self.name, &self.vet_version())
     }
@@ -363,45 +386,17 @@ fn real_main() -> Result<(), miette::Report> {
     ///////////////////////////////////////////////////
 
     let cli = &partial_cfg.cli;
-    let cargo_path = std::env::var_os(CARGO_ENV).expect("Cargo failed to set $CARGO, how?");
 
-    let mut cmd = cargo_metadata::MetadataCommand::new();
-    cmd.cargo_path(cargo_path);
-    if let Some(manifest_path) = &cli.manifest_path {
-        cmd.manifest_path(manifest_path);
-    }
-    if !cli.no_all_features {
-        cmd.features(cargo_metadata::CargoOpt::AllFeatures);
-    }
-    if cli.no_default_features {
-        cmd.features(cargo_metadata::CargoOpt::NoDefaultFeatures);
-    }
-    if !cli.features.is_empty() {
-        cmd.features(cargo_metadata::CargoOpt::SomeFeatures(cli.features.clone()));
-    }
-    // We never want cargo-vet to update the Cargo.lock.
-    // For frozen runs we also don't want to touch the network.
-    let mut other_options = Vec::new();
-    if cli.frozen {
-        other_options.push("--frozen".to_string());
+    let metadata = if let Some(metadata_file) = &cli.metadata_file {
+        load_metadata_file(metadata_file)?
     } else {
-        other_options.push("--locked".to_string());
-    }
-    if !using_log_file
-        && cli.output_format == OutputFormat::Human
-        && console::colors_enabled_stderr()
-    {
-        other_options.push("--color=always".to_string());
-    }
-    other_options.extend(cli.cargo_arg.iter().cloned());
-    cmd.other_options(other_options);
-
-    info!("Running: {:#?}", cmd.cargo_command());
-
-    // ERRORS: immediate fatal diagnostic
-    let metadata = {
-        let _spinner = indeterminate_spinner("Running", "`cargo metadata`");
-        cmd.exec().map_err(MetadataAcquireError::from)?
+        cargo_metadata(
+            cli,
+            cli.manifest_path.as_deref(),
+            &cli.filter_platform,
+            None,
+            false,
+        )?
     };
 
     // trace!("Got Metadata! {:#?}", metadata);
@@ -496,9 +491,31 @@ fn real_main() -> Result<(), miette::Report> {
         ));
     }
 
+    // Packages from alternate registries need to be renamed before anything
+    // else looks at the metadata.
+    let graph = if init {
+        let config = Store::read_config(&metacfg)?;
+        workspace_graph(
+            cli,
+            cli.manifest_path.as_deref(),
+            metacfg.store_path().as_path_unlocked(),
+            &config,
+            metadata,
+            cli.metadata_file.is_some(),
+        )?
+    } else {
+        WorkspaceGraph {
+            metadata,
+            feature_metadata: SortedMap::new(),
+            target_cfgs: SortedMap::new(),
+        }
+    };
+
     let cfg = Config {
         metacfg,
-        metadata,
+        metadata: graph.metadata,
+        feature_metadata: graph.feature_metadata,
+        target_cfgs: graph.target_cfgs,
         _rest: partial_cfg,
     };
 
@@ -507,12 +524,16 @@ fn real_main() -> Result<(), miette::Report> {
         None => cmd_check(&out, &cfg, &cfg.cli.check_args),
         Some(Check(sub_args)) => cmd_check(&out, &cfg, sub_args),
         Some(Init(sub_args)) => cmd_init(&out, &cfg, sub_args),
+        Some(Sbom(sub_args)) => cmd_sbom(&out, &cfg, sub_args),
+        Some(StoreDiff(sub_args)) => cmd_store_diff(&out, &cfg, sub_args),
         Some(Certify(sub_args)) => cmd_certify(&out, &cfg, sub_args),
         Some(Import(sub_args)) => cmd_import(&out, &cfg, sub_args),
         Some(Trust(sub_args)) => cmd_trust(&out, &cfg, sub_args),
         Some(AddExemption(sub_args)) => cmd_add_exemption(&out, &cfg, sub_args),
         Some(RecordViolation(sub_args)) => cmd_record_violation(&out, &cfg, sub_args),
         Some(Suggest(sub_args)) => cmd_suggest(&out, &cfg, sub_args),
+        Some(Explain(sub_args)) => cmd_explain(&out, &cfg, sub_args),
+        Some(Why(sub_args)) => cmd_why(&out, &cfg, sub_args),
         Some(Fmt(sub_args)) => cmd_fmt(&out, &cfg, sub_args),
         Some(Prune(sub_args)) => cmd_prune(&out, &cfg, sub_args),
         Some(DumpGraph(sub_args)) => cmd_dump_graph(&out, &cfg, sub_args),
@@ -529,6 +550,179 @@ fn real_main() -> Result<(), miette::Report> {
     }
 }
 
+/// Run `cargo metadata` for the workspace of `manifest_path`. Named features
+/// and extra cargo arguments only apply to the current workspace, as other
+/// workspaces sharing the store may not have them.
+fn cargo_metadata(
+    cli: &Cli,
+    manifest_path: Option<&std::path::Path>,
+    platforms: &[String],
+    feature_config: Option<&FeatureConfig>,
+    other_workspace: bool,
+) -> Result<Metadata, MetadataAcquireError> {
+    // Only look up cargo when it's actually needed, a graph read from a
+    // file may be vetted without it.
+    let cargo_path = std::env::var_os(CARGO_ENV).expect("Cargo failed to set $CARGO, how?");
+    let mut cmd = cargo_metadata::MetadataCommand::new();
+    cmd.cargo_path(cargo_path);
+    if let Some(manifest_path) = manifest_path {
+        cmd.manifest_path(manifest_path);
+    }
+    let (all_features, default_features, features) = match feature_config {
+        Some(feature_config) => (
+            feature_config.all_features,
+            feature_config.default_features,
+            &feature_config.features,
+        ),
+        None => (
+            !cli.no_all_features,
+            !cli.no_default_features,
+            &cli.features,
+        ),
+    };
+    if all_features {
+        cmd.features(cargo_metadata::CargoOpt::AllFeatures);
+    }
+    if !default_features {
+        cmd.features(cargo_metadata::CargoOpt::NoDefaultFeatures);
+    }
+    if !features.is_empty() && !other_workspace {
+        cmd.features(cargo_metadata::CargoOpt::SomeFeatures(features.clone()));
+    }
+    // We never want cargo-vet to update the Cargo.lock.
+    // For frozen runs we also don't want to touch the network.
+    let mut other_options = Vec::new();
+    if cli.frozen {
+        other_options.push("--frozen".to_string());
+    } else {
+        other_options.push("--locked".to_string());
+    }
+    if cli.log_file.is_none()
+        && cli.output_format == OutputFormat::Human
+        && console::colors_enabled_stderr()
+    {
+        other_options.push("--color=always".to_string());
+    }
+    other_options.extend(
+        platforms
+            .iter()
+            .map(|platform| format!("--filter-platform={platform}")),
+    );
+    if !other_workspace {
+        other_options.extend(cli.cargo_arg.iter().cloned());
+    }
+    cmd.other_options(other_options);
+
+    info!("Running: {:#?}", cmd.cargo_command());
+
+    // ERRORS: immediate fatal diagnostic
+    let _spinner = indeterminate_spinner("Running", "`cargo metadata`");
+    cmd.exec().map_err(MetadataAcquireError::from)
+}
+
+/// The dependency graph of a workspace, along with the graphs of each of its
+/// feature configurations.
+struct WorkspaceGraph {
+    metadata: Metadata,
+    feature_metadata: SortedMap<FeatureConfigName, Metadata>,
+    target_cfgs: resolver::TargetCfgs,
+}
+
+/// Build the dependency graph of the workspace of `manifest_path`, whose
+/// `cargo metadata` is `metadata`, as configured by the store at
+/// `store_path`. A graph read `from_file` can't be regenerated with other
+/// options, so it's used as-is.
+fn workspace_graph(
+    cli: &Cli,
+    manifest_path: Option<&std::path::Path>,
+    store_path: &std::path::Path,
+    config: &ConfigFile,
+    mut metadata: Metadata,
+    from_file: bool,
+) -> Result<WorkspaceGraph, miette::Report> {
+    let platforms = if cli.filter_platform.is_empty() {
+        &config.filter_platforms
+    } else {
+        &cli.filter_platform
+    };
+    let target_cfgs = if config.platform_criteria.is_empty() {
+        SortedMap::new()
+    } else {
+        get_target_cfgs(platforms)
+    };
+
+    let workspaces: &[String] = if from_file { &[] } else { &config.workspaces };
+    let mut feature_metadata = SortedMap::new();
+
+    // Features selected on the command line take precedence over the
+    // feature configurations.
+    let use_feature_configs = !from_file
+        && !config.feature_configs.is_empty()
+        && !cli.no_all_features
+        && !cli.no_default_features
+        && cli.features.is_empty();
+
+    // Other workspaces which share the store are analyzed along with this
+    // one, so that their dependencies are vetted, and exemptions and
+    // imports they need aren't pruned.
+    let with_other_workspaces = |mut metadata: Metadata,
+                                 feature_config: Option<&FeatureConfig>|
+     -> Result<Metadata, miette::Report> {
+        let mut workspace_roots = vec![metadata.workspace_root.clone()];
+        for workspace in workspaces {
+            let mut manifest_path = store_path.join(workspace);
+            if manifest_path.is_dir() {
+                manifest_path.push("Cargo.toml");
+            }
+            let other = cargo_metadata(cli, Some(&manifest_path), platforms, feature_config, true)
+                .wrap_err_with(|| format!("couldn't get the metadata of workspace {workspace}"))?;
+            if workspace_roots.contains(&other.workspace_root) {
+                continue;
+            }
+            workspace_roots.push(other.workspace_root.clone());
+            merge_metadata(&mut metadata, other);
+        }
+
+        qualify_registry_packages(&mut metadata, &config.registries);
+        Ok(metadata)
+    };
+
+    if use_feature_configs {
+        // Each feature configuration gets its own graph, and the graph of
+        // the whole project is their union.
+        for (name, feature_config) in &config.feature_configs {
+            let config_metadata =
+                cargo_metadata(cli, manifest_path, platforms, Some(feature_config), false)
+                    .wrap_err_with(|| {
+                        format!("couldn't get the metadata of feature configuration {name}")
+                    })?;
+            feature_metadata.insert(
+                name.clone(),
+                with_other_workspaces(config_metadata, Some(feature_config))?,
+            );
+        }
+        let mut configs = feature_metadata.values();
+        metadata = configs.next().expect("no feature configurations").clone();
+        for config_metadata in configs {
+            merge_metadata(&mut metadata, config_metadata.clone());
+        }
+        check_feature_config_members(config, &metadata)?;
+    } else {
+        // The platforms in the config can only be known once we've found
+        // the store, so re-run `cargo metadata` if they need to be applied.
+        if !from_file && cli.filter_platform.is_empty() && !platforms.is_empty() {
+            metadata = cargo_metadata(cli, manifest_path, platforms, None, false)?;
+        }
+        metadata = with_other_workspaces(metadata, None)?;
+    }
+
+    Ok(WorkspaceGraph {
+        metadata,
+        feature_metadata,
+        target_cfgs,
+    })
+}
+
 fn cmd_init(_out: &Arc<dyn Out>, cfg: &Config, _sub_args: &InitArgs) -> Result<(), miette::Report> {
     // Initialize vet
     trace!("initializing...");
@@ -561,7 +755,7 @@ fn cmd_inspect(
     let version = &sub_args.version;
     let package = &*sub_args.package;
 
-    let fetched = {
+    let (fetched, risks) = {
         let network = Network::acquire(cfg);
         let store = Store::acquire(cfg, network.as_ref(), false)?;
         let cache = Cache::acquire(cfg)?;
@@ -614,18 +808,38 @@ fn cmd_inspect(
                     // use fetch_package, as we want to point the user at the
                     // actual cargo checkout, rather than our repack, which may
                     // be incomplete, and will be clobbered by GC.
-                    if let Some(git_rev) = &version.git_rev {
+                    let fetched = if let Some(git_rev) = &version.git_rev {
                         storage::locate_local_checkout(&cfg.metadata, package, version).ok_or_else(
                             || FetchError::UnknownGitRevision {
                                 package: package.to_owned(),
                                 git_rev: git_rev.to_owned(),
                             },
-                        )
+                        )?
                     } else {
                         cache
                             .fetch_package(&cfg.metadata, network.as_ref(), package, version)
-                            .await
-                    }
+                            .await?
+                    };
+                    let delta = format::Delta {
+                        from: None,
+                        to: version.clone(),
+                    };
+                    let risks = match cache
+                        .fetch_and_diffstat_package(
+                            &cfg.metadata,
+                            network.as_ref(),
+                            package,
+                            &delta,
+                        )
+                        .await
+                    {
+                        Ok(diff) => diff.risks,
+                        Err(err) => {
+                            warn!("couldn't scan {package}:{version} for risk indicators: {err}");
+                            vec![]
+                        }
+                    };
+                    Ok::<_, FetchError>((fetched, risks))
                 },
                 prompt_criteria_eulas(
                     out,
@@ -643,6 +857,8 @@ fn cmd_inspect(
         })?
     };
 
+    print_risk_indicators(out, &risks);
+
     #[cfg(target_family = "unix")]
     if let Some(shell) = std::env::var_os("SHELL") {
         // Loosely borrowed from cargo crev.
@@ -677,10 +893,29 @@ fn cmd_certify(
     let network = Network::acquire(cfg);
     let mut store = Store::acquire(cfg, network.as_ref(), false)?;
 
-    // Grab the last fetch and immediately drop the cache
-    let last_fetch = Cache::acquire(cfg)?.get_last_fetch();
+    // Grab the last fetch and any diffs we've already scanned for the package,
+    // then immediately drop the cache
+    let (last_fetch, cached_diffs) = {
+        let cache = Cache::acquire(cfg)?;
+        let last_fetch = cache.get_last_fetch();
+        let cached_diffs = sub_args
+            .package
+            .as_deref()
+            .or(last_fetch.as_ref().map(|last_fetch| last_fetch.package()))
+            .map(|package| cache.get_cached_diffs(package))
+            .unwrap_or_default();
+        (last_fetch, cached_diffs)
+    };
 
-    do_cmd_certify(out, cfg, sub_args, &mut store, network.as_ref(), last_fetch)?;
+    do_cmd_certify(
+        out,
+        cfg,
+        sub_args,
+        &mut store,
+        network.as_ref(),
+        last_fetch,
+        &cached_diffs,
+    )?;
 
     store.commit()?;
     Ok(())
@@ -693,6 +928,7 @@ fn do_cmd_certify(
     store: &mut Store,
     network: Option<&Network>,
     last_fetch: Option<FetchCommand>,
+    cached_diffs: &SortedMap<format::Delta, PackageDiff>,
 ) -> Result<(), CertifyError> {
     // Before setting up magic, we need to agree on a package
     let package = if let Some(package) = &sub_args.package {
@@ -893,6 +1129,29 @@ fn do_cmd_certify(
             editor.add_comments("=== END CRITERIA ===")?;
             editor.add_comments("")?;
         }
+        let delta = match &kind {
+            CertifyKind::Full { version } => Some(format::Delta {
+                from: None,
+                to: version.clone(),
+            }),
+            CertifyKind::Delta { from, to } => Some(format::Delta {
+                from: Some(from.clone()),
+                to: to.clone(),
+            }),
+            CertifyKind::Wildcard { .. } => None,
+        };
+        if let Some(diff) = delta.and_then(|delta| cached_diffs.get(&delta)) {
+            if !diff.risks.is_empty() {
+                editor.add_comments("=== RISK INDICATORS ===")?;
+                editor.add_comments("")?;
+                for risk in &diff.risks {
+                    editor.add_comments(&format!("- {risk}"))?;
+                }
+                editor.add_comments("")?;
+                editor.add_comments("=== END RISK INDICATORS ===")?;
+                editor.add_comments("")?;
+            }
+        }
         editor.add_comments("Uncomment the following statement:")?;
         editor.add_text("")?;
         editor.add_comments(&statement)?;
@@ -948,8 +1207,10 @@ fn do_cmd_certify(
                     who,
                     importable,
                     notes,
+                    date: Some(cfg.today()),
                     aggregated_from: vec![],
                     is_fresh_import: false,
+                    is_stale: false,
                 });
         }
         CertifyKind::Delta { from, to } => {
@@ -963,8 +1224,10 @@ fn do_cmd_certify(
                 who,
                 importable,
                 notes,
+                date: Some(cfg.today()),
                 aggregated_from: vec![],
                 is_fresh_import: false,
+                is_stale: false,
             };
 
             // Collapse a delta audit with a git `from` version with a prior audit that is
@@ -1176,8 +1439,8 @@ fn guess_audit_criteria(
 ) -> Vec<String> {
     // Attempt to resolve a normal `cargo vet`, and try to find criteria which
     // would heal some errors in that result if it fails.
-    let criteria = resolver::resolve(&cfg.metadata, cfg.cli.filter_graph.as_ref(), store)
-        .compute_suggested_criteria(package, from, to);
+    let criteria =
+        resolver::resolve_config(cfg, store).compute_suggested_criteria(package, from, to);
     if !criteria.is_empty() {
         return criteria;
     }
@@ -1187,12 +1450,8 @@ fn guess_audit_criteria(
     //
     // This is as much as we can do, so just return the result whether or not we
     // find anything.
-    resolver::resolve(
-        &cfg.metadata,
-        cfg.cli.filter_graph.as_ref(),
-        &store.clone_for_suggest(true),
-    )
-    .compute_suggested_criteria(package, from, to)
+    resolver::resolve_config(cfg, &store.clone_for_suggest(true))
+        .compute_suggested_criteria(package, from, to)
 }
 
 /// Prompt the user to read the EULAs for the expected criteria which they will
@@ -1284,7 +1543,7 @@ async fn prompt_criteria_eulas(
 }
 
 fn cmd_import(
-    _out: &Arc<dyn Out>,
+    out: &Arc<dyn Out>,
     cfg: &Config,
     sub_args: &ImportArgs,
 ) -> Result<(), miette::Report> {
@@ -1292,33 +1551,71 @@ fn cmd_import(
         return Err(miette!("`cargo vet import` cannot be run while frozen"));
     };
 
+    let mut store = Store::acquire_offline(cfg)?;
+
     // Determine the URL for the import, potentially fetching the registry to
-    // find it.
+    // find it. When updating, the existing URLs are kept by default.
     let registry_file;
-    let import_urls = if sub_args.url.is_empty() {
+    let import_urls = if !sub_args.url.is_empty() {
+        sub_args.url.clone()
+    } else if sub_args.update {
+        store
+            .config
+            .imports
+            .get(&sub_args.name)
+            .ok_or_else(|| miette!("no import named {} to update", &sub_args.name))
+            .map(|import| import.url.clone())?
+    } else {
         registry_file = tokio::runtime::Handle::current().block_on(fetch_registry(&network))?;
         registry_file
             .registry
             .get(&sub_args.name)
             .ok_or_else(|| miette!("no peer named {} found in the registry", &sub_args.name))
             .map(|entry| entry.url.clone())?
-    } else {
-        sub_args.url.clone()
     };
 
-    let mut store = Store::acquire_offline(cfg)?;
-
     // Insert a new entry for the new import, or update an existing entry to use
-    // the newly specified URLs.
-    store
+    // the newly specified URLs and public keys.
+    let import = store
         .config
         .imports
         .entry(sub_args.name.clone())
-        .or_default()
-        .url = import_urls;
+        .or_default();
+    import.url = import_urls;
+    if !sub_args.public_key.is_empty() {
+        import.public_key = sub_args.public_key.clone();
+    }
 
-    // After adding the new entry, go online, this will fetch the new import.
     let cache = Cache::acquire(cfg)?;
+
+    // When updating, pin the import to its current contents, and keep the old
+    // imports.lock around so that the changes can be shown. Imports which are
+    // already pinned to a rev, or unpinned imports from git, are pinned to the
+    // commits their URLs currently refer to, and everything else by sha256.
+    let old_imports = if sub_args.update {
+        let pin_revs = !import.rev.is_empty()
+            || (import.sha256.is_empty()
+                && import.url.iter().all(|url| storage::is_git_import(url)));
+        if pin_revs {
+            let revs = storage::fetch_import_revs(&cache, &sub_args.name, &import.url);
+            import.rev = tokio::runtime::Handle::current().block_on(revs)?;
+        }
+        if !pin_revs || !import.sha256.is_empty() {
+            let pins = storage::fetch_import_pins(
+                &network,
+                &cache,
+                &sub_args.name,
+                &import.url,
+                &import.rev,
+            );
+            import.sha256 = tokio::runtime::Handle::current().block_on(pins)?;
+        }
+        Some(store.imports.clone())
+    } else {
+        None
+    };
+
+    // After adding the new entry, go online, this will fetch the new import.
     tokio::runtime::Handle::current().block_on(store.go_online(cfg, &network, &cache, false))?;
 
     // Update the store state, pruning unnecessary exemptions, audits, and imports.
@@ -1329,11 +1626,49 @@ fn cmd_import(
         prune_imports: true,
     });
 
+    if let Some(old_imports) = old_imports {
+        print_imports_changes(out, &sub_args.name, &old_imports, &store.imports)?;
+    }
+
     store.commit()?;
 
     Ok(())
 }
 
+/// Print the changes to imports.lock made by updating the import `name`.
+fn print_imports_changes(
+    out: &Arc<dyn Out>,
+    name: &str,
+    old_imports: &ImportsFile,
+    new_imports: &ImportsFile,
+) -> Result<(), miette::Report> {
+    let format_imports = |imports: &ImportsFile| {
+        serialization::to_formatted_toml(imports, Some(&storage::user_info_map(imports)))
+            .map(|doc| doc.to_string())
+            .into_diagnostic()
+    };
+    let old_imports = format_imports(old_imports)?;
+    let new_imports = format_imports(new_imports)?;
+
+    if old_imports == new_imports {
+        writeln!(out, "No changes to imports.lock from updating {name}");
+    } else {
+        writeln!(out, "Changes to imports.lock from updating {name}:");
+        writeln!(
+            out,
+            "{}",
+            similar::udiff::unified_diff(
+                similar::Algorithm::Myers,
+                &old_imports,
+                &new_imports,
+                5,
+                Some(("old", "new")),
+            )
+        );
+    }
+    Ok(())
+}
+
 fn cmd_trust(out: &Arc<dyn Out>, cfg: &Config, sub_args: &TrustArgs) -> Result<(), miette::Report> {
     // Certify that you have reviewed a crate's source for some version / delta
     let network = Network::acquire(cfg);
@@ -1395,8 +1730,7 @@ fn do_cmd_trust(
         // Run the resolver against the store in "suggest" mode to discover the
         // set of packages which either fail to audit or need exemptions.
         let suggest_store = store.clone_for_suggest(true);
-        let report =
-            resolver::resolve(&cfg.metadata, cfg.cli.filter_graph.as_ref(), &suggest_store);
+        let report = resolver::resolve_config(cfg, &suggest_store);
         let resolver::Conclusion::FailForVet(fail) = &report.conclusion else {
             return Err(miette!(
                 "No failing or exempted crates, trust --all will do nothing"
@@ -1655,8 +1989,10 @@ fn cmd_record_violation(
         who,
         importable: true,
         notes,
+        date: Some(cfg.today()),
         aggregated_from: vec![],
         is_fresh_import: false,
+        is_stale: false,
     };
 
     store
@@ -1716,6 +2052,8 @@ fn cmd_add_exemption(
         notes,
         version: sub_args.version.clone(),
         suggest,
+        end: None,
+        is_expired: false,
     };
 
     store
@@ -1741,13 +2079,26 @@ fn cmd_suggest(
     let suggest_store = Store::acquire(cfg, network.as_ref(), false)?.clone_for_suggest(true);
 
     // DO THE THING!!!!
-    let report = resolver::resolve(&cfg.metadata, cfg.cli.filter_graph.as_ref(), &suggest_store);
+    let report = resolver::resolve_config(cfg, &suggest_store);
     let suggest = report.compute_suggest(cfg, &suggest_store, network.as_ref())?;
     match cfg.cli.output_format {
         OutputFormat::Human => report
             .print_suggest_human(out, cfg, suggest.as_ref())
             .into_diagnostic()?,
         OutputFormat::Json => report.print_json(out, suggest.as_ref())?,
+        OutputFormat::Sarif => report.print_sarif(
+            out,
+            cfg,
+            cfg.metacfg.store_path().as_path_unlocked(),
+            &suggest_store,
+            suggest.as_ref(),
+            None,
+        )?,
+        OutputFormat::Junit => {
+            return Err(miette!(
+                "JUnit output is only supported by `cargo vet check`"
+            ))
+        }
     }
 
     Ok(())
@@ -1864,40 +2215,6 @@ fn do_cmd_renew(out: &Arc<dyn Out>, cfg: &Config, store: &mut Store, sub_args: &
 
     let new_end_date = cfg.today() + chrono::Months::new(12);
 
-    let mut renewing: WildcardAuditRenewal;
-
-    if let Some(name) = &sub_args.crate_name {
-        match WildcardAuditRenewal::single_crate(name, store) {
-            Some(renewal) => {
-                renewing = renewal;
-                if renewing.is_empty() {
-                    info!("no wildcard audits for {name} are eligible for renewal (all have `renew = false`)");
-                    return;
-                }
-            }
-            None => {
-                warn!("ran `renew {name}`, but there are no wildcard audits for the crate");
-                return;
-            }
-        }
-    } else {
-        // Find and update all expiring crates.
-        assert!(sub_args.expiring);
-        renewing = WildcardAuditRenewal::expiring(cfg, store, !sub_args.include_inactive);
-
-        if renewing.is_empty() {
-            info!("no wildcard audits that are eligible for renewal have expired or are expiring in the next {WILDCARD_AUDIT_EXPIRATION_STRING}");
-            return;
-        }
-    }
-
-    renewing.renew(new_end_date);
-
-    writeln!(
-        out,
-        "Updated wildcard audits for the following crates and publishers to expire on {new_end_date}:"
-    );
-
     let user_string = |user_id: u64| -> String {
         cache
             .as_ref()
@@ -1905,18 +2222,74 @@ fn do_cmd_renew(out: &Arc<dyn Out>, cfg: &Config, store: &mut Store, sub_args: &
             .map(|n| n.to_string())
             .unwrap_or_else(|| format!("id={}", user_id))
     };
-    for (name, entries) in renewing.crates {
+
+    let wildcards = match &sub_args.crate_name {
+        Some(name) => WildcardAuditRenewal::single_crate(name, store),
+        None => Some(WildcardAuditRenewal::expiring(
+            cfg,
+            store,
+            !sub_args.include_inactive,
+        )),
+    };
+    let has_wildcards = wildcards.is_some();
+    let mut renewed_any = false;
+
+    if let Some(mut renewing) = wildcards.filter(|renewing| !renewing.is_empty()) {
+        renewing.renew(new_end_date);
+        renewed_any = true;
+
         writeln!(
             out,
-            "  {}: {:80}",
-            name,
-            string_format::FormatShortList::new(
-                entries
-                    .iter()
-                    .map(|(entry, _)| user_string(entry.user_id))
-                    .collect()
-            )
+            "Updated wildcard audits for the following crates and publishers to expire on {new_end_date}:"
+        );
+        for (name, entries) in renewing.crates {
+            writeln!(
+                out,
+                "  {}: {:80}",
+                name,
+                string_format::FormatShortList::new(
+                    entries
+                        .iter()
+                        .map(|(entry, _)| user_string(entry.user_id))
+                        .collect()
+                )
+            );
+        }
+    }
+
+    let mut exemptions = match &sub_args.crate_name {
+        Some(name) => ExemptionRenewal::single_crate(name, &mut store.config.exemptions),
+        None => ExemptionRenewal::expiring(cfg, &mut store.config.exemptions),
+    };
+    if !exemptions.is_empty() {
+        exemptions.renew(new_end_date);
+        renewed_any = true;
+
+        writeln!(
+            out,
+            "Updated exemptions for the following crates and versions to expire on {new_end_date}:"
         );
+        for (name, entries) in exemptions.crates {
+            writeln!(
+                out,
+                "  {}: {:80}",
+                name,
+                string_format::FormatShortList::new(
+                    entries
+                        .iter()
+                        .map(|(entry, _)| entry.version.to_string())
+                        .collect()
+                )
+            );
+        }
+    }
+
+    if !renewed_any {
+        match &sub_args.crate_name {
+            Some(name) if has_wildcards => info!("no wildcard audits for {name} are eligible for renewal (all have `renew = false`)"),
+            Some(name) => warn!("ran `renew {name}`, but there are no wildcard audits or exemptions with an end date for the crate"),
+            None => info!("no wildcard audits or exemptions that are eligible for renewal have expired or are expiring in the next {WILDCARD_AUDIT_EXPIRATION_STRING}"),
+        }
     }
 }
 
@@ -1950,7 +2323,7 @@ async fn fix_audit_as(
                 cfg.metadata
                     .packages
                     .iter()
-                    .filter(|&p| (p.name == error.package))
+                    .filter(|&p| p.name == error.package)
                     .map(|p| p.vet_version())
                     .collect()
             };
@@ -2048,13 +2421,33 @@ fn cmd_regenerate_exemptions(
     Ok(())
 }
 
+/// Print a summary of the risk indicators found in a package or delta, so the
+/// reviewer knows where to look first.
+fn print_risk_indicators(out: &Arc<dyn Out>, risks: &[RiskIndicator]) {
+    if risks.is_empty() {
+        return;
+    }
+    writeln!(
+        out,
+        "{}",
+        out.style()
+            .yellow()
+            .bold()
+            .apply_to("Risk indicators (consider reviewing these first):")
+    );
+    for risk in risks {
+        writeln!(out, "  - {risk}");
+    }
+    writeln!(out);
+}
+
 fn cmd_diff(out: &Arc<dyn Out>, cfg: &Config, sub_args: &DiffArgs) -> Result<(), miette::Report> {
     let version1 = &sub_args.version1;
     let version2 = &sub_args.version2;
     let package = &*sub_args.package;
 
-    let to_compare = {
-        let network = Network::acquire(cfg);
+    let network = Network::acquire(cfg);
+    let (cache, pkg1, pkg2, diff, to_compare) = {
         let store = Store::acquire(cfg, network.as_ref(), false)?;
         let cache = Cache::acquire(cfg)?;
 
@@ -2106,7 +2499,7 @@ fn cmd_diff(out: &Arc<dyn Out>, cfg: &Config, sub_args: &DiffArgs) -> Result<(),
             return Ok(());
         }
 
-        tokio::runtime::Handle::current().block_on(async {
+        let (pkg1, pkg2, diff, to_compare) = tokio::runtime::Handle::current().block_on(async {
             // NOTE: don't `try_join` everything as we don't want to abort the
             // prompt to the user if the download fails while it is being shown, as
             // that could be disorienting.
@@ -2116,14 +2509,14 @@ fn cmd_diff(out: &Arc<dyn Out>, cfg: &Config, sub_args: &DiffArgs) -> Result<(),
                         cache.fetch_package(&cfg.metadata, network.as_ref(), package, version1),
                         cache.fetch_package(&cfg.metadata, network.as_ref(), package, version2)
                     )?;
-                    let (_, to_compare) = cache
+                    let (diff, to_compare) = cache
                         .diffstat_package(
                             &pkg1,
                             &pkg2,
                             version1.git_rev.is_some() || version2.git_rev.is_some(),
                         )
                         .await?;
-                    Ok::<_, FetchAndDiffError>(to_compare)
+                    Ok::<_, FetchAndDiffError>((pkg1, pkg2, diff, to_compare))
                 },
                 prompt_criteria_eulas(
                     out,
@@ -2138,10 +2531,48 @@ fn cmd_diff(out: &Arc<dyn Out>, cfg: &Config, sub_args: &DiffArgs) -> Result<(),
             );
             eulas.into_diagnostic()?;
             to_compare.into_diagnostic()
-        })?
+        })?;
+        (cache, pkg1, pkg2, diff, to_compare)
     };
 
     writeln!(out);
+    print_risk_indicators(out, &diff.risks);
+
+    if !sub_args.git_diff {
+        let files = to_compare
+            .iter()
+            .map(|(from, to)| DiffFile::load(&pkg1, &pkg2, from, to))
+            .collect::<Result<Vec<_>, _>>()
+            .into_diagnostic()?;
+        let delta = format::Delta {
+            from: Some(version1.clone()),
+            to: version2.clone(),
+        };
+        let reviewed = cache.get_reviewed_files(package, &delta);
+        // Release the cache while the user is reviewing, re-acquiring it
+        // briefly to record each file they mark as reviewed.
+        drop(cache);
+        let mut viewer = DiffViewer::new(
+            &**out,
+            package,
+            delta.clone(),
+            files,
+            reviewed,
+            |path, reviewed| {
+                let cache =
+                    Cache::acquire(cfg).map_err(|err| io::Error::new(io::ErrorKind::Other, err))?;
+                cache.set_file_reviewed(package, &delta, path, reviewed);
+                Ok(())
+            },
+        );
+        if out.is_term() {
+            viewer.run().into_diagnostic()?;
+        } else {
+            viewer.print_all();
+        }
+        writeln!(out, "\nUse |cargo vet certify| to record your audit.");
+        return Ok(());
+    }
 
     // Start a pager to show the output from our diff invocations. This will
     // fall back to just printing to `stdout` if no pager is available or we're
@@ -2176,33 +2607,51 @@ fn cmd_diff(out: &Arc<dyn Out>, cfg: &Config, sub_args: &DiffArgs) -> Result<(),
     Ok(())
 }
 
-fn cmd_check(
-    out: &Arc<dyn Out>,
-    cfg: &Config,
-    _sub_args: &CheckArgs,
-) -> Result<(), miette::Report> {
+fn cmd_check(out: &Arc<dyn Out>, cfg: &Config, sub_args: &CheckArgs) -> Result<(), miette::Report> {
     // Run the checker to validate that the current set of deps is covered by the current cargo vet store
     trace!("vetting...");
 
     let network = Network::acquire(cfg);
     let mut store = Store::acquire(cfg, network.as_ref(), false)?;
 
+    let baseline = sub_args
+        .baseline
+        .as_deref()
+        .map(|baseline| load_baseline(cfg, baseline))
+        .transpose()?;
+
+    let mut policy_errors = None;
     if !cfg.cli.locked {
         // Check if any of our first-parties are in the crates.io registry
         let mut cache = Cache::acquire(cfg).into_diagnostic()?;
         // Check crate policies prior to audit_as_crates_io because the suggestions of
         // check_audit_as_crates_io will rely on the correct structure of crate policies.
-        check_crate_policies(cfg, &store)?;
-        tokio::runtime::Handle::current().block_on(check_audit_as_crates_io(
-            cfg,
-            &store,
-            network.as_ref(),
-            &mut cache,
-        ))?;
+        match check_crate_policies(cfg, &store) {
+            // SARIF output reports policy errors alongside the other results.
+            Err(errors) if cfg.cli.output_format == OutputFormat::Sarif => {
+                policy_errors = Some(errors)
+            }
+            result => {
+                result?;
+                tokio::runtime::Handle::current().block_on(check_audit_as_crates_io(
+                    cfg,
+                    &store,
+                    network.as_ref(),
+                    &mut cache,
+                ))?;
+            }
+        }
     }
 
     // DO THE THING!!!!
-    let report = resolver::resolve(&cfg.metadata, cfg.cli.filter_graph.as_ref(), &store);
+    let report = resolver::resolve_with_baseline(
+        &cfg.metadata,
+        &cfg.feature_metadata,
+        &cfg.target_cfgs,
+        cfg.cli.filter_graph.as_ref(),
+        &store,
+        baseline.as_ref(),
+    );
 
     // Bare `cargo vet` shouldn't suggest in CI
     let suggest = if !cfg.cli.locked {
@@ -2216,10 +2665,39 @@ fn cmd_check(
             .print_human(out, cfg, suggest.as_ref())
             .into_diagnostic()?,
         OutputFormat::Json => report.print_json(out, suggest.as_ref())?,
+        OutputFormat::Sarif => report.print_sarif(
+            out,
+            cfg,
+            cfg.metacfg.store_path().as_path_unlocked(),
+            &store,
+            suggest.as_ref(),
+            policy_errors.as_ref(),
+        )?,
+        OutputFormat::Junit => report.print_junit(out, &store)?,
+    }
+
+    // Exemptions which have passed their end date no longer vet anything, so
+    // point out why the packages they covered may now be failing.
+    let expired =
+        ExemptionRenewal::expiring(cfg, &mut store.config.exemptions).expired_exemptions();
+    if !expired.is_empty() {
+        let expired = string_format::FormatShortList::new(expired);
+        warn!("Your supply-chain has exemptions for {expired} which have expired and no longer apply.");
+        warn!(
+            "  Audit these crates, or run `cargo vet renew --expiring` to extend the exemptions."
+        );
+    }
+
+    // Disputes which no longer match a violation should be cleaned up.
+    for (package, dispute) in store.unmatched_disputes() {
+        warn!(
+            "The dispute of {}'s violation of {package} ({}) doesn't match any violation it has published.",
+            *dispute.import, dispute.violation
+        );
     }
 
     // Only save imports if we succeeded, to avoid any modifications on error.
-    if report.has_errors() {
+    if report.has_errors() || policy_errors.is_some() {
         // ERRORS: immediate fatal diagnostic? Arguably should be silent.
         // Err(eyre!("report contains errors"))?;
         panic_any(ExitPanic(-1));
@@ -2288,6 +2766,16 @@ fn cmd_check(
                 }
                 warn!("  Consider running `cargo vet renew --expiring` or adding `renew = false` to the wildcard entries in audits.toml.");
             }
+
+            // Warn about exemptions which will be expiring soon. Expired
+            // exemptions were already reported above.
+            let expiring =
+                ExemptionRenewal::expiring(cfg, &mut store.config.exemptions).expiring_exemptions();
+            if !expiring.is_empty() {
+                let expiring = string_format::FormatShortList::new(expiring);
+                warn!("Your supply-chain has exemptions for {expiring} which expire within the next {WILDCARD_AUDIT_EXPIRATION_STRING}.");
+                warn!("  Consider auditing these crates, or running `cargo vet renew --expiring` to extend the exemptions.");
+            }
         }
 
         store.commit()?;
@@ -2401,6 +2889,90 @@ impl<'a> WildcardAuditRenewal<'a> {
     }
 }
 
+#[derive(Default)]
+struct ExemptionRenewal<'a> {
+    // the bool indicates whether the exemption is already expired (true) or will expire soon
+    // (false)
+    pub crates: SortedMap<PackageStr<'a>, Vec<(&'a mut ExemptedDependency, bool)>>,
+}
+
+impl<'a> ExemptionRenewal<'a> {
+    /// Get all exemptions with an end date which have expired or will expire soon.
+    ///
+    /// Like `WildcardAuditRenewal::expiring`, this doesn't modify the exemptions, but holds
+    /// mutable references to them for potential use by `renew`.
+    pub fn expiring(
+        cfg: &Config,
+        exemptions: &'a mut SortedMap<PackageName, Vec<ExemptedDependency>>,
+    ) -> Self {
+        let expire_date = cfg.today() + *WILDCARD_AUDIT_EXPIRATION_DURATION;
+
+        let mut crates: SortedMap<PackageStr<'a>, Vec<(&'a mut ExemptedDependency, bool)>> =
+            Default::default();
+        for (name, entries) in exemptions.iter_mut() {
+            for entry in entries.iter_mut().filter(|e| e.expires_before(expire_date)) {
+                let expired = entry.expires_before(cfg.today());
+                crates.entry(name).or_default().push((entry, expired));
+            }
+        }
+        ExemptionRenewal { crates }
+    }
+
+    /// Create a renewal of every exemption with an end date for a single crate, regardless of
+    /// expiration.
+    pub fn single_crate(
+        name: PackageStr<'a>,
+        exemptions: &'a mut SortedMap<PackageName, Vec<ExemptedDependency>>,
+    ) -> Self {
+        let mut crates: SortedMap<PackageStr<'a>, Vec<(&'a mut ExemptedDependency, bool)>> =
+            Default::default();
+        if let Some((name, entries)) = exemptions.iter_mut().find(|(n, _)| *n == name) {
+            for entry in entries.iter_mut().filter(|e| e.end.is_some()) {
+                crates.entry(name).or_default().push((entry, false));
+            }
+        }
+        ExemptionRenewal { crates }
+    }
+
+    /// Whether there are no exemptions expiring or expired.
+    pub fn is_empty(&self) -> bool {
+        self.crates.is_empty()
+    }
+
+    /// Get the exemptions which have expired, as `crate:version` strings.
+    pub fn expired_exemptions(&self) -> Vec<String> {
+        self.exemptions_where(|expired| expired)
+    }
+
+    /// Get the exemptions which will expire soon, as `crate:version` strings.
+    pub fn expiring_exemptions(&self) -> Vec<String> {
+        self.exemptions_where(|expired| !expired)
+    }
+
+    fn exemptions_where(&self, f: impl Fn(bool) -> bool) -> Vec<String> {
+        self.crates
+            .iter()
+            .flat_map(|(name, entries)| {
+                entries
+                    .iter()
+                    .filter(|(_, expired)| f(*expired))
+                    .map(move |(entry, _)| format!("{name}:{}", entry.version))
+            })
+            .collect()
+    }
+
+    /// Renew all stored entries.
+    pub fn renew(&mut self, new_end_date: chrono::NaiveDate) {
+        for entry in self
+            .crates
+            .values_mut()
+            .flat_map(|v| v.iter_mut().map(|t| &mut t.0))
+        {
+            entry.end = Some(new_end_date.into());
+        }
+    }
+}
+
 fn cmd_prune(
     _out: &Arc<dyn Out>,
     cfg: &Config,
@@ -2605,6 +3177,386 @@ fn do_aggregate_audits(sources: Vec<(String, AuditsFile)>) -> Result<AuditsFile,
     }
 }
 
+fn cmd_explain(
+    out: &Arc<dyn Out>,
+    cfg: &Config,
+    sub_args: &ExplainArgs,
+) -> Result<(), miette::Report> {
+    // Explain how the given package was vetted
+    trace!("explaining...");
+
+    let network = Network::acquire(cfg);
+    let store = Store::acquire(cfg, network.as_ref(), false)?;
+
+    let report = resolver::resolve_config(cfg, &store);
+    let explain = report.compute_explain(&store, &sub_args.package, sub_args.version.as_ref())?;
+    if explain.packages.is_empty() {
+        return Err(match &sub_args.version {
+            Some(version) => miette!(
+                "{}:{} is not in the dependency graph",
+                sub_args.package,
+                version
+            ),
+            None => miette!("{} is not in the dependency graph", sub_args.package),
+        });
+    }
+
+    match cfg.cli.output_format {
+        OutputFormat::Human => resolver::print_explain_human(out, &explain).into_diagnostic()?,
+        OutputFormat::Json => serde_json::to_writer_pretty(&**out, &explain).into_diagnostic()?,
+        OutputFormat::Sarif | OutputFormat::Junit => {
+            return Err(miette!(
+                "`cargo vet explain` only supports human and json output"
+            ))
+        }
+    }
+
+    Ok(())
+}
+
+fn cmd_why(out: &Arc<dyn Out>, cfg: &Config, sub_args: &WhyArgs) -> Result<(), miette::Report> {
+    // Explain where the given package's requirements come from
+    trace!("explaining requirements...");
+
+    let network = Network::acquire(cfg);
+    let store = Store::acquire(cfg, network.as_ref(), false)?;
+
+    let report = resolver::resolve_config(cfg, &store);
+    let why = report.compute_why(&sub_args.package, sub_args.version.as_ref());
+    if why.packages.is_empty() {
+        return Err(match &sub_args.version {
+            Some(version) => miette!(
+                "{}:{} is not in the dependency graph",
+                sub_args.package,
+                version
+            ),
+            None => miette!("{} is not in the dependency graph", sub_args.package),
+        });
+    }
+
+    match cfg.cli.output_format {
+        OutputFormat::Human => resolver::print_why_human(out, &why).into_diagnostic()?,
+        OutputFormat::Json => serde_json::to_writer_pretty(&**out, &why).into_diagnostic()?,
+        OutputFormat::Sarif | OutputFormat::Junit => {
+            return Err(miette!(
+                "`cargo vet why` only supports human and json output"
+            ))
+        }
+    }
+
+    Ok(())
+}
+
+fn cmd_sbom(out: &Arc<dyn Out>, cfg: &Config, sub_args: &SbomArgs) -> Result<(), miette::Report> {
+    // Export the dependency graph along with how each package was vetted
+    trace!("exporting sbom...");
+
+    let network = Network::acquire(cfg);
+    let store = Store::acquire(cfg, network.as_ref(), false)?;
+
+    let report = resolver::resolve_config(cfg, &store);
+    report.print_sbom(out, cfg, &store, sub_args.format)?;
+
+    Ok(())
+}
+
+fn cmd_store_diff(
+    out: &Arc<dyn Out>,
+    cfg: &Config,
+    sub_args: &StoreDiffArgs,
+) -> Result<(), miette::Report> {
+    // Compare two revisions of the store
+    trace!("diffing store...");
+
+    let store_path = cfg.metacfg.store_path();
+    let store_path = store_path.as_path_unlocked();
+    let old = load_store_revision(store_path, &sub_args.old)?;
+    let new = match &sub_args.new {
+        Some(new) => load_store_revision(store_path, new)?,
+        None => Store::acquire_offline(cfg)?,
+    };
+
+    let diff = store_diff::diff_stores(&old, &new);
+    match cfg.cli.output_format {
+        OutputFormat::Human => store_diff::print_store_diff(out, &diff, sub_args.markdown),
+        OutputFormat::Json => serde_json::to_writer_pretty(&**out, &diff).into_diagnostic()?,
+        OutputFormat::Sarif | OutputFormat::Junit => {
+            return Err(miette!(
+                "`cargo vet store-diff` only supports human and json output"
+            ))
+        }
+    }
+
+    Ok(())
+}
+
+/// Read the contents of the file at `path` (relative to `dir`) at the given
+/// git revision.
+fn git_show_file(
+    dir: &std::path::Path,
+    revision: &str,
+    path: &str,
+) -> Result<String, CommandError> {
+    let output = std::process::Command::new("git")
+        .arg("show")
+        .arg(format!("{revision}:./{path}"))
+        .current_dir(dir)
+        .output()
+        .map_err(CommandError::CommandFailed)?;
+    if !output.status.success() {
+        return Err(CommandError::BadStatus(output.status.code().unwrap_or(-1)));
+    }
+    String::from_utf8(output.stdout).map_err(CommandError::BadOutput)
+}
+
+/// A temporary checkout of a git revision, which is removed when dropped.
+struct GitWorktree {
+    repo: std::path::PathBuf,
+    dir: tempfile::TempDir,
+}
+
+impl GitWorktree {
+    fn add(repo: &std::path::Path, revision: &str) -> Result<Self, CommandError> {
+        let dir = tempfile::tempdir().map_err(CommandError::CommandFailed)?;
+        let worktree = GitWorktree {
+            repo: repo.to_owned(),
+            dir,
+        };
+        let status = std::process::Command::new("git")
+            .arg("worktree")
+            .arg("add")
+            .arg("--quiet")
+            .arg("--detach")
+            .arg("--end-of-options")
+            .arg(worktree.path())
+            .arg(revision)
+            .current_dir(repo)
+            .stdout(Stdio::null())
+            .status()
+            .map_err(CommandError::CommandFailed)?;
+        if !status.success() {
+            return Err(CommandError::BadStatus(status.code().unwrap_or(-1)));
+        }
+        Ok(worktree)
+    }
+
+    fn path(&self) -> std::path::PathBuf {
+        self.dir.path().join("checkout")
+    }
+}
+
+impl Drop for GitWorktree {
+    fn drop(&mut self) {
+        let _ = std::process::Command::new("git")
+            .arg("worktree")
+            .arg("remove")
+            .arg("--force")
+            .arg(self.path())
+            .current_dir(&self.repo)
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .status();
+    }
+}
+
+/// Find which packages already failed to vet in the baseline of `check
+/// --baseline`, which is either a path to the `Cargo.lock` of a checkout of the
+/// baseline, or a git revision to check out. The graph of the baseline is
+/// resolved against the store of the baseline, which is found at the same
+/// place in the workspace as the current store.
+fn load_baseline(cfg: &Config, baseline: &str) -> Result<resolver::Baseline, miette::Report> {
+    let workspace_root = cfg.metadata.workspace_root.as_std_path();
+    let store_path = cfg.metacfg.store_path();
+    let relative_store_path = store_path
+        .as_path_unlocked()
+        .strip_prefix(workspace_root)
+        .map_err(|_| {
+            miette!(
+                "the store at {} isn't within the workspace, so it can't be found in the baseline",
+                store_path.display()
+            )
+        })?;
+    let relative_manifest_path = cfg
+        .cli
+        .manifest_path
+        .as_deref()
+        .and_then(|path| path.canonicalize().ok())
+        .and_then(|path| Some(path.strip_prefix(workspace_root).ok()?.to_owned()))
+        .unwrap_or_else(|| "Cargo.toml".into());
+
+    let path = std::path::Path::new(baseline);
+    let mut _worktree = None;
+    let baseline_root = if path.is_file() {
+        // The Cargo.lock is always at the root of its workspace.
+        path.canonicalize()
+            .into_diagnostic()?
+            .parent()
+            .expect("a file always has a parent directory")
+            .to_owned()
+    } else {
+        let prefix = git_workspace_prefix(workspace_root).into_diagnostic()?;
+        let worktree = GitWorktree::add(workspace_root, baseline)
+            .into_diagnostic()
+            .wrap_err_with(|| format!("couldn't check out the baseline {baseline}"))?;
+        let root = worktree.path().join(prefix);
+        _worktree = Some(worktree);
+        root
+    };
+
+    let baseline_store_path = baseline_root.join(relative_store_path);
+    let store = load_store_dir(&baseline_store_path)
+        .wrap_err_with(|| format!("couldn't load the store of the baseline {baseline}"))?;
+    let manifest_path = baseline_root.join(relative_manifest_path);
+    let metadata = cargo_metadata(
+        &cfg.cli,
+        Some(&manifest_path),
+        &cfg.cli.filter_platform,
+        None,
+        false,
+    )
+    .wrap_err_with(|| format!("couldn't get the metadata of the baseline {baseline}"))?;
+    let graph = workspace_graph(
+        &cfg.cli,
+        Some(&manifest_path),
+        &baseline_store_path,
+        &store.config,
+        metadata,
+        false,
+    )?;
+
+    Ok(resolver::resolve_baseline(
+        &graph.metadata,
+        &graph.feature_metadata,
+        &graph.target_cfgs,
+        cfg.cli.filter_graph.as_ref(),
+        &store,
+    ))
+}
+
+/// The path of the workspace at `dir` within its git repository, which ends in
+/// a `/` unless it's empty.
+fn git_workspace_prefix(dir: &std::path::Path) -> Result<String, CommandError> {
+    let output = std::process::Command::new("git")
+        .arg("rev-parse")
+        .arg("--show-prefix")
+        .current_dir(dir)
+        .output()
+        .map_err(CommandError::CommandFailed)?;
+    if !output.status.success() {
+        return Err(CommandError::BadStatus(output.status.code().unwrap_or(-1)));
+    }
+    let prefix = String::from_utf8(output.stdout).map_err(CommandError::BadOutput)?;
+    Ok(prefix.trim_end().to_owned())
+}
+
+/// Load the dependency graph from a saved `cargo metadata` JSON file, or
+/// synthesize it from a Cargo.lock if the file isn't JSON.
+fn load_metadata_file(path: &std::path::Path) -> Result<Metadata, miette::Report> {
+    let contents = std::fs::read_to_string(path)
+        .into_diagnostic()
+        .wrap_err_with(|| format!("couldn't read the metadata file {}", path.display()))?;
+
+    // The format is picked by content rather than by name, as some build
+    // systems save `cargo metadata` JSON with a `.lock` extension (e.g. Bazel's
+    // `Cargo.Bazel.lock`). A TOML Cargo.lock can't start with `{`.
+    if contents.trim_start().starts_with('{') {
+        return serde_json::from_str(&contents)
+            .into_diagnostic()
+            .wrap_err_with(|| format!("couldn't parse the metadata file {}", path.display()));
+    }
+
+    let lockfile: format::CargoLockFile = toml::de::from_str(&contents)
+        .into_diagnostic()
+        .wrap_err_with(|| format!("couldn't parse the Cargo.lock {}", path.display()))?;
+    let workspace_root = path
+        .canonicalize()
+        .into_diagnostic()?
+        .parent()
+        .expect("a file always has a parent directory")
+        .to_owned();
+    Ok(metadata_from_lockfile(&lockfile, &workspace_root))
+}
+
+/// Synthesize the metadata of a project from its Cargo.lock. The lockfile
+/// doesn't say how packages depend on each other, so every package is made a
+/// workspace member without any dependencies, which makes them all roots.
+fn metadata_from_lockfile(
+    lockfile: &format::CargoLockFile,
+    workspace_root: &std::path::Path,
+) -> Metadata {
+    let workspace_root = workspace_root.display().to_string();
+    let id = |package: &format::CargoLockPackage| match &package.source {
+        Some(source) => format!("{} {} ({source})", package.name, package.version),
+        None => format!(
+            "{} {} (path+file://{workspace_root})",
+            package.name, package.version
+        ),
+    };
+
+    let metadata = serde_json::json!({
+        "packages": lockfile.package.iter().map(|package| serde_json::json!({
+            "name": package.name,
+            "version": package.version.to_string(),
+            "id": id(package),
+            "source": package.source,
+            "dependencies": [],
+            "targets": [],
+            "features": {},
+            "manifest_path": format!("{workspace_root}/{}/Cargo.toml", package.name),
+        })).collect::<Vec<_>>(),
+        "workspace_members": lockfile.package.iter().map(id).collect::<Vec<_>>(),
+        "resolve": {
+            "nodes": lockfile.package.iter().map(|package| serde_json::json!({
+                "id": id(package),
+                "dependencies": [],
+                "deps": [],
+            })).collect::<Vec<_>>(),
+            "root": null,
+        },
+        "target_directory": format!("{workspace_root}/target"),
+        "version": 1,
+        "workspace_root": workspace_root,
+        "metadata": null,
+    });
+    serde_json::from_value(metadata).expect("metadata synthesized from Cargo.lock was invalid")
+}
+
+/// Load the store at `revision`, which is either a directory containing the
+/// store's files, or a git revision of the repository containing `store_path`.
+fn load_store_revision(
+    store_path: &std::path::Path,
+    revision: &str,
+) -> Result<Store, miette::Report> {
+    if std::path::Path::new(revision).is_dir() {
+        return load_store_dir(std::path::Path::new(revision));
+    }
+    load_store_files(revision, |name| {
+        git_show_file(store_path, revision, name).into_diagnostic()
+    })
+}
+
+/// Load the store whose files are in the directory `dir`.
+fn load_store_dir(dir: &std::path::Path) -> Result<Store, miette::Report> {
+    load_store_files(&dir.display().to_string(), |name| {
+        std::fs::read_to_string(dir.join(name)).into_diagnostic()
+    })
+}
+
+fn load_store_files(
+    source: &str,
+    read_file: impl Fn(&str) -> Result<String, miette::Report>,
+) -> Result<Store, miette::Report> {
+    let read_file = |name: &str| {
+        read_file(name).wrap_err_with(|| format!("couldn't read {name} from {source}"))
+    };
+
+    let config = read_file(storage::CONFIG_TOML)?;
+    let audits = read_file(storage::AUDITS_TOML)?;
+    // Older revisions of the store may predate imports.lock.
+    let imports = read_file(storage::IMPORTS_LOCK).unwrap_or_default();
+    Ok(Store::from_sources(&config, &audits, &imports)?)
+}
+
 fn cmd_dump_graph(
     out: &Arc<dyn Out>,
     cfg: &Config,
@@ -2614,16 +3566,43 @@ fn cmd_dump_graph(
     trace!("dumping...");
 
     let graph = resolver::DepGraph::new(&cfg.metadata, cfg.cli.filter_graph.as_ref(), None);
+    if sub_args.metadata {
+        let metadata = filter_metadata(&cfg.metadata, &graph);
+        serde_json::to_writer_pretty(&**out, &metadata).into_diagnostic()?;
+        return Ok(());
+    }
     match cfg.cli.output_format {
         OutputFormat::Human => graph.print_mermaid(out, sub_args).into_diagnostic()?,
         OutputFormat::Json => {
             serde_json::to_writer_pretty(&**out, &graph.nodes).into_diagnostic()?
         }
+        OutputFormat::Sarif | OutputFormat::Junit => {
+            return Err(miette!(
+                "`cargo vet dump-graph` only supports human and json output"
+            ))
+        }
     }
 
     Ok(())
 }
 
+/// Restrict `metadata` to the packages in `graph`, so that a filtered graph
+/// can be saved and read back with `--metadata-file`.
+fn filter_metadata(metadata: &Metadata, graph: &resolver::DepGraph<'_>) -> Metadata {
+    let keep = |id: &cargo_metadata::PackageId| graph.interner_by_pkgid.contains_key(id);
+    let mut metadata = metadata.clone();
+    metadata.packages.retain(|package| keep(&package.id));
+    metadata.workspace_members.retain(keep);
+    if let Some(resolve) = &mut metadata.resolve {
+        resolve.nodes.retain(|node| keep(&node.id));
+        for node in &mut resolve.nodes {
+            node.dependencies.retain(keep);
+            node.deps.retain(|dep| keep(&dep.pkg));
+        }
+    }
+    metadata
+}
+
 fn cmd_fmt(_out: &Arc<dyn Out>, cfg: &Config, _sub_args: &FmtArgs) -> Result<(), miette::Report> {
     // Reformat all the files (just load and store them, formatting is implicit).
     trace!("formatting...");
@@ -2903,7 +3882,7 @@ fn first_party_packages_strict<'a>(
     metadata
         .packages
         .iter()
-        .filter(move |package| !package.is_crates_io())
+        .filter(move |package| !package.is_registry())
 }
 
 /// All third-party packages, **without** the audit-as-crates-io policy applied (used in crate
@@ -2915,7 +3894,153 @@ fn foreign_packages_strict<'a>(
     metadata
         .packages
         .iter()
-        .filter(move |package| package.is_crates_io())
+        .filter(move |package| package.is_registry())
+}
+
+/// Merge the metadata of another workspace sharing the store into `metadata`,
+/// so that the dependency graph contains the workspace members of both.
+///
+/// Packages which are in both workspaces may have been resolved with different
+/// dependencies or features, so the union of their resolve nodes is used.
+fn merge_metadata(metadata: &mut Metadata, other: Metadata) {
+    let known_packages: FastSet<cargo_metadata::PackageId> =
+        metadata.packages.iter().map(|p| p.id.clone()).collect();
+    metadata.packages.extend(
+        other
+            .packages
+            .into_iter()
+            .filter(|package| !known_packages.contains(&package.id)),
+    );
+    let known_members: FastSet<cargo_metadata::PackageId> =
+        metadata.workspace_members.iter().cloned().collect();
+    metadata.workspace_members.extend(
+        other
+            .workspace_members
+            .into_iter()
+            .filter(|member| !known_members.contains(member)),
+    );
+
+    let (Some(resolve), Some(other_resolve)) = (&mut metadata.resolve, other.resolve) else {
+        return;
+    };
+    let mut node_indices: FastMap<cargo_metadata::PackageId, usize> = resolve
+        .nodes
+        .iter()
+        .enumerate()
+        .map(|(idx, node)| (node.id.clone(), idx))
+        .collect();
+    for other_node in other_resolve.nodes {
+        let Some(&node_idx) = node_indices.get(&other_node.id) else {
+            node_indices.insert(other_node.id.clone(), resolve.nodes.len());
+            resolve.nodes.push(other_node);
+            continue;
+        };
+        let node = &mut resolve.nodes[node_idx];
+        for other_dep in other_node.deps {
+            let Some(dep) = node.deps.iter_mut().find(|d| d.pkg == other_dep.pkg) else {
+                node.deps.push(other_dep);
+                continue;
+            };
+            for kind in other_dep.dep_kinds {
+                let is_same = |k: &cargo_metadata::DepKindInfo| {
+                    k.kind == kind.kind
+                        && k.target.as_ref().map(|t| t.to_string())
+                            == kind.target.as_ref().map(|t| t.to_string())
+                };
+                if !dep.dep_kinds.iter().any(is_same) {
+                    dep.dep_kinds.push(kind);
+                }
+            }
+        }
+        for dependency in other_node.dependencies {
+            if !node.dependencies.contains(&dependency) {
+                node.dependencies.push(dependency);
+            }
+        }
+        for feature in other_node.features {
+            if !node.features.contains(&feature) {
+                node.features.push(feature);
+            }
+        }
+    }
+}
+
+/// Ask rustc for the cfg values of each of the target `platforms`, so that
+/// `platform-criteria` can be evaluated against them. If they can't all be
+/// determined, none are returned, and `platform-criteria` are only matched
+/// textually.
+fn get_target_cfgs(platforms: &[String]) -> resolver::TargetCfgs {
+    let rustc = std::env::var_os("RUSTC").unwrap_or_else(|| "rustc".into());
+    let mut target_cfgs = SortedMap::new();
+    for platform in platforms {
+        let cfgs = std::process::Command::new(&rustc)
+            .args(["--print", "cfg", "--target", platform])
+            .output()
+            .map_err(|error| error.to_string())
+            .and_then(|output| {
+                if !output.status.success() {
+                    return Err(String::from_utf8_lossy(&output.stderr).into_owned());
+                }
+                String::from_utf8_lossy(&output.stdout)
+                    .lines()
+                    .map(|line| line.parse::<cargo_platform::Cfg>())
+                    .collect::<Result<Vec<_>, _>>()
+                    .map_err(|error| error.to_string())
+            });
+        match cfgs {
+            Ok(cfgs) => {
+                target_cfgs.insert(platform.clone(), cfgs);
+            }
+            Err(error) => {
+                warn!(
+                    "couldn't determine the cfg values of {platform}, so platform-criteria \
+                    will only be matched textually: {error}"
+                );
+                return SortedMap::new();
+            }
+        }
+    }
+    target_cfgs
+}
+
+/// Rename the packages from the alternate registries listed in config.toml to
+/// their qualified names (`{registry}:{package}`).
+///
+/// This is done once, immediately after the metadata is loaded, so that the
+/// rest of vet (audits, exemptions, policies, the resolver, ...) will
+/// consistently treat these packages as third-party, and never confuse them
+/// with a crates.io package with the same name.
+fn qualify_registry_packages(
+    metadata: &mut Metadata,
+    registries: &SortedMap<RegistryName, AlternateRegistry>,
+) {
+    if registries.is_empty() {
+        return;
+    }
+    for package in &mut metadata.packages {
+        let Some(source) = &package.source else {
+            continue;
+        };
+        if source.is_crates_io() {
+            continue;
+        }
+        if let Some(name) = registry_qualified_name(registries, &package.name, &source.repr) {
+            package.name = name;
+        }
+    }
+}
+
+/// The name used for a package from the given `source`, if it comes from one
+/// of the alternate `registries`.
+fn registry_qualified_name(
+    registries: &SortedMap<RegistryName, AlternateRegistry>,
+    name: &str,
+    source: &str,
+) -> Option<PackageName> {
+    let (registry, _) = registries
+        .iter()
+        .find(|(_, registry)| registry.matches_source(source))?;
+    Some(format::qualified_package_name(registry, name))
 }
 
 async fn check_audit_as_crates_io(
@@ -3042,6 +4167,35 @@ async fn check_audit_as_crates_io(
     }
 }
 
+/// Check that the `members` of each feature configuration are workspace
+/// members of `metadata`, as a misspelled member would otherwise silently
+/// exclude the real ones from the configuration.
+fn check_feature_config_members(
+    config: &ConfigFile,
+    metadata: &Metadata,
+) -> Result<(), UnknownFeatureConfigMembersError> {
+    let workspace_members: FastSet<&str> = metadata
+        .workspace_members
+        .iter()
+        .map(|id| &metadata[id].name[..])
+        .collect();
+    for (name, feature_config) in &config.feature_configs {
+        let unknown: Vec<PackageName> = feature_config
+            .members
+            .iter()
+            .filter(|member| !workspace_members.contains(&member[..]))
+            .cloned()
+            .collect();
+        if !unknown.is_empty() {
+            return Err(UnknownFeatureConfigMembersError {
+                feature_config: name.clone(),
+                members: unknown,
+            });
+        }
+    }
+    Ok(())
+}
+
 /// Check crate policies for correctness.
 ///
 /// This verifies two rules:
@@ -8,7 +8,9 @@
 //! tasks.
 
 use std::{
+    collections::HashMap,
     ffi::{OsStr, OsString},
+    future::Future,
     io::Write,
     path::{Path, PathBuf},
     sync::Mutex,
@@ -17,8 +19,9 @@ use std::{
 
 use base64_stream::FromBase64Writer;
 use bytes::Bytes;
-use reqwest::{Client, Url};
+use reqwest::{header::AUTHORIZATION, Client, Proxy, StatusCode, Url};
 use tokio::io::AsyncWriteExt;
+use tracing::warn;
 
 use crate::{
     errors::{DownloadError, SourceFile},
@@ -66,6 +69,13 @@ impl Response<'_> {
 pub struct Network {
     /// The HTTP client all requests go through
     client: Client,
+    /// Base URL for crates.io API requests, including package downloads
+    crates_io_api: Url,
+    /// Authorization tokens to send with requests under each registry's index
+    /// URL, keyed by that URL
+    auth_tokens: HashMap<String, String>,
+    /// Number of times to retry a download which failed with a transient error
+    retries: u32,
     /// Semaphore preventing exceeding the maximum number of connections.
     connection_semaphore: tokio::sync::Semaphore,
     /// Cache of source files downloaded by Url
@@ -76,6 +86,10 @@ pub struct Network {
 }
 
 const DEFAULT_TIMEOUT_SECS: u64 = 60;
+const DEFAULT_RETRIES: u32 = 3;
+const RETRY_BASE_DELAY: Duration = Duration::from_millis(500);
+const RETRY_MAX_DELAY: Duration = Duration::from_secs(10);
+const CRATES_IO_API: &str = "https://crates.io/api/";
 const MAX_CONCURRENT_CONNECTIONS: usize = 40;
 const USER_AGENT: &str = concat!(
     env!("CARGO_PKG_NAME"),
@@ -130,38 +144,108 @@ impl Network {
     ///
     /// There should only ever be one Network instance instantiated. Do it early
     /// and then pass it around by-ref.
+    ///
+    /// The `http.cainfo`, `http.proxy`, `http.timeout` and `net.retry` settings
+    /// from cargo's config are honored, as are the `token`s of registries in
+    /// cargo's config, which are sent with all requests under the URL of the
+    /// registry's index.
     pub fn acquire(cfg: &PartialConfig) -> Option<Self> {
         if cfg.cli.frozen {
             None
         } else {
-            // TODO: make this configurable on the CLI or something
-            let timeout = Duration::from_secs(DEFAULT_TIMEOUT_SECS);
-            // TODO: make this configurable on the CLI or something
-            let mut client_builder = Client::builder().user_agent(USER_AGENT).timeout(timeout);
-            if let Ok(cargo_config) = cargo_config2::Config::load() {
-                // Add the cargo `http.cainfo` to the reqwest client if it is set
-                if let Some(cainfo) = cargo_config.http.cainfo {
-                    match Network::parse_ca_file(&cainfo) {
-                        Ok(cert) => client_builder = client_builder.add_root_certificate(cert),
-                        Err(e) => println!(
-                            "failed to load certificate from Cargo http.cainfo `{}`, attempting to download without it. Error: {e:?}", cainfo
-                       ),
+            Some(Self::with_cargo_config(
+                cfg,
+                cargo_config2::Config::load().ok(),
+            ))
+        }
+    }
+
+    /// Create a Network using the settings from `cargo_config`, if it could be
+    /// loaded.
+    pub(crate) fn with_cargo_config(
+        cfg: &PartialConfig,
+        cargo_config: Option<cargo_config2::Config>,
+    ) -> Self {
+        let mut timeout = Duration::from_secs(DEFAULT_TIMEOUT_SECS);
+        let mut retries = DEFAULT_RETRIES;
+        let mut auth_tokens = HashMap::new();
+        let mut client_builder = Client::builder().user_agent(USER_AGENT);
+        if let Some(cargo_config) = cargo_config {
+            // Add the cargo `http.cainfo` to the reqwest client if it is set
+            if let Some(cainfo) = cargo_config.http.cainfo {
+                match Network::parse_ca_file(&cainfo) {
+                    Ok(cert) => client_builder = client_builder.add_root_certificate(cert),
+                    Err(e) => println!(
+                        "failed to load certificate from Cargo http.cainfo `{}`, attempting to download without it. Error: {e:?}", cainfo
+                   ),
+                }
+            }
+            if let Some(proxy) = cargo_config.http.proxy.filter(|proxy| !proxy.is_empty()) {
+                match Proxy::all(&proxy) {
+                    Ok(proxy) => client_builder = client_builder.proxy(proxy),
+                    Err(e) => warn!(
+                        "failed to use proxy from Cargo http.proxy `{proxy}`, attempting to download without it. Error: {e:?}"
+                    ),
+                }
+            }
+            if let Some(secs) = cargo_config.http.timeout {
+                timeout = Duration::from_secs(secs.into());
+            }
+            if let Some(retry) = cargo_config.net.retry {
+                retries = retry;
+            }
+            for registry in cargo_config.registries.into_values() {
+                let prefix = registry.index.as_deref().and_then(|index| {
+                    let index = index.strip_prefix("sparse+").unwrap_or(index);
+                    let mut index = Url::parse(index).ok()?;
+                    // Make sure the index is treated as a directory, so that
+                    // the token isn't sent to sibling paths on the same host.
+                    if !index.path().ends_with('/') {
+                        index.set_path(&format!("{}/", index.path()));
                     }
+                    Some(index.to_string())
+                });
+                if let (Some(prefix), Some(token)) = (prefix, registry.token) {
+                    auth_tokens.insert(prefix, token);
                 }
             }
-            let client = client_builder
-                .build()
-                .expect("Couldn't construct HTTP Client?");
-            Some(Self {
-                client,
-                connection_semaphore: tokio::sync::Semaphore::new(MAX_CONCURRENT_CONNECTIONS),
-                source_file_cache: Default::default(),
-                #[cfg(test)]
-                mock_network: None,
-            })
+        }
+        let client = client_builder
+            .timeout(timeout)
+            .build()
+            .expect("Couldn't construct HTTP Client?");
+        let crates_io_api = match &cfg.cli.crates_io_mirror {
+            // Make sure the mirror URL is treated as a directory when
+            // joining paths onto it.
+            Some(mirror) if !mirror.path().ends_with('/') => {
+                let mut mirror = mirror.clone();
+                mirror.set_path(&format!("{}/", mirror.path()));
+                mirror
+            }
+            Some(mirror) => mirror.clone(),
+            None => Url::parse(CRATES_IO_API).unwrap(),
+        };
+        Self {
+            client,
+            crates_io_api,
+            auth_tokens,
+            retries,
+            connection_semaphore: tokio::sync::Semaphore::new(MAX_CONCURRENT_CONNECTIONS),
+            source_file_cache: Default::default(),
+            #[cfg(test)]
+            mock_network: None,
         }
     }
 
+    /// The URL of `path` within the crates.io API, e.g. `v1/crates/serde`.
+    ///
+    /// This is relative to the configured mirror, if there is one.
+    pub fn crates_io_api_url(&self, path: &str) -> Url {
+        self.crates_io_api
+            .join(path)
+            .expect("invalid crates.io API path")
+    }
+
     fn parse_ca_file(path: &str) -> Result<reqwest::Certificate, Box<dyn std::error::Error>> {
         Ok(reqwest::Certificate::from_pem(&std::fs::read(path)?)?)
     }
@@ -176,21 +260,33 @@ impl Network {
             persist_to.as_os_str(),
             OsStr::new(".part"),
         ]));
+        self.with_retries(&url, || {
+            self.download_and_persist_once(url.clone(), persist_to, &download_tmp_path)
+        })
+        .await
+    }
+
+    async fn download_and_persist_once(
+        &self,
+        url: Url,
+        persist_to: &Path,
+        download_tmp_path: &Path,
+    ) -> Result<(), DownloadError> {
         {
             let mut res = self.fetch_core(url).await?;
 
             let mut download_tmp =
-                tokio::fs::File::create(&download_tmp_path)
+                tokio::fs::File::create(download_tmp_path)
                     .await
                     .map_err(|error| DownloadError::FailedToCreateDownload {
-                        target: download_tmp_path.clone(),
+                        target: download_tmp_path.to_owned(),
                         error,
                     })?;
 
             while let Some(chunk) = res.chunk().await? {
                 download_tmp.write_all(&chunk[..]).await.map_err(|error| {
                     DownloadError::FailedToWriteDownload {
-                        target: download_tmp_path.clone(),
+                        target: download_tmp_path.to_owned(),
                         error,
                     }
                 })?;
@@ -198,10 +294,10 @@ impl Network {
         }
 
         // Rename the downloaded file into the final location.
-        match tokio::fs::rename(&download_tmp_path, &persist_to).await {
+        match tokio::fs::rename(download_tmp_path, persist_to).await {
             Ok(()) => {}
             Err(err) => {
-                let _ = tokio::fs::remove_file(&download_tmp_path).await;
+                let _ = tokio::fs::remove_file(download_tmp_path).await;
                 return Err(err).map_err(|error| DownloadError::FailedToFinalizeDownload {
                     target: persist_to.to_owned(),
                     error,
@@ -214,6 +310,11 @@ impl Network {
 
     /// Download a file into memory
     pub async fn download(&self, url: Url) -> Result<Vec<u8>, DownloadError> {
+        self.with_retries(&url, || self.download_once(url.clone()))
+            .await
+    }
+
+    async fn download_once(&self, url: Url) -> Result<Vec<u8>, DownloadError> {
         let mut res = self.fetch_core(url).await?;
 
         let encoding = PayloadEncoding::for_response(&res);
@@ -256,9 +357,46 @@ impl Network {
         }
     }
 
+    /// Run a download, retrying it with exponential backoff if it fails with a
+    /// transient error, such as a timeout or a 5xx response.
+    async fn with_retries<T, F, Fut>(&self, url: &Url, mut download: F) -> Result<T, DownloadError>
+    where
+        F: FnMut() -> Fut,
+        Fut: Future<Output = Result<T, DownloadError>>,
+    {
+        let mut attempt = 0;
+        loop {
+            match download().await {
+                Err(error) if attempt < self.retries && is_transient(&error) => {
+                    let delay = RETRY_BASE_DELAY
+                        .saturating_mul(2u32.saturating_pow(attempt))
+                        .min(RETRY_MAX_DELAY);
+                    attempt += 1;
+                    warn!(
+                        "retrying download of {url} in {delay:?} ({attempt}/{}): {error:?}",
+                        self.retries
+                    );
+                    tokio::time::sleep(delay).await;
+                }
+                result => return result,
+            }
+        }
+    }
+
+    /// The authorization token to send with a request to `url`, if it is under
+    /// the index URL of a registry with a token.
+    fn auth_token(&self, url: &Url) -> Option<&str> {
+        self.auth_tokens
+            .iter()
+            .filter(|(prefix, _)| url.as_str().starts_with(prefix.as_str()))
+            // Prefer the most specific index if several match.
+            .max_by_key(|(prefix, _)| prefix.len())
+            .map(|(_, token)| token.as_str())
+    }
+
     /// Internal core implementation of network fetching which is shared between
     /// `download` and `download_and_persist`.
-    async fn fetch_core(&self, url: Url) -> Result<Response, DownloadError> {
+    async fn fetch_core(&self, url: Url) -> Result<Response<'_>, DownloadError> {
         #[cfg(test)]
         if let Some(mock_network) = &self.mock_network {
             let chunk = mock_network
@@ -284,9 +422,11 @@ impl Network {
             .await
             .expect("Semaphore dropped?!");
 
-        let res = self
-            .client
-            .get(url.clone())
+        let mut request = self.client.get(url.clone());
+        if let Some(token) = self.auth_token(&url) {
+            request = request.header(AUTHORIZATION, token);
+        }
+        let res = request
             .send()
             .await
             .and_then(|res| res.error_for_status())
@@ -299,12 +439,32 @@ impl Network {
     }
 }
 
+/// Whether a failed download might succeed if it is retried: only failures to
+/// connect, timeouts, and 5xx or 429 responses are, so that e.g. a bad URL or
+/// a 404 fails right away.
+fn is_transient(error: &DownloadError) -> bool {
+    match error {
+        DownloadError::FailedToStartDownload { error, .. }
+        | DownloadError::FailedToReadDownload { error, .. } => {
+            error.is_timeout()
+                || error.is_connect()
+                || error.status().is_some_and(|status| {
+                    status.is_server_error() || status == StatusCode::TOO_MANY_REQUESTS
+                })
+        }
+        _ => false,
+    }
+}
+
 #[cfg(test)]
 impl Network {
     /// Create a new Network which is serving mocked out resources.
     pub(crate) fn new_mock() -> Self {
         let mut network = Network {
             client: Client::new(),
+            crates_io_api: Url::parse(CRATES_IO_API).unwrap(),
+            auth_tokens: HashMap::new(),
+            retries: 0,
             connection_semaphore: tokio::sync::Semaphore::new(MAX_CONCURRENT_CONNECTIONS),
             source_file_cache: Default::default(),
             #[cfg(test)]
@@ -318,6 +478,11 @@ impl Network {
         network
     }
 
+    /// Send crates.io API requests made through this network to `mirror`.
+    pub(crate) fn mock_crates_io_mirror(&mut self, mirror: &str) {
+        self.crates_io_api = Url::parse(mirror).unwrap();
+    }
+
     /// Add a new resource to be served by a mocked-out network.
     pub(crate) fn mock_serve(&mut self, url: impl AsRef<str>, data: impl AsRef<[u8]>) {
         self.mock_network
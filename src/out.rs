@@ -5,7 +5,7 @@
 //! stderr.
 
 use crate::git_tool::Editor;
-use console::{Style, Term};
+use console::{Key, Style, Term};
 use indicatif::{MultiProgress, ProgressBar, ProgressDrawTarget, ProgressStyle};
 use lazy_static::lazy_static;
 use std::{borrow::Cow, fmt, fs::File, io, mem, time::Duration};
@@ -50,6 +50,18 @@ pub trait Out: Send + Sync + 'static {
         Err(io::ErrorKind::Unsupported.into())
     }
 
+    /// Wait for the user to press a key, without echoing it. If there's no
+    /// user able to respond, an error will be returned instead.
+    fn read_key(&self) -> io::Result<Key> {
+        Err(io::ErrorKind::Unsupported.into())
+    }
+
+    /// Get the size of the terminal as `(rows, columns)`. Defaults to a
+    /// typical terminal size when not writing to a terminal.
+    fn size(&self) -> (u16, u16) {
+        (24, 80)
+    }
+
     /// Get a `Style` object which can be used to style text written to this
     /// user. Defaults to a disabled style object.
     fn style(&self) -> Style {
@@ -86,6 +98,14 @@ impl Out for Term {
         self.read_line()
     }
 
+    fn read_key(&self) -> io::Result<Key> {
+        self.read_key()
+    }
+
+    fn size(&self) -> (u16, u16) {
+        self.size()
+    }
+
     fn style(&self) -> Style {
         self.style()
     }
@@ -40,29 +40,45 @@
 //!   existing set of criteria, to suggest the best audit and criteria which could
 //!   be used to allow the crate to vet successfully.
 
-use cargo_metadata::{DependencyKind, Metadata, Node, PackageId};
+use cargo_metadata::{semver, DependencyKind, Metadata, Node, PackageId};
 use futures_util::future::join_all;
 use miette::IntoDiagnostic;
+use miette::SourceSpan;
+use reqwest::Url;
 use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
 use std::cell::RefCell;
 use std::cmp::Reverse;
 use std::collections::BinaryHeap;
+use std::path::Path;
 use std::sync::Arc;
 use tracing::{trace, trace_span, warn};
 
-use crate::cli::{DumpGraphArgs, GraphFilter, GraphFilterProperty, GraphFilterQuery, OutputFormat};
+use crate::cli::{
+    DumpGraphArgs, GraphFilter, GraphFilterProperty, GraphFilterQuery, OutputFormat, SbomFormat,
+};
 use crate::criteria::{CriteriaMapper, CriteriaSet};
-use crate::errors::SuggestError;
+use crate::errors::{
+    CratePolicyError, CratePolicyErrors, MissingEdgeOriginError, SourceFile, SuggestError,
+};
 use crate::format::{
-    self, AuditEntry, AuditKind, AuditsFile, CratesCacheUser, CratesPublisher, CriteriaName, Delta,
-    DiffStat, ExemptedDependency, FastMap, FastSet, ImportName, ImportsFile, JsonPackage,
-    JsonReport, JsonReportConclusion, JsonReportFailForVet, JsonReportFailForViolationConflict,
-    JsonReportSuccess, JsonSuggest, JsonSuggestItem, JsonVetFailure, PackageName, PackageStr,
-    Policy, UnpublishedEntry, VetVersion, WildcardEntry,
+    self, AuditEntry, AuditKind, AuditsFile, ConfigFile, CratesCacheUser, CratesPublisher,
+    CriteriaMap, CriteriaName, CycloneDxBom, CycloneDxComponent, CycloneDxDependency,
+    CycloneDxMetadata, CycloneDxProperty, CycloneDxTools, Delta, DiffStat, DiffWeights,
+    DisputedViolation, ExemptedDependency, FastMap, FastSet, FeatureConfig, FeatureConfigName,
+    ImportName, ImportsFile, JsonExplain, JsonExplainCriteria, JsonExplainEdge, JsonExplainPackage,
+    JsonExplainResult, JsonPackage, JsonReport, JsonReportConclusion, JsonReportFailForVet,
+    JsonReportFailForViolationConflict, JsonReportSuccess, JsonSuggest, JsonSuggestItem,
+    JsonVetFailure, JsonWhy, JsonWhyCriteria, JsonWhyOrigin, JsonWhyPackage, JsonWhyStep,
+    PackageName, PackageStr, Policy, SarifArtifactLocation, SarifDriver, SarifLocation, SarifLog,
+    SarifMessage, SarifPhysicalLocation, SarifRegion, SarifResult, SarifRule, SarifRun, SarifTool,
+    SpdxAnnotation, SpdxCreationInfo, SpdxDocument, SpdxExternalRef, SpdxPackage, SpdxRelationship,
+    TrustEntry, UnpublishedEntry, VetVersion, WildcardEntry,
 };
-use crate::format::{SortedMap, SortedSet};
+use crate::format::{RiskIndicator, SortedMap, SortedSet};
 use crate::network::Network;
 use crate::out::{progress_bar, IncProgressOnDrop, Out};
+use crate::serialization::spanned::Spanned;
 use crate::storage::Cache;
 use crate::string_format::FormatShortList;
 use crate::{Config, PackageExt, Store};
@@ -76,15 +92,39 @@ pub struct ResolveReport<'a> {
     /// Mappings between criteria names and CriteriaSets/Indices.
     pub criteria_mapper: CriteriaMapper,
 
+    /// The cfg values of the targets the graph was resolved for.
+    pub target_cfgs: TargetCfgs,
+
     /// Low-level results for each package's individual criteria resolving
     /// analysis, indexed by [`PackageIdx`][]. Will be `None` for first-party
     /// crates or crates with violation conflicts.
     pub results: Vec<Option<ResolveResult>>,
 
+    /// The criteria required for each package, indexed by [`PackageIdx`][].
+    pub requirements: Vec<CriteriaSet>,
+
+    /// How the requirements were placed on each package, in each feature
+    /// configuration, for `why`.
+    requirement_sources: Vec<RequirementSources>,
+
     /// The final conclusion of our analysis.
     pub conclusion: Conclusion,
+
+    /// Violations published by peers which weren't enforced because they've
+    /// been disputed, for each third-party package in the graph.
+    pub disputed_violations: SortedMap<PackageName, Vec<DisputedViolation>>,
+
+    /// With `check --baseline`, the failures which were ignored because the
+    /// package's version already failed to vet for those criteria in the
+    /// baseline.
+    pub baseline_failures: Option<Vec<(PackageIdx, AuditFailure)>>,
 }
 
+/// The criteria which each third-party package failed to vet for in a
+/// baseline, used by `check --baseline` to only report failures which are new
+/// since the baseline.
+pub type Baseline = FastMap<(PackageName, semver::Version), SortedSet<CriteriaName>>;
+
 #[derive(Debug)]
 pub enum Conclusion {
     Success(Success),
@@ -137,6 +177,7 @@ pub struct Suggest {
     pub suggestions: Vec<SuggestItem>,
     pub suggestions_by_criteria: SortedMap<CriteriaName, Vec<SuggestItem>>,
     pub total_lines: u64,
+    pub total_weighted_lines: u64,
     pub warnings: Vec<String>,
 }
 
@@ -164,6 +205,19 @@ pub struct DiffRecommendation {
     pub from: Option<VetVersion>,
     pub to: VetVersion,
     pub diffstat: DiffStat,
+    pub cost: DiffCost,
+    /// Patterns worth reviewing first which were introduced by the diff.
+    #[serde(skip)]
+    pub risks: Vec<RiskIndicator>,
+}
+
+/// The estimated cost of auditing a diff, in lines.
+#[derive(Debug, Clone, Serialize, Deserialize, Eq, PartialEq)]
+pub struct DiffCost {
+    /// Every changed line, as in the diffstat.
+    pub raw: u64,
+    /// The changed lines weighted by the store's `diff-weights`.
+    pub weighted: u64,
 }
 
 #[derive(Debug, Clone)]
@@ -202,6 +256,11 @@ pub struct PackageNode<'a> {
     pub normal_and_build_deps: Vec<PackageIdx>,
     /// All deps combined (deduplicated)
     pub all_deps: Vec<PackageIdx>,
+    /// The platforms (target triples or `cfg(...)` expressions) that normal
+    /// and build deps are conditional on, for deps which are only used on
+    /// some platforms
+    #[serde(skip_serializing_if = "SortedMap::is_empty")]
+    pub platform_deps: SortedMap<PackageIdx, Vec<String>>,
     /// All reverse-deps (mostly just used for contextualizing what uses it)
     pub reverse_deps: SortedSet<PackageIdx>,
     /// Whether this package is a workspace member (can have dev-deps)
@@ -308,6 +367,130 @@ pub enum DeltaEdgeOrigin {
     FreshExemption { version: VetVersion },
 }
 
+/// The entry in the store which an edge in the audit graph originates from.
+pub enum DeltaEdgeEntry<'a> {
+    /// A full or delta audit from `from` (or the root) to `to`, imported from
+    /// `import` if it isn't local.
+    Audit {
+        import: Option<&'a ImportName>,
+        entry: &'a AuditEntry,
+        from: Option<&'a VetVersion>,
+        to: &'a VetVersion,
+    },
+    /// A wildcard audit, imported from `import` if it isn't local, which
+    /// applies to the version published by `publisher`.
+    WildcardAudit {
+        import: Option<&'a ImportName>,
+        entry: &'a WildcardEntry,
+        publisher: &'a CratesPublisher,
+    },
+    /// A trusted entry which applies to the version published by `publisher`.
+    Trusted {
+        entry: &'a TrustEntry,
+        publisher: &'a CratesPublisher,
+    },
+    Exemption(&'a ExemptedDependency),
+    FreshExemption(&'a VetVersion),
+    Unpublished(&'a UnpublishedEntry),
+}
+
+impl DeltaEdgeOrigin {
+    /// Look up the entry in the store which this edge in `package`'s audit
+    /// graph originates from.
+    pub fn entry<'a>(
+        &'a self,
+        store: &'a Store,
+        package: PackageStr<'_>,
+    ) -> Result<DeltaEdgeEntry<'a>, MissingEdgeOriginError> {
+        let audits_file = |import_index: Option<usize>| match import_index {
+            Some(import_index) => store
+                .imported_audits()
+                .iter()
+                .nth(import_index)
+                .map(|(import_name, audits_file)| (Some(import_name), audits_file)),
+            None => Some((None, &store.audits)),
+        };
+        let publisher =
+            |publisher_index: usize| store.publishers().get(package)?.get(publisher_index);
+
+        let entry = match self {
+            DeltaEdgeOrigin::StoredLocalAudit { audit_index, .. } => {
+                audit_entry(None, &store.audits, package, *audit_index)
+            }
+            DeltaEdgeOrigin::ImportedAudit {
+                import_index,
+                audit_index,
+            } => audits_file(Some(*import_index)).and_then(|(import, audits_file)| {
+                audit_entry(import, audits_file, package, *audit_index)
+            }),
+            DeltaEdgeOrigin::WildcardAudit {
+                import_index,
+                audit_index,
+                publisher_index,
+            } => (|| {
+                let (import, audits_file) = audits_file(*import_index)?;
+                Some(DeltaEdgeEntry::WildcardAudit {
+                    import,
+                    entry: audits_file
+                        .wildcard_audits
+                        .get(package)?
+                        .get(*audit_index)?,
+                    publisher: publisher(*publisher_index)?,
+                })
+            })(),
+            DeltaEdgeOrigin::Trusted { publisher_index } => (|| {
+                let publisher = publisher(*publisher_index)?;
+                let entry = store.audits.trusted.get(package)?.iter().find(|entry| {
+                    entry.user_id == publisher.user_id
+                        && *entry.start <= publisher.when
+                        && publisher.when < *entry.end
+                })?;
+                Some(DeltaEdgeEntry::Trusted { entry, publisher })
+            })(),
+            DeltaEdgeOrigin::Exemption { exemption_index } => store
+                .config
+                .exemptions
+                .get(package)
+                .and_then(|exemptions| exemptions.get(*exemption_index))
+                .map(DeltaEdgeEntry::Exemption),
+            DeltaEdgeOrigin::FreshExemption { version } => {
+                Some(DeltaEdgeEntry::FreshExemption(version))
+            }
+            DeltaEdgeOrigin::Unpublished { unpublished_index } => store
+                .unpublished()
+                .get(package)
+                .and_then(|unpublished| unpublished.get(*unpublished_index))
+                .map(DeltaEdgeEntry::Unpublished),
+        };
+        entry.ok_or_else(|| MissingEdgeOriginError {
+            package: package.to_owned(),
+            origin: format!("{self:?}"),
+        })
+    }
+}
+
+/// The audit at `audit_index` for `package` in `audits_file`, unless it's a
+/// violation, which never forms an edge.
+fn audit_entry<'a>(
+    import: Option<&'a ImportName>,
+    audits_file: &'a AuditsFile,
+    package: PackageStr<'_>,
+    audit_index: usize,
+) -> Option<DeltaEdgeEntry<'a>> {
+    let entry = audits_file.audits.get(package)?.get(audit_index)?;
+    let (from, to) = match &entry.kind {
+        AuditKind::Full { version } => (None, version),
+        AuditKind::Delta { from, to } => (Some(from), to),
+        AuditKind::Violation { .. } => return None,
+    };
+    Some(DeltaEdgeEntry::Audit {
+        import,
+        entry,
+        from,
+        to,
+    })
+}
+
 /// An indication of a required local audit, imported entry, or exemption. Used to compute the
 /// minimal set of possible imports for imports.lock and for pruning unused audits and exemptions.
 #[derive(Debug, Clone, Eq, PartialEq, Ord, PartialOrd)]
@@ -440,6 +623,7 @@ impl<'a> DepGraph<'a> {
                 dev_deps: vec![],
                 normal_and_build_deps: vec![],
                 all_deps: vec![],
+                platform_deps: SortedMap::new(),
                 reverse_deps: SortedSet::new(),
                 is_workspace_member: false,
                 is_root: false,
@@ -558,6 +742,7 @@ impl<'a> DepGraph<'a> {
                         &[DependencyKind::Normal, DependencyKind::Build],
                         interner_by_pkgid,
                     );
+                    let platform_deps = platform_deps(resolve_node, interner_by_pkgid);
 
                     // Now visit all the normal and build deps
                     for &child in &normal_and_build_deps {
@@ -582,6 +767,7 @@ impl<'a> DepGraph<'a> {
                     cur_node.normal_deps = normal_deps;
                     cur_node.normal_and_build_deps = normal_and_build_deps;
                     cur_node.all_deps = all_deps;
+                    cur_node.platform_deps = platform_deps;
 
                     // dev-deps will be handled in a second pass
                 }
@@ -605,6 +791,36 @@ impl<'a> DepGraph<'a> {
                     .map(|dep| interner_by_pkgid[&dep.pkg])
                     .collect()
             }
+            fn platform_deps(
+                resolve_node: &Node,
+                interner_by_pkgid: &SortedMap<&PackageId, PackageIdx>,
+            ) -> SortedMap<PackageIdx, Vec<String>> {
+                // A dep is only platform-specific if every normal and build
+                // edge to it has a target.
+                resolve_node
+                    .deps
+                    .iter()
+                    .filter_map(|dep| {
+                        let mut platforms = Vec::new();
+                        for dep_kind in &dep.dep_kinds {
+                            if !matches!(
+                                dep_kind.kind,
+                                DependencyKind::Normal | DependencyKind::Build
+                            ) {
+                                continue;
+                            }
+                            let platform = dep_kind.target.as_ref()?.to_string();
+                            if !platforms.contains(&platform) {
+                                platforms.push(platform);
+                            }
+                        }
+                        if platforms.is_empty() {
+                            return None;
+                        }
+                        Some((interner_by_pkgid[&dep.pkg], platforms))
+                    })
+                    .collect()
+            }
         }
 
         let result = Self {
@@ -709,6 +925,7 @@ impl<'a> DepGraph<'a> {
                 dev_deps: vec![],
                 normal_and_build_deps: vec![],
                 all_deps: vec![],
+                platform_deps: SortedMap::new(),
                 reverse_deps: SortedSet::new(),
                 is_workspace_member: package.is_workspace_member,
                 is_third_party: package.is_third_party,
@@ -750,6 +967,11 @@ impl<'a> DepGraph<'a> {
                         new_package.all_deps.push(new_dep);
                     }
                 }
+                for (old_dep, platforms) in &old_package.platform_deps {
+                    if let Some(&new_dep) = old_to_new.get(old_dep) {
+                        new_package.platform_deps.insert(new_dep, platforms.clone());
+                    }
+                }
                 for old_dep in &old_package.reverse_deps {
                     if let Some(&new_dep) = old_to_new.get(old_dep) {
                         new_package.reverse_deps.insert(new_dep);
@@ -865,6 +1087,43 @@ pub fn resolve<'a>(
     metadata: &'a Metadata,
     filter_graph: Option<&Vec<GraphFilter>>,
     store: &Store,
+) -> ResolveReport<'a> {
+    resolve_with_baseline(
+        metadata,
+        &SortedMap::new(),
+        &SortedMap::new(),
+        filter_graph,
+        store,
+        None,
+    )
+}
+
+/// Resolve the dependency graph of `cfg`, taking its feature configurations
+/// into account.
+pub fn resolve_config<'a>(cfg: &'a Config, store: &Store) -> ResolveReport<'a> {
+    resolve_with_baseline(
+        &cfg.metadata,
+        &cfg.feature_metadata,
+        &cfg.target_cfgs,
+        cfg.cli.filter_graph.as_ref(),
+        store,
+        None,
+    )
+}
+
+/// Like [`resolve`], but failures for packages whose version already failed
+/// to vet for the same criteria in the `baseline` are set aside in
+/// [`ResolveReport::baseline_failures`] rather than failing the check.
+///
+/// If `feature_metadata` isn't empty, `metadata` must be the union of the
+/// graphs of the feature configurations it contains.
+pub fn resolve_with_baseline<'a>(
+    metadata: &'a Metadata,
+    feature_metadata: &SortedMap<FeatureConfigName, Metadata>,
+    target_cfgs: &TargetCfgs,
+    filter_graph: Option<&Vec<GraphFilter>>,
+    store: &Store,
+    baseline: Option<&Baseline>,
 ) -> ResolveReport<'a> {
     // A large part of our algorithm is unioning and intersecting criteria, so we map all
     // the criteria into indexed boolean sets (*whispers* an integer with lots of bits).
@@ -875,48 +1134,357 @@ pub fn resolve<'a>(
     let criteria_mapper = CriteriaMapper::new(&store.audits.criteria);
     trace!("built CriteriaMapper!");
 
-    let requirements = resolve_requirements(&graph, &store.config.policy, &criteria_mapper);
+    let (requirements, requirement_sources) = resolve_feature_requirements(
+        &graph,
+        feature_metadata,
+        target_cfgs,
+        filter_graph,
+        &store.config,
+        &criteria_mapper,
+    );
+
+    let (results, conclusion, baseline_failures) =
+        resolve_audits(&graph, store, &criteria_mapper, &requirements, baseline);
 
-    let (results, conclusion) = resolve_audits(&graph, store, &criteria_mapper, &requirements);
+    let mut disputed_violations = SortedMap::new();
+    for node in graph.nodes.iter().filter(|node| node.is_third_party) {
+        let disputed = disputed_violations_for(store, node.name, None);
+        if !disputed.is_empty() {
+            disputed_violations.insert(node.name.to_owned(), disputed);
+        }
+    }
 
     ResolveReport {
         graph,
         criteria_mapper,
+        target_cfgs: target_cfgs.clone(),
         results,
+        requirements,
+        requirement_sources,
         conclusion,
+        disputed_violations,
+        baseline_failures,
+    }
+}
+
+/// Resolve the graph of a baseline against the store of that baseline, to find
+/// which criteria each package already failed to vet for.
+pub fn resolve_baseline(
+    metadata: &Metadata,
+    feature_metadata: &SortedMap<FeatureConfigName, Metadata>,
+    target_cfgs: &TargetCfgs,
+    filter_graph: Option<&Vec<GraphFilter>>,
+    store: &Store,
+) -> Baseline {
+    let graph = DepGraph::new(metadata, filter_graph, Some(&store.config.policy));
+    let criteria_mapper = CriteriaMapper::new(&store.audits.criteria);
+    let (requirements, _) = resolve_feature_requirements(
+        &graph,
+        feature_metadata,
+        target_cfgs,
+        filter_graph,
+        &store.config,
+        &criteria_mapper,
+    );
+    let (results, _, _) = resolve_audits(&graph, store, &criteria_mapper, &requirements, None);
+
+    let mut baseline = Baseline::new();
+    for ((package, required_criteria), result) in
+        graph.nodes.iter().zip(&requirements).zip(&results)
+    {
+        if !package.is_third_party {
+            continue;
+        }
+        // Packages with violation conflicts have no results, and fail to vet
+        // for all of their criteria.
+        let failed: SortedSet<CriteriaName> = required_criteria
+            .indices()
+            .filter(|&criteria_idx| {
+                result
+                    .as_ref()
+                    .map_or(true, |result| result.search_results[criteria_idx].is_err())
+            })
+            .map(|criteria_idx| criteria_mapper.criteria_name(criteria_idx).to_owned())
+            .collect();
+        if !failed.is_empty() {
+            baseline
+                .entry((package.name.to_owned(), package.version.semver.clone()))
+                .or_default()
+                .extend(failed);
+        }
+    }
+    baseline
+}
+
+/// The criteria which entries imported from `import_name` may grant, if the
+/// import has a `max-criteria` limit.
+fn import_criteria_ceiling(
+    store: &Store,
+    criteria_mapper: &CriteriaMapper,
+    import_name: &str,
+) -> Option<CriteriaSet> {
+    let import = store.config.imports.get(import_name)?;
+    if import.max_criteria.is_empty() {
+        return None;
+    }
+    Some(criteria_mapper.criteria_from_list(&import.max_criteria))
+}
+
+/// Describe the disputed violations of `package`, optionally limited to those
+/// which apply to `version`.
+fn disputed_violations_for(
+    store: &Store,
+    package: PackageStr<'_>,
+    version: Option<&VetVersion>,
+) -> Vec<DisputedViolation> {
+    store
+        .disputed_violations(package)
+        .into_iter()
+        .filter(|(_, _, dispute)| {
+            version.map_or(true, |version| dispute.violation.matches(version))
+        })
+        .map(|(import, entry, dispute)| DisputedViolation {
+            import: import.clone(),
+            violation: dispute.violation.clone(),
+            criteria: entry.criteria.iter().map(|c| c.to_string()).collect(),
+            notes: dispute.notes.clone(),
+        })
+        .collect()
+}
+
+/// The `rustc --print cfg` values of each target triple the graph is filtered
+/// to, which `platform-criteria` are evaluated against.
+pub type TargetCfgs = SortedMap<String, Vec<cargo_platform::Cfg>>;
+
+/// The `platform-criteria` required of a dependency which is only used on
+/// `platforms`.
+///
+/// Without any `target_cfgs`, the platforms are matched textually against the
+/// keys, and every one of the platforms needs an entry. Otherwise each target
+/// the dependency is used on needs an entry which applies to it, and the
+/// criteria of all of them are required. If any are missing, the dependent's
+/// own criteria apply.
+fn platform_criteria(
+    platform_criteria: &CriteriaMap,
+    target_cfgs: &TargetCfgs,
+    platforms: Option<&Vec<String>>,
+) -> Option<Vec<CriteriaName>> {
+    let platforms = platforms?;
+    let mut criteria = Vec::new();
+    if target_cfgs.is_empty() {
+        for platform in platforms {
+            let entry = platform_criteria.get(platform.as_str())?;
+            criteria.extend(entry.iter().map(|c| c.to_string()));
+        }
+        return Some(criteria);
+    }
+
+    let parse = |platform: &str| platform.parse::<cargo_platform::Platform>().ok();
+    let platforms = platforms
+        .iter()
+        .map(|platform| parse(platform))
+        .collect::<Option<Vec<_>>>()?;
+    let mut used = false;
+    for (target, cfgs) in target_cfgs {
+        if !platforms.iter().any(|p| p.matches(target, cfgs)) {
+            continue;
+        }
+        used = true;
+        let mut found = false;
+        for (key, entry) in platform_criteria {
+            if parse(key).is_some_and(|key| key.matches(target, cfgs)) {
+                found = true;
+                criteria.extend(entry.iter().map(|c| c.to_string()));
+            }
+        }
+        if !found {
+            return None;
+        }
+    }
+    criteria.sort();
+    criteria.dedup();
+    used.then_some(criteria)
+}
+
+/// How a requirement was placed on a package while resolving requirements.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum RequirementReason {
+    /// The default criteria of a root, or those of its feature configuration.
+    Root,
+    /// The `criteria` of the package's own policy entry.
+    Criteria,
+    /// The requirements of a dependent, passed down to its normal or build
+    /// dependency.
+    Inherited,
+    /// The `dependency-criteria` of a dependent's policy entry.
+    DependencyCriteria,
+    /// The `platform-criteria` matching a platform-specific dependency.
+    PlatformCriteria,
+    /// The `dev-criteria` of a dependent's policy entry.
+    DevCriteria,
+    /// The default criteria of dev-dependencies.
+    DefaultDevCriteria,
+}
+
+impl RequirementReason {
+    fn name(self) -> &'static str {
+        match self {
+            RequirementReason::Root => "root",
+            RequirementReason::Criteria => "criteria",
+            RequirementReason::Inherited => "inherited",
+            RequirementReason::DependencyCriteria => "dependency-criteria",
+            RequirementReason::PlatformCriteria => "platform-criteria",
+            RequirementReason::DevCriteria => "dev-criteria",
+            RequirementReason::DefaultDevCriteria => "default-dev-criteria",
+        }
+    }
+}
+
+/// A requirement placed on a package while resolving requirements, recorded
+/// so that `why` can trace it back to where it was introduced.
+#[derive(Debug, Clone)]
+struct RequirementSource {
+    reason: RequirementReason,
+    /// The dependent which placed the requirement and the kind of dependency
+    /// it was placed along, or `None` if the package placed it on itself.
+    dependent: Option<(PackageIdx, &'static str)>,
+    criteria: CriteriaSet,
+}
+
+/// The sources of the requirements placed on each package in one feature
+/// configuration, or in the whole graph if there are none, indexed by
+/// [`PackageIdx`][] of the resolved graph.
+#[derive(Debug, Clone)]
+struct RequirementSources {
+    feature_config: Option<FeatureConfigName>,
+    sources: Vec<Vec<RequirementSource>>,
+}
+
+/// Compute the requirements of each package in `graph`. With feature
+/// configurations, the graph of each configuration is resolved separately,
+/// and a package requires the criteria of every configuration it appears in.
+fn resolve_feature_requirements(
+    graph: &DepGraph<'_>,
+    feature_metadata: &SortedMap<FeatureConfigName, Metadata>,
+    target_cfgs: &TargetCfgs,
+    filter_graph: Option<&Vec<GraphFilter>>,
+    config: &ConfigFile,
+    criteria_mapper: &CriteriaMapper,
+) -> (Vec<CriteriaSet>, Vec<RequirementSources>) {
+    if feature_metadata.is_empty() {
+        let (requirements, sources) =
+            resolve_requirements(graph, config, target_cfgs, criteria_mapper, None);
+        let sources = RequirementSources {
+            feature_config: None,
+            sources,
+        };
+        return (requirements, vec![sources]);
+    }
+
+    let mut requirements = vec![criteria_mapper.no_criteria(); graph.nodes.len()];
+    let mut all_sources = Vec::with_capacity(feature_metadata.len());
+    for (name, metadata) in feature_metadata {
+        let feature_graph = DepGraph::new(metadata, filter_graph, Some(&config.policy));
+        let (feature_requirements, feature_sources) = resolve_requirements(
+            &feature_graph,
+            config,
+            target_cfgs,
+            criteria_mapper,
+            config.feature_configs.get(name),
+        );
+
+        // Map the configuration's graph onto the resolved graph.
+        let pkgidxs: Vec<_> = feature_graph
+            .nodes
+            .iter()
+            .map(|node| graph.interner_by_pkgid.get(node.package_id).copied())
+            .collect();
+        let mut sources = vec![vec![]; graph.nodes.len()];
+        for ((pkgidx, required), feature_sources) in pkgidxs
+            .iter()
+            .zip(&feature_requirements)
+            .zip(feature_sources)
+        {
+            let Some(pkgidx) = *pkgidx else { continue };
+            requirements[pkgidx].unioned_with(required);
+            sources[pkgidx] = feature_sources
+                .into_iter()
+                .filter_map(|source| {
+                    let dependent = match source.dependent {
+                        Some((depidx, kind)) => Some((pkgidxs[depidx]?, kind)),
+                        None => None,
+                    };
+                    Some(RequirementSource {
+                        dependent,
+                        ..source
+                    })
+                })
+                .collect();
+        }
+        all_sources.push(RequirementSources {
+            feature_config: Some(name.clone()),
+            sources,
+        });
     }
+    (requirements, all_sources)
 }
 
 fn resolve_requirements(
     graph: &DepGraph<'_>,
-    policy: &Policy,
+    config: &ConfigFile,
+    target_cfgs: &TargetCfgs,
     criteria_mapper: &CriteriaMapper,
-) -> Vec<CriteriaSet> {
+    feature_config: Option<&FeatureConfig>,
+) -> (Vec<CriteriaSet>, Vec<Vec<RequirementSource>>) {
+    let policy = &config.policy;
+    // Workspace members which aren't built in the feature configuration don't
+    // place any requirements on their dependencies.
+    let is_excluded = |package: &PackageNode<'_>| {
+        package.is_workspace_member
+            && feature_config.is_some_and(|feature_config| !feature_config.includes(package.name))
+    };
     let _resolve_requirements = trace_span!("resolve_requirements").entered();
 
     let mut requirements = vec![criteria_mapper.no_criteria(); graph.nodes.len()];
+    let mut sources: Vec<Vec<RequirementSource>> = vec![vec![]; graph.nodes.len()];
 
     // For any packages which have dev-dependencies, apply policy-specified
     // dependency-criteria or dev-criteria to those dependencies.
-    for package in &graph.nodes {
-        if package.dev_deps.is_empty() {
+    for (pkgidx, package) in graph.nodes.iter().enumerate() {
+        if package.dev_deps.is_empty() || is_excluded(package) {
             continue;
         }
 
         let policy = policy.get(package.name, &package.version);
         let dev_criteria = if let Some(c) = policy.and_then(|p| p.dev_criteria.as_ref()) {
-            criteria_mapper.criteria_from_list(c)
+            (
+                RequirementReason::DevCriteria,
+                criteria_mapper.criteria_from_list(c),
+            )
         } else {
-            criteria_mapper.criteria_from_list([format::DEFAULT_POLICY_DEV_CRITERIA])
+            (
+                RequirementReason::DefaultDevCriteria,
+                criteria_mapper.criteria_from_list([format::DEFAULT_POLICY_DEV_CRITERIA]),
+            )
         };
 
         for &depidx in &package.dev_deps {
             let dep_package = &graph.nodes[depidx];
             let dependency_criteria = policy
                 .and_then(|policy| policy.dependency_criteria.get(dep_package.name))
-                .map(|criteria| criteria_mapper.criteria_from_list(criteria));
-            requirements[depidx]
-                .unioned_with(dependency_criteria.as_ref().unwrap_or(&dev_criteria));
+                .map(|criteria| {
+                    (
+                        RequirementReason::DependencyCriteria,
+                        criteria_mapper.criteria_from_list(criteria),
+                    )
+                });
+            let (reason, criteria) = dependency_criteria.as_ref().unwrap_or(&dev_criteria);
+            requirements[depidx].unioned_with(criteria);
+            sources[depidx].push(RequirementSource {
+                reason: *reason,
+                dependent: Some((pkgidx, "dev")),
+                criteria: criteria.clone(),
+            });
         }
     }
 
@@ -926,16 +1494,37 @@ fn resolve_requirements(
         let package = &graph.nodes[pkgidx];
         let policy = policy.get(package.name, &package.version);
 
+        // Excluded members are only built if another package in the feature
+        // configuration depends on them, and otherwise neither their policy
+        // nor their dependencies need anything.
+        if is_excluded(package) && requirements[pkgidx].is_empty() {
+            continue;
+        }
+
         if let Some(c) = policy.and_then(|p| p.criteria.as_ref()) {
             // If we specify a policy on ourselves, override any requirements we've
             // had placed on us by reverse-dependencies.
             requirements[pkgidx] = criteria_mapper.criteria_from_list(c);
-        } else if package.is_root {
+            sources[pkgidx] = vec![RequirementSource {
+                reason: RequirementReason::Criteria,
+                dependent: None,
+                criteria: requirements[pkgidx].clone(),
+            }];
+        } else if package.is_root && !is_excluded(package) {
             // If this is a root crate, it will require at least
-            // `DEFAULT_POLICY_CRITERIA` by default, unless overridden.
-            requirements[pkgidx].unioned_with(
-                &criteria_mapper.criteria_from_list([format::DEFAULT_POLICY_CRITERIA]),
-            );
+            // `DEFAULT_POLICY_CRITERIA` by default, unless overridden by the
+            // feature configuration.
+            let criteria =
+                match feature_config.and_then(|feature_config| feature_config.criteria.as_ref()) {
+                    Some(c) => criteria_mapper.criteria_from_list(c),
+                    None => criteria_mapper.criteria_from_list([format::DEFAULT_POLICY_CRITERIA]),
+                };
+            requirements[pkgidx].unioned_with(&criteria);
+            sources[pkgidx].push(RequirementSource {
+                reason: RequirementReason::Root,
+                dependent: None,
+                criteria,
+            });
         }
         let normal_criteria = requirements[pkgidx].clone();
 
@@ -944,24 +1533,139 @@ fn resolve_requirements(
             let dep_package = &graph.nodes[depidx];
             let dependency_criteria = policy
                 .and_then(|policy| policy.dependency_criteria.get(dep_package.name))
-                .map(|criteria| criteria_mapper.criteria_from_list(criteria));
-            requirements[depidx]
-                .unioned_with(dependency_criteria.as_ref().unwrap_or(&normal_criteria));
+                .map(|criteria| {
+                    (
+                        RequirementReason::DependencyCriteria,
+                        criteria_mapper.criteria_from_list(criteria),
+                    )
+                })
+                .or_else(|| {
+                    platform_criteria(
+                        &config.platform_criteria,
+                        target_cfgs,
+                        package.platform_deps.get(&depidx),
+                    )
+                    .map(|criteria| {
+                        (
+                            RequirementReason::PlatformCriteria,
+                            criteria_mapper.criteria_from_list(&criteria),
+                        )
+                    })
+                });
+            let (reason, criteria) = dependency_criteria
+                .unwrap_or_else(|| (RequirementReason::Inherited, normal_criteria.clone()));
+            requirements[depidx].unioned_with(&criteria);
+            let kind = if package.normal_deps.contains(&depidx) {
+                "normal"
+            } else {
+                "build"
+            };
+            sources[depidx].push(RequirementSource {
+                reason,
+                dependent: Some((pkgidx, kind)),
+                criteria,
+            });
+        }
+    }
+
+    (requirements, sources)
+}
+
+/// Find the roots and policy entries which cause the package to require the
+/// given criteria, by following the requirement sources recorded by
+/// `resolve_requirements` back up the graph. Each origin is found with the
+/// shortest dependency path from it to the package.
+fn requirement_origins(
+    graph: &DepGraph<'_>,
+    criteria_mapper: &CriteriaMapper,
+    sources: &RequirementSources,
+    pkgidx: PackageIdx,
+    criteria_idx: usize,
+) -> Vec<JsonWhyOrigin> {
+    let step = |depidx: PackageIdx, kind: &str| {
+        let dep = &graph.nodes[depidx];
+        JsonWhyStep {
+            name: dep.name.to_owned(),
+            version: dep.version.clone(),
+            kind: kind.to_owned(),
+        }
+    };
+
+    // Breadth-first search from the package towards the roots, along the
+    // edges which passed the requirement down from a dependent.
+    let mut origins = Vec::new();
+    let mut visited = FastSet::new();
+    let mut queue = std::collections::VecDeque::from([(pkgidx, Vec::new())]);
+    while let Some((pkgidx, path)) = queue.pop_front() {
+        if !visited.insert(pkgidx) {
+            continue;
+        }
+
+        // Report the package's own requirements before those from its
+        // dependents.
+        let (own, inherited): (Vec<_>, Vec<_>) = sources.sources[pkgidx]
+            .iter()
+            .filter(|source| source.criteria.has_criteria(criteria_idx))
+            .partition(|source| source.dependent.is_none());
+        for source in own.into_iter().chain(inherited) {
+            let (origin_idx, path) = match source.dependent {
+                Some((parentidx, kind)) => {
+                    let mut path = path.clone();
+                    path.insert(0, step(pkgidx, kind));
+                    (parentidx, path)
+                }
+                None => (pkgidx, path.clone()),
+            };
+            if source.reason == RequirementReason::Inherited {
+                queue.push_back((origin_idx, path));
+                continue;
+            }
+            let package = &graph.nodes[origin_idx];
+            origins.push(JsonWhyOrigin {
+                reason: source.reason.name().to_owned(),
+                package: JsonPackage {
+                    name: package.name.to_owned(),
+                    version: package.version.clone(),
+                },
+                criteria: criteria_mapper
+                    .criteria_names(&source.criteria)
+                    .map(|name| name.to_owned())
+                    .collect(),
+                path,
+                feature_config: sources.feature_config.clone(),
+            });
         }
     }
 
-    requirements
+    // Cycles in the graph can lead back to an origin along a longer path,
+    // so only keep the first, and shortest, path to each origin.
+    let mut seen = FastSet::new();
+    origins.retain(|origin| {
+        seen.insert((
+            origin.reason.clone(),
+            origin.package.name.clone(),
+            origin.package.version.clone(),
+        ))
+    });
+    origins
 }
 
+#[allow(clippy::type_complexity)]
 fn resolve_audits(
     graph: &DepGraph<'_>,
     store: &Store,
     criteria_mapper: &CriteriaMapper,
     requirements: &[CriteriaSet],
-) -> (Vec<Option<ResolveResult>>, Conclusion) {
+    baseline: Option<&Baseline>,
+) -> (
+    Vec<Option<ResolveResult>>,
+    Conclusion,
+    Option<Vec<(PackageIdx, AuditFailure)>>,
+) {
     let _resolve_audits = trace_span!("resolve_audits").entered();
     let mut violations = Vec::new();
     let mut failures = Vec::new();
+    let mut baseline_failures = baseline.map(|_| Vec::new());
     let mut vetted_with_exemptions = Vec::new();
     let mut vetted_partially = Vec::new();
     let mut vetted_fully = Vec::new();
@@ -1012,7 +1716,28 @@ fn resolve_audits(
             }
 
             if !criteria_failures.is_empty() {
-                failures.push((pkgidx, AuditFailure { criteria_failures }));
+                // Only failures for criteria which the package already failed
+                // to vet for in the baseline are ignored, so removing audits or
+                // requiring more of a package is still reported.
+                let in_baseline = baseline.is_some_and(|baseline| {
+                    baseline
+                        .get(&(package.name.to_owned(), package.version.semver.clone()))
+                        .is_some_and(|failed| {
+                            criteria_failures.indices().all(|criteria_idx| {
+                                failed.contains(criteria_mapper.criteria_name(criteria_idx))
+                            })
+                        })
+                });
+                let failure = (pkgidx, AuditFailure { criteria_failures });
+                match &mut baseline_failures {
+                    // Packages which already failed in the baseline aren't
+                    // counted as vetted if the check otherwise succeeds.
+                    Some(baseline_failures) if in_baseline => {
+                        baseline_failures.push(failure);
+                        return Some(ResolveResult { search_results });
+                    }
+                    _ => failures.push(failure),
+                }
             }
 
             // XXX: Callers using these fields in success should perhaps be
@@ -1044,7 +1769,7 @@ fn resolve_audits(
         })
     };
 
-    (results, conclusion)
+    (results, conclusion, baseline_failures)
 }
 
 impl<'a> AuditGraph<'a> {
@@ -1155,6 +1880,32 @@ impl<'a> AuditGraph<'a> {
 
         let exemptions = store.config.exemptions.get(package);
 
+        // Stale full audits no longer satisfy the criteria listed in the
+        // `stale-audits` policy, or any criteria which imply them.
+        let mut stale_criteria = criteria_mapper.no_criteria();
+        for criteria in store.config.stale_audits.iter().flat_map(|p| &p.criteria) {
+            let criteria_idx = criteria_mapper.criteria_index(criteria);
+            stale_criteria.set_criteria(criteria_idx);
+            for implied_by in criteria_mapper.implied_by_indices(criteria_idx) {
+                stale_criteria.set_criteria(implied_by);
+            }
+        }
+
+        let disputes = store.config.disputes.get(package);
+
+        // Entries imported from a peer only grant the criteria allowed by the
+        // import's `max-criteria`, indexed by import.
+        let import_ceilings: Vec<Option<CriteriaSet>> = store
+            .imported_audits()
+            .keys()
+            .map(|import_name| import_criteria_ceiling(store, criteria_mapper, import_name))
+            .collect();
+        let cap_criteria = |import_index: Option<usize>, criteria: &mut CriteriaSet| {
+            if let Some(ceiling) = import_index.and_then(|idx| import_ceilings[idx].as_ref()) {
+                criteria.intersected_with(ceiling);
+            }
+        };
+
         let mut forward_audits = DirectedAuditGraph::new();
         let mut backward_audits = DirectedAuditGraph::new();
         let mut violation_nodes = Vec::new();
@@ -1166,12 +1917,25 @@ impl<'a> AuditGraph<'a> {
                 AuditKind::Full { version } => (None, version),
                 AuditKind::Delta { from, to } => (Some(from), to),
                 AuditKind::Violation { .. } => {
-                    violation_nodes.push((namespace.clone(), entry));
+                    // Violations from peers which we've disputed aren't
+                    // enforced.
+                    let disputed = namespace.as_ref().is_some_and(|import| {
+                        disputes.is_some_and(|d| d.iter().any(|d| d.matches(import, entry)))
+                    });
+                    if !disputed {
+                        violation_nodes.push((namespace.clone(), entry));
+                    }
                     continue;
                 }
             };
 
-            let criteria = criteria_mapper.criteria_from_list(&entry.criteria);
+            let mut criteria = criteria_mapper.criteria_from_list(&entry.criteria);
+            if entry.is_stale {
+                criteria.clear_criteria(&stale_criteria);
+            }
+            if let DeltaEdgeOrigin::ImportedAudit { import_index, .. } = origin {
+                cap_criteria(Some(import_index), &mut criteria);
+            }
             let freshness = DeltaEdgeFreshness::new(entry.is_fresh_import, false);
 
             forward_audits.entry(from_ver).or_default().push(DeltaEdge {
@@ -1202,7 +1966,8 @@ impl<'a> AuditGraph<'a> {
                 {
                     let from_ver = None;
                     let to_ver = Some(&publisher.version);
-                    let criteria = criteria_mapper.criteria_from_list(&entry.criteria);
+                    let mut criteria = criteria_mapper.criteria_from_list(&entry.criteria);
+                    cap_criteria(import_index, &mut criteria);
                     let origin = DeltaEdgeOrigin::WildcardAudit {
                         import_index,
                         audit_index,
@@ -1279,9 +2044,12 @@ impl<'a> AuditGraph<'a> {
             });
         }
 
-        // Exempted entries are equivalent to full-audits
+        // Exempted entries are equivalent to full-audits, until they expire.
         if let Some(alloweds) = exemptions {
             for (exemption_index, allowed) in alloweds.iter().enumerate() {
+                if allowed.is_expired {
+                    continue;
+                }
                 let from_ver = None;
                 let to_ver = Some(&allowed.version);
                 let criteria = criteria_mapper.criteria_from_list(&allowed.criteria);
@@ -1758,6 +2526,8 @@ impl ResolveReport<'_> {
                             .map(|criteria_idx| {
                                 result.search_results[criteria_idx].as_ref().unwrap_err()
                             }),
+                        &stale_audit_versions(&store, package.name),
+                        &store.config.diff_weights,
                         &warnings,
                     )
                     .await
@@ -1879,12 +2649,13 @@ impl ResolveReport<'_> {
                                 package.name,
                                 target_version,
                                 failures.iter(),
+                                &[],
+                                &store.config.diff_weights,
                                 &warnings,
                             )
                             .await?;
 
-                            if registry_suggested_diff.diffstat.count()
-                                < suggested_diff.diffstat.count()
+                            if registry_suggested_diff.cost.weighted < suggested_diff.cost.weighted
                             {
                                 Some(RegistrySuggestion {
                                     name: name.clone(),
@@ -1900,7 +2671,7 @@ impl ResolveReport<'_> {
                     .into_iter()
                     .flatten()
                     .collect();
-                    registry_suggestion.sort_by_key(|suggestion| suggestion.diff.diffstat.count());
+                    registry_suggestion.sort_by_key(|suggestion| suggestion.diff.cost.weighted);
 
                     extra_suggested_diff
                         .into_iter()
@@ -1931,11 +2702,11 @@ impl ResolveReport<'_> {
             .flatten()
             .collect::<Vec<_>>();
 
-        // First sort by diff size (ascending), then package name, then version
-        // being certified, to have stable output ordering.
+        // First sort by weighted diff size (ascending), then package name, then
+        // version being certified, to have stable output ordering.
         suggestions.sort_by_key(|item| {
             (
-                item.suggested_diff.diffstat.count(),
+                item.suggested_diff.cost.weighted,
                 self.graph.nodes[item.package].name,
                 item.suggested_diff.to.clone(),
             )
@@ -1963,9 +2734,10 @@ impl ResolveReport<'_> {
             s.notable_parents.dedup();
         }
 
-        let total_lines = suggestions
+        let total_lines = suggestions.iter().map(|s| s.suggested_diff.cost.raw).sum();
+        let total_weighted_lines = suggestions
             .iter()
-            .map(|s| s.suggested_diff.diffstat.count())
+            .map(|s| s.suggested_diff.cost.weighted)
             .sum();
 
         let mut suggestions_by_criteria = SortedMap::<CriteriaName, Vec<SuggestItem>>::new();
@@ -2003,6 +2775,7 @@ impl ResolveReport<'_> {
             suggestions,
             suggestions_by_criteria,
             total_lines,
+            total_weighted_lines,
             warnings: warnings.into_inner(),
         }))
     }
@@ -2071,41 +2844,128 @@ impl ResolveReport<'_> {
         suggest: Option<&Suggest>,
     ) -> Result<(), std::io::Error> {
         match &self.conclusion {
-            Conclusion::Success(res) => res.print_human(out, self, cfg),
-            Conclusion::FailForViolationConflict(res) => res.print_human(out, self, cfg),
-            Conclusion::FailForVet(res) => res.print_human(out, self, cfg, suggest),
+            Conclusion::Success(res) => res.print_human(out, self, cfg)?,
+            Conclusion::FailForViolationConflict(res) => res.print_human(out, self, cfg)?,
+            Conclusion::FailForVet(res) => res.print_human(out, self, cfg, suggest)?,
         }
-    }
 
-    /// Print only the suggest portion of a human-readable report
-    pub fn print_suggest_human(
-        &self,
-        out: &Arc<dyn Out>,
-        _cfg: &Config,
-        suggest: Option<&Suggest>,
-    ) -> Result<(), std::io::Error> {
-        if let Some(suggest) = suggest {
-            suggest.print_human(out, self)?;
-        } else {
-            // This API is only used for vet-suggest
-            writeln!(out, "Nothing to suggest, you're fully audited!");
+        if !self.disputed_violations.is_empty() {
+            writeln!(out);
+            writeln!(out, "Ignoring disputed violations:");
+            for (package, disputed) in &self.disputed_violations {
+                for violation in disputed {
+                    writeln!(
+                        out,
+                        "  {package} violation against {} ({}) from {}",
+                        violation.violation,
+                        violation.criteria.join(", "),
+                        violation.import
+                    );
+                    writeln!(out, "    notes: {}", violation.notes);
+                }
+            }
+        }
+
+        if let Some(baseline_failures) = &self.baseline_failures {
+            self.print_baseline_summary(out, baseline_failures, suggest);
         }
         Ok(())
     }
 
-    /// Print a full json report
-    pub fn print_json(
+    /// Summarize the cost of a change compared to the `check --baseline`, and
+    /// the failures which were ignored because they predate it.
+    fn print_baseline_summary(
         &self,
         out: &Arc<dyn Out>,
+        baseline_failures: &[(PackageIdx, AuditFailure)],
         suggest: Option<&Suggest>,
-    ) -> Result<(), miette::Report> {
-        let result = JsonReport {
-            conclusion: match &self.conclusion {
-                Conclusion::Success(success) => {
-                    let json_package = |pkgidx: &PackageIdx| {
-                        let package = &self.graph.nodes[*pkgidx];
-                        JsonPackage {
-                            name: package.name.to_owned(),
+    ) {
+        writeln!(out);
+        match (&self.conclusion, suggest) {
+            (Conclusion::FailForVet(fail), Some(suggest)) => {
+                write!(
+                    out,
+                    "Cost of this change: {} packages which newly fail to vet, estimated at {} lines",
+                    fail.failures.len(),
+                    suggest.total_lines
+                );
+                if suggest.total_weighted_lines != suggest.total_lines {
+                    write!(out, " ({} weighted)", suggest.total_weighted_lines);
+                }
+                writeln!(out);
+            }
+            (Conclusion::FailForVet(fail), None) => {
+                writeln!(
+                    out,
+                    "Cost of this change: {} packages which newly fail to vet",
+                    fail.failures.len()
+                );
+            }
+            (Conclusion::FailForViolationConflict(_), _) => writeln!(
+                out,
+                "Cost of this change: unknown until the violation conflicts are resolved"
+            ),
+            (Conclusion::Success(_), _) => writeln!(out, "Cost of this change: nothing new to vet"),
+        }
+
+        if !baseline_failures.is_empty() {
+            let packages = baseline_failures
+                .iter()
+                .map(|(pkgidx, _)| {
+                    let package = &self.graph.nodes[*pkgidx];
+                    format!("{}:{}", package.name, package.version)
+                })
+                .collect::<Vec<_>>();
+            writeln!(
+                out,
+                "Ignoring {} packages which already failed to vet in the baseline: {}",
+                packages.len(),
+                FormatShortList::new(packages)
+            );
+        }
+    }
+
+    /// Print only the suggest portion of a human-readable report
+    pub fn print_suggest_human(
+        &self,
+        out: &Arc<dyn Out>,
+        _cfg: &Config,
+        suggest: Option<&Suggest>,
+    ) -> Result<(), std::io::Error> {
+        if let Some(suggest) = suggest {
+            suggest.print_human(out, self)?;
+        } else {
+            // This API is only used for vet-suggest
+            writeln!(out, "Nothing to suggest, you're fully audited!");
+        }
+        Ok(())
+    }
+
+    /// Print a full json report
+    pub fn print_json(
+        &self,
+        out: &Arc<dyn Out>,
+        suggest: Option<&Suggest>,
+    ) -> Result<(), miette::Report> {
+        let json_vet_failure = |(pkgidx, audit_fail): &(PackageIdx, AuditFailure)| {
+            let package = &self.graph.nodes[*pkgidx];
+            JsonVetFailure {
+                name: package.name.to_owned(),
+                version: package.version.clone(),
+                missing_criteria: self
+                    .criteria_mapper
+                    .criteria_names(&audit_fail.criteria_failures)
+                    .map(|s| s.to_owned())
+                    .collect(),
+            }
+        };
+        let result = JsonReport {
+            conclusion: match &self.conclusion {
+                Conclusion::Success(success) => {
+                    let json_package = |pkgidx: &PackageIdx| {
+                        let package = &self.graph.nodes[*pkgidx];
+                        JsonPackage {
+                            name: package.name.to_owned(),
                             version: package.version.clone(),
                         }
                     };
@@ -2151,25 +3011,11 @@ impl ResolveReport<'_> {
                                 .map(|s| s.to_owned())
                                 .collect(),
                             suggested_diff: item.suggested_diff.clone(),
+                            risk_indicators: item.suggested_diff.risks.clone(),
                         }
                     };
                     JsonReportConclusion::FailForVet(JsonReportFailForVet {
-                        failures: fail
-                            .failures
-                            .iter()
-                            .map(|(pkgidx, audit_fail)| {
-                                let package = &self.graph.nodes[*pkgidx];
-                                JsonVetFailure {
-                                    name: package.name.to_owned(),
-                                    version: package.version.clone(),
-                                    missing_criteria: self
-                                        .criteria_mapper
-                                        .criteria_names(&audit_fail.criteria_failures)
-                                        .map(|s| s.to_owned())
-                                        .collect(),
-                                }
-                            })
-                            .collect(),
+                        failures: fail.failures.iter().map(json_vet_failure).collect(),
                         suggest: suggest.as_ref().map(|suggest| JsonSuggest {
                             suggestions: suggest
                                 .suggestions
@@ -2187,23 +3033,1344 @@ impl ResolveReport<'_> {
                                 })
                                 .collect(),
                             total_lines: suggest.total_lines,
+                            total_weighted_lines: suggest.total_weighted_lines,
                         }),
                     })
                 }
             },
+            disputed_violations: self.disputed_violations.clone(),
+            baseline_failures: self
+                .baseline_failures
+                .as_ref()
+                .map(|failures| failures.iter().map(json_vet_failure).collect()),
         };
 
         serde_json::to_writer_pretty(&**out, &result).into_diagnostic()?;
 
         Ok(())
     }
+
+    /// Print a SARIF log containing a result for each problem in the report,
+    /// as well as for any crate policy errors found before resolving.
+    pub fn print_sarif(
+        &self,
+        out: &Arc<dyn Out>,
+        cfg: &Config,
+        store_path: &Path,
+        store: &Store,
+        suggest: Option<&Suggest>,
+        policy_errors: Option<&CratePolicyErrors>,
+    ) -> Result<(), miette::Report> {
+        let workspace_root = cfg.metadata.workspace_root.as_std_path();
+        let locator = SarifLocator {
+            workspace_root,
+            store_path,
+            store,
+            lockfile: std::fs::read_to_string(workspace_root.join("Cargo.lock")).ok(),
+        };
+
+        let mut results = Vec::new();
+        for error in policy_errors.into_iter().flat_map(|errors| &errors.errors) {
+            match error {
+                CratePolicyError::NeedsVersion(errors) => {
+                    results.extend(errors.errors.iter().map(|error| {
+                        let location = match &error.version {
+                            Some(version) => locator.lockfile_entry(&error.package, version),
+                            None => locator.store_file(&store.config_src, None),
+                        };
+                        sarif_result(
+                            SARIF_POLICY_NEEDS_VERSION,
+                            format!("the policy for {} is missing a version", error.package),
+                            location,
+                            vec![],
+                        )
+                    }))
+                }
+                CratePolicyError::UnusedVersion(errors) => {
+                    results.extend(errors.errors.iter().map(|error| {
+                        let key = error.to_string();
+                        sarif_result(
+                            SARIF_UNUSED_POLICY,
+                            format!("the policy for {key} doesn't match any crate"),
+                            locator.store_table(
+                                &store.config_src,
+                                &[format!("[policy.{key}]"), format!("[policy.\"{key}\"]")],
+                            ),
+                            vec![],
+                        )
+                    }))
+                }
+            }
+        }
+
+        match &self.conclusion {
+            Conclusion::Success(_) => {}
+            Conclusion::FailForViolationConflict(fail) => {
+                for (pkgidx, violations) in &fail.violations {
+                    let package = &self.graph.nodes[*pkgidx];
+                    for violation in violations {
+                        let (violation_source, violation, mut related) = match violation {
+                            ViolationConflict::UnauditedConflict {
+                                violation_source,
+                                violation,
+                                exemptions,
+                            } => (
+                                violation_source,
+                                violation,
+                                locator.store_file(
+                                    &store.config_src,
+                                    exemptions.criteria.first().map(Spanned::span),
+                                ),
+                            ),
+                            ViolationConflict::AuditConflict {
+                                violation_source,
+                                violation,
+                                audit_source,
+                                audit,
+                            } => (
+                                violation_source,
+                                violation,
+                                locator.audit(audit_source.as_ref(), audit),
+                            ),
+                        };
+                        sarif_location_message(&mut related, "conflicts with this entry");
+                        results.push(sarif_result(
+                            SARIF_VIOLATION_CONFLICT,
+                            format!(
+                                "{}:{} conflicts with a violation",
+                                package.name, package.version
+                            ),
+                            locator.audit(violation_source.as_ref(), violation),
+                            vec![
+                                related,
+                                locator.lockfile_entry(package.name, &package.version),
+                            ],
+                        ));
+                    }
+                }
+            }
+            Conclusion::FailForVet(fail) => {
+                for (pkgidx, audit_fail) in &fail.failures {
+                    let package = &self.graph.nodes[*pkgidx];
+                    let mut message = format!(
+                        "{}:{} is missing criteria: {}",
+                        package.name,
+                        package.version,
+                        self.criteria_mapper
+                            .criteria_names(&audit_fail.criteria_failures)
+                            .collect::<Vec<_>>()
+                            .join(", ")
+                    );
+                    let suggestion = suggest
+                        .into_iter()
+                        .flat_map(|suggest| &suggest.suggestions)
+                        .find(|item| item.package == *pkgidx);
+                    if let Some(item) = suggestion {
+                        message.push_str(&match &item.suggested_diff.from {
+                            Some(from) => format!(
+                                " (suggested: cargo vet diff {} {} {})",
+                                package.name, from, item.suggested_diff.to
+                            ),
+                            None => format!(
+                                " (suggested: cargo vet inspect {} {})",
+                                package.name, item.suggested_diff.to
+                            ),
+                        });
+                    }
+                    results.push(sarif_result(
+                        SARIF_UNVETTED,
+                        message,
+                        locator.lockfile_entry(package.name, &package.version),
+                        vec![],
+                    ));
+                }
+            }
+        }
+
+        let rule = |id: &str, description: &str, page: &str| SarifRule {
+            id: id.to_owned(),
+            short_description: SarifMessage {
+                text: description.to_owned(),
+            },
+            help_uri: format!("https://mozilla.github.io/cargo-vet/{page}"),
+        };
+        let log = SarifLog {
+            schema: "https://json.schemastore.org/sarif-2.1.0.json".to_owned(),
+            version: "2.1.0".to_owned(),
+            runs: vec![SarifRun {
+                tool: SarifTool {
+                    driver: SarifDriver {
+                        name: "cargo-vet".to_owned(),
+                        version: env!("CARGO_PKG_VERSION").to_owned(),
+                        information_uri: "https://mozilla.github.io/cargo-vet/".to_owned(),
+                        rules: vec![
+                            rule(
+                                SARIF_UNVETTED,
+                                "A dependency hasn't been audited for the required criteria",
+                                "performing-audits.html",
+                            ),
+                            rule(
+                                SARIF_VIOLATION_CONFLICT,
+                                "A violation conflicts with an audit or exemption",
+                                "audit-entries.html#violation",
+                            ),
+                            rule(
+                                SARIF_POLICY_NEEDS_VERSION,
+                                "A crate policy with dependency-criteria needs a version",
+                                "config.html#dependency-criteria",
+                            ),
+                            rule(
+                                SARIF_UNUSED_POLICY,
+                                "A crate policy doesn't match any third-party crate",
+                                "config.html#the-policy-table",
+                            ),
+                        ],
+                    },
+                },
+                results,
+            }],
+        };
+
+        serde_json::to_writer_pretty(&**out, &log).into_diagnostic()?;
+
+        Ok(())
+    }
+
+    /// Print a JUnit XML report with a test case for each third-party package,
+    /// which passes if the package was vetted.
+    pub fn print_junit(
+        &self,
+        out: &Arc<dyn Out>,
+        store: &Store,
+    ) -> Result<(), MissingEdgeOriginError> {
+        let violations: FastMap<PackageIdx, &Vec<ViolationConflict>> = match &self.conclusion {
+            Conclusion::FailForViolationConflict(fail) => {
+                fail.violations.iter().map(|(k, v)| (*k, v)).collect()
+            }
+            _ => FastMap::new(),
+        };
+
+        let baseline_failures: FastSet<PackageIdx> = self
+            .baseline_failures
+            .iter()
+            .flatten()
+            .map(|(pkgidx, _)| *pkgidx)
+            .collect();
+
+        let mut test_cases = Vec::new();
+        for (pkgidx, package) in self.graph.nodes.iter().enumerate() {
+            // Packages which already failed in the `check --baseline` aren't
+            // tested.
+            if !package.is_third_party || baseline_failures.contains(&pkgidx) {
+                continue;
+            }
+            let name = format!("{}:{}", package.name, package.version);
+            let missing = self.missing_criteria(pkgidx);
+            let outcome = if let Some(violations) = violations.get(&pkgidx) {
+                Err((
+                    "violation conflict".to_owned(),
+                    violations
+                        .iter()
+                        .map(violation_description)
+                        .collect::<Vec<_>>(),
+                ))
+            } else if !missing.is_empty() {
+                Err((
+                    format!(
+                        "missing criteria: {}",
+                        self.criteria_mapper
+                            .criteria_names(&missing)
+                            .collect::<Vec<_>>()
+                            .join(", ")
+                    ),
+                    vec![],
+                ))
+            } else {
+                Ok(self
+                    .vet_evidence(store, pkgidx, &self.requirements[pkgidx])?
+                    .into_iter()
+                    .map(|evidence| evidence.description)
+                    .collect::<Vec<_>>())
+            };
+            test_cases.push((package.name, name, outcome));
+        }
+
+        let tests = test_cases.len();
+        let failed = test_cases.iter().filter(|(.., o)| o.is_err()).count();
+        writeln!(out, r#"<?xml version="1.0" encoding="UTF-8"?>"#);
+        writeln!(
+            out,
+            r#"<testsuites name="cargo-vet" tests="{tests}" failures="{failed}">"#
+        );
+        writeln!(
+            out,
+            r#"  <testsuite name="cargo-vet" tests="{tests}" failures="{failed}">"#
+        );
+        for (classname, name, outcome) in test_cases {
+            writeln!(
+                out,
+                r#"    <testcase classname="{}" name="{}">"#,
+                xml_escape(classname),
+                xml_escape(&name)
+            );
+            match outcome {
+                Ok(vetted_by) => {
+                    writeln!(
+                        out,
+                        "      <system-out>{}</system-out>",
+                        xml_escape(&vetted_by.join("\n"))
+                    );
+                }
+                Err((message, details)) => {
+                    writeln!(
+                        out,
+                        r#"      <failure message="{}" type="{}">{}</failure>"#,
+                        xml_escape(&message),
+                        if details.is_empty() {
+                            "unvetted"
+                        } else {
+                            "violation"
+                        },
+                        xml_escape(&details.join("\n"))
+                    );
+                }
+            }
+            writeln!(out, "    </testcase>");
+        }
+        writeln!(out, "  </testsuite>");
+        writeln!(out, "</testsuites>");
+
+        Ok(())
+    }
+
+    /// Print a software bill of materials listing every package in the graph,
+    /// with each third-party package annotated with how it was vetted.
+    pub fn print_sbom(
+        &self,
+        out: &Arc<dyn Out>,
+        cfg: &Config,
+        store: &Store,
+        format: SbomFormat,
+    ) -> Result<(), miette::Report> {
+        let timestamp = cfg.now.format("%Y-%m-%dT%H:%M:%SZ").to_string();
+        let tool = format!("cargo-vet-{}", env!("CARGO_PKG_VERSION"));
+        let document_name = cfg
+            .metadata
+            .workspace_root
+            .file_name()
+            .unwrap_or("workspace")
+            .to_owned();
+
+        let purl = |package: &PackageNode<'_>| {
+            if !package.is_third_party {
+                return None;
+            }
+            let version = &package.version.semver;
+            Some(match format::split_qualified_package_name(package.name) {
+                Some((registry, name)) => {
+                    let qualifier = store.config.registries.get(registry).map(|registry| {
+                        let index = registry.index.trim_start_matches("registry+");
+                        format!(
+                            "?repository_url={}",
+                            url::form_urlencoded::byte_serialize(index.as_bytes())
+                                .collect::<String>()
+                        )
+                    });
+                    format!(
+                        "pkg:cargo/{name}@{version}{}",
+                        qualifier.unwrap_or_default()
+                    )
+                }
+                None => format!("pkg:cargo/{}@{version}", package.name),
+            })
+        };
+        let annotations = self
+            .graph
+            .nodes
+            .iter()
+            .enumerate()
+            .map(|(pkgidx, package)| {
+                if package.is_third_party {
+                    self.sbom_annotations(store, pkgidx)
+                } else {
+                    Ok(vec![])
+                }
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        match format {
+            SbomFormat::Cyclonedx => {
+                let bom_ref =
+                    |package: &PackageNode<'_>| format!("{}@{}", package.name, package.version);
+                let bom = CycloneDxBom {
+                    bom_format: "CycloneDX".to_owned(),
+                    spec_version: "1.5".to_owned(),
+                    version: 1,
+                    metadata: CycloneDxMetadata {
+                        timestamp,
+                        tools: CycloneDxTools {
+                            components: vec![CycloneDxComponent {
+                                kind: "application".to_owned(),
+                                bom_ref: None,
+                                name: "cargo-vet".to_owned(),
+                                version: env!("CARGO_PKG_VERSION").to_owned(),
+                                purl: None,
+                                properties: vec![],
+                            }],
+                        },
+                    },
+                    components: self
+                        .graph
+                        .nodes
+                        .iter()
+                        .zip(annotations)
+                        .map(|(package, annotations)| CycloneDxComponent {
+                            kind: "library".to_owned(),
+                            bom_ref: Some(bom_ref(package)),
+                            name: package.name.to_owned(),
+                            version: package.version.to_string(),
+                            purl: purl(package),
+                            properties: annotations
+                                .into_iter()
+                                .map(|(name, value)| CycloneDxProperty {
+                                    name: format!("cargo-vet:{name}"),
+                                    value,
+                                })
+                                .collect(),
+                        })
+                        .collect(),
+                    dependencies: self
+                        .graph
+                        .nodes
+                        .iter()
+                        .map(|package| CycloneDxDependency {
+                            bom_ref: bom_ref(package),
+                            depends_on: package
+                                .all_deps
+                                .iter()
+                                .map(|&dep| bom_ref(&self.graph.nodes[dep]))
+                                .collect(),
+                        })
+                        .collect(),
+                };
+                serde_json::to_writer_pretty(&**out, &bom).into_diagnostic()?;
+            }
+            SbomFormat::Spdx => {
+                let spdx_id = |package: &PackageNode<'_>| {
+                    let id = format!("{}-{}", package.name, package.version).replace(
+                        |c: char| !c.is_ascii_alphanumeric() && c != '.' && c != '-',
+                        "-",
+                    );
+                    format!("SPDXRef-Package-{id}")
+                };
+                // The namespace must be unique for each document, so derive it
+                // from the packages being described.
+                let mut hasher = Sha256::new();
+                for package in &self.graph.nodes {
+                    hasher.update(format!("{}@{}\n", package.name, package.version));
+                }
+                let document = SpdxDocument {
+                    spdx_version: "SPDX-2.3".to_owned(),
+                    data_license: "CC0-1.0".to_owned(),
+                    spdx_id: "SPDXRef-DOCUMENT".to_owned(),
+                    name: document_name.clone(),
+                    document_namespace: format!(
+                        "https://spdx.org/spdxdocs/cargo-vet/{}-{:x}",
+                        url::form_urlencoded::byte_serialize(document_name.as_bytes())
+                            .collect::<String>(),
+                        hasher.finalize()
+                    ),
+                    creation_info: SpdxCreationInfo {
+                        created: timestamp.clone(),
+                        creators: vec![format!("Tool: {tool}")],
+                    },
+                    packages: self
+                        .graph
+                        .nodes
+                        .iter()
+                        .zip(annotations)
+                        .map(|(package, annotations)| SpdxPackage {
+                            spdx_id: spdx_id(package),
+                            name: package.name.to_owned(),
+                            version_info: package.version.to_string(),
+                            download_location: "NOASSERTION".to_owned(),
+                            files_analyzed: false,
+                            external_refs: purl(package)
+                                .into_iter()
+                                .map(|purl| SpdxExternalRef {
+                                    reference_category: "PACKAGE-MANAGER".to_owned(),
+                                    reference_type: "purl".to_owned(),
+                                    reference_locator: purl,
+                                })
+                                .collect(),
+                            annotations: annotations
+                                .into_iter()
+                                .map(|(name, value)| SpdxAnnotation {
+                                    annotation_date: timestamp.clone(),
+                                    annotation_type: "REVIEW".to_owned(),
+                                    annotator: format!("Tool: {tool}"),
+                                    comment: format!("cargo-vet:{name}: {value}"),
+                                })
+                                .collect(),
+                        })
+                        .collect(),
+                    relationships: self
+                        .graph
+                        .nodes
+                        .iter()
+                        .filter(|package| package.is_root)
+                        .map(|package| SpdxRelationship {
+                            spdx_element_id: "SPDXRef-DOCUMENT".to_owned(),
+                            relationship_type: "DESCRIBES".to_owned(),
+                            related_spdx_element: spdx_id(package),
+                        })
+                        .chain(self.graph.nodes.iter().flat_map(|package| {
+                            package.all_deps.iter().map(|&dep| SpdxRelationship {
+                                spdx_element_id: spdx_id(package),
+                                relationship_type: "DEPENDS_ON".to_owned(),
+                                related_spdx_element: spdx_id(&self.graph.nodes[dep]),
+                            })
+                        }))
+                        .collect(),
+                };
+                serde_json::to_writer_pretty(&**out, &document).into_diagnostic()?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// The `cargo-vet:*` annotations describing the vet status of a
+    /// third-party package in an SBOM.
+    fn sbom_annotations(
+        &self,
+        store: &Store,
+        pkgidx: PackageIdx,
+    ) -> Result<Vec<(&'static str, String)>, MissingEdgeOriginError> {
+        let mut annotations = vec![];
+        if let Conclusion::FailForViolationConflict(fail) = &self.conclusion {
+            if fail.violations.iter().any(|(idx, _)| *idx == pkgidx) {
+                annotations.push(("status", "violation".to_owned()));
+                return Ok(annotations);
+            }
+        }
+        let missing = self.missing_criteria(pkgidx);
+        if missing.is_empty() {
+            annotations.push(("status", "vetted".to_owned()));
+        } else {
+            annotations.push(("status", "unvetted".to_owned()));
+            annotations.extend(
+                self.criteria_mapper
+                    .criteria_names(&missing)
+                    .map(|name| ("missing-criteria", name.to_owned())),
+            );
+        }
+
+        let satisfied = self.satisfied_criteria(pkgidx);
+        annotations.extend(
+            self.criteria_mapper
+                .criteria_names(&satisfied)
+                .map(|name| ("criteria", name.to_owned())),
+        );
+
+        let evidence = self.vet_evidence(store, pkgidx, &satisfied)?;
+        let kinds = evidence.iter().map(|e| e.kind).collect::<SortedSet<_>>();
+        let auditors = evidence
+            .iter()
+            .flat_map(|e| e.auditors.iter().cloned())
+            .collect::<SortedSet<_>>();
+        annotations.extend(
+            kinds
+                .into_iter()
+                .map(|kind| ("source", kind.name().to_owned())),
+        );
+        annotations.extend(evidence.into_iter().map(|e| ("vetted-by", e.description)));
+        annotations.extend(auditors.into_iter().map(|who| ("auditor", who)));
+        Ok(annotations)
+    }
+
+    /// Describe how each version of `package` in the graph matching `version`
+    /// was vetted for the criteria it requires or satisfies.
+    pub fn compute_explain(
+        &self,
+        store: &Store,
+        package: PackageStr<'_>,
+        version: Option<&VetVersion>,
+    ) -> Result<JsonExplain, MissingEdgeOriginError> {
+        let criteria_names = |criteria: &CriteriaSet| {
+            self.criteria_mapper
+                .criteria_names(criteria)
+                .map(|name| name.to_owned())
+                .collect::<Vec<_>>()
+        };
+
+        let mut packages = Vec::new();
+        for (pkgidx, node) in self.graph.nodes.iter().enumerate() {
+            if node.name != package || version.is_some_and(|v| v != &node.version) {
+                continue;
+            }
+
+            let required = &self.requirements[pkgidx];
+            let violation_conflict = match &self.conclusion {
+                Conclusion::FailForViolationConflict(fail) => {
+                    fail.violations.iter().any(|(idx, _)| *idx == pkgidx)
+                }
+                _ => false,
+            };
+
+            // Only explain the strongest criteria which were satisfied or
+            // which are missing, as implied criteria share their paths.
+            let satisfied = self.satisfied_criteria(pkgidx);
+            let mut missing = required.clone();
+            missing.clear_criteria(&satisfied);
+            let mut criteria_indices = self
+                .criteria_mapper
+                .minimal_indices(&satisfied)
+                .chain(self.criteria_mapper.minimal_indices(&missing))
+                .collect::<Vec<_>>();
+            criteria_indices.sort();
+
+            let mut criteria = Vec::new();
+            if let Some(result) = &self.results[pkgidx] {
+                for criteria_idx in criteria_indices {
+                    let result = match &result.search_results[criteria_idx] {
+                        Ok(path) => JsonExplainResult::Vetted {
+                            // Paths are found by searching backwards from the
+                            // package's version, so reverse them to start at
+                            // the root.
+                            path: path
+                                .iter()
+                                .rev()
+                                .map(|origin| self.explain_edge(store, pkgidx, origin))
+                                .collect::<Result<_, _>>()?,
+                        },
+                        Err(failure) => JsonExplainResult::Unvetted {
+                            reachable_from_root: failure
+                                .reachable_from_root
+                                .iter()
+                                .cloned()
+                                .collect(),
+                            reachable_from_target: failure
+                                .reachable_from_target
+                                .iter()
+                                .cloned()
+                                .collect(),
+                        },
+                    };
+                    criteria.push(JsonExplainCriteria {
+                        criteria: self.criteria_mapper.criteria_name(criteria_idx).to_owned(),
+                        required: required.has_criteria(criteria_idx),
+                        result,
+                    });
+                }
+            }
+
+            packages.push(JsonExplainPackage {
+                name: node.name.to_owned(),
+                version: node.version.clone(),
+                third_party: node.is_third_party,
+                violation_conflict,
+                required_criteria: criteria_names(required),
+                criteria,
+                disputed_violations: disputed_violations_for(store, node.name, Some(&node.version)),
+            });
+        }
+
+        Ok(JsonExplain { packages })
+    }
+
+    /// Describe a single edge along a path in the audit graph.
+    fn explain_edge(
+        &self,
+        store: &Store,
+        pkgidx: PackageIdx,
+        origin: &DeltaEdgeOrigin,
+    ) -> Result<JsonExplainEdge, MissingEdgeOriginError> {
+        let package = &self.graph.nodes[pkgidx];
+        let criteria_names = |criteria: &[Spanned<CriteriaName>]| {
+            criteria.iter().map(|c| c.to_string()).collect::<Vec<_>>()
+        };
+        let who = |who: &[Spanned<String>]| who.iter().map(|w| w.to_string()).collect::<Vec<_>>();
+        let file = |import: Option<&ImportName>| match import {
+            Some(_) => store.imports_src.name().to_owned(),
+            None => store.audits_src.name().to_owned(),
+        };
+        // The criteria granted by an entry, and the criteria it was capped
+        // from if its import's `max-criteria` prevented it from granting them
+        // all.
+        let granted_criteria = |import_name: Option<&ImportName>,
+                                criteria: &[Spanned<CriteriaName>]| {
+            let ceiling = import_name.and_then(|import_name| {
+                import_criteria_ceiling(store, &self.criteria_mapper, import_name)
+            });
+            let full = self.criteria_mapper.criteria_from_list(criteria);
+            match ceiling {
+                Some(ceiling) if !ceiling.contains(&full) => {
+                    let mut capped = full;
+                    capped.intersected_with(&ceiling);
+                    let capped = self
+                        .criteria_mapper
+                        .criteria_names(&capped)
+                        .map(|name| name.to_owned())
+                        .collect();
+                    (capped, criteria_names(criteria))
+                }
+                _ => (criteria_names(criteria), vec![]),
+            }
+        };
+
+        Ok(match origin.entry(store, package.name)? {
+            DeltaEdgeEntry::Audit {
+                import,
+                entry,
+                from,
+                to,
+            } => {
+                let (criteria, capped_from) = granted_criteria(import, &entry.criteria);
+                JsonExplainEdge {
+                    kind: "audit".to_owned(),
+                    from: from.cloned(),
+                    to: to.clone(),
+                    file: file(import),
+                    import: import.cloned(),
+                    criteria,
+                    capped_from,
+                    who: who(&entry.who),
+                    notes: entry.notes.clone(),
+                }
+            }
+            DeltaEdgeEntry::WildcardAudit {
+                import,
+                entry,
+                publisher,
+            } => {
+                let (criteria, capped_from) = granted_criteria(import, &entry.criteria);
+                JsonExplainEdge {
+                    kind: "wildcard-audit".to_owned(),
+                    from: None,
+                    to: publisher.version.clone(),
+                    file: file(import),
+                    import: import.cloned(),
+                    criteria,
+                    capped_from,
+                    who: who(&entry.who),
+                    notes: entry.notes.clone(),
+                }
+            }
+            DeltaEdgeEntry::Trusted { entry, publisher } => JsonExplainEdge {
+                kind: "trusted".to_owned(),
+                from: None,
+                to: publisher.version.clone(),
+                file: store.audits_src.name().to_owned(),
+                import: None,
+                criteria: criteria_names(&entry.criteria),
+                capped_from: vec![],
+                who: vec![publisher.user_login.clone()],
+                notes: entry.notes.clone(),
+            },
+            DeltaEdgeEntry::Exemption(entry) => JsonExplainEdge {
+                kind: "exemption".to_owned(),
+                from: None,
+                to: entry.version.clone(),
+                file: store.config_src.name().to_owned(),
+                import: None,
+                criteria: criteria_names(&entry.criteria),
+                capped_from: vec![],
+                who: vec![],
+                notes: entry.notes.clone(),
+            },
+            DeltaEdgeEntry::FreshExemption(version) => JsonExplainEdge {
+                kind: "exemption".to_owned(),
+                from: None,
+                to: version.clone(),
+                file: store.config_src.name().to_owned(),
+                import: None,
+                criteria: vec![],
+                capped_from: vec![],
+                who: vec![],
+                notes: None,
+            },
+            DeltaEdgeEntry::Unpublished(entry) => JsonExplainEdge {
+                kind: "unpublished".to_owned(),
+                from: Some(entry.audited_as.clone()),
+                to: entry.version.clone(),
+                file: store.imports_src.name().to_owned(),
+                import: None,
+                criteria: vec![],
+                capped_from: vec![],
+                who: vec![],
+                notes: None,
+            },
+        })
+    }
+
+    /// Describe why each version of `package` in the graph matching `version`
+    /// requires the criteria it does, by tracing each of the strongest required
+    /// criteria back to the roots and policy entries which introduce it.
+    ///
+    /// With feature configurations, the requirements are traced through the
+    /// graph of each configuration separately, as they were resolved.
+    pub fn compute_why(&self, package: PackageStr<'_>, version: Option<&VetVersion>) -> JsonWhy {
+        let origins = |pkgidx: PackageIdx, criteria_idx: usize| {
+            self.requirement_sources
+                .iter()
+                .flat_map(|sources| {
+                    requirement_origins(
+                        &self.graph,
+                        &self.criteria_mapper,
+                        sources,
+                        pkgidx,
+                        criteria_idx,
+                    )
+                })
+                .collect()
+        };
+
+        let mut packages = Vec::new();
+        for (pkgidx, node) in self.graph.nodes.iter().enumerate() {
+            if node.name != package || version.is_some_and(|v| v != &node.version) {
+                continue;
+            }
+
+            let required = &self.requirements[pkgidx];
+            packages.push(JsonWhyPackage {
+                name: node.name.to_owned(),
+                version: node.version.clone(),
+                required_criteria: self
+                    .criteria_mapper
+                    .criteria_names(required)
+                    .map(|name| name.to_owned())
+                    .collect(),
+                criteria: self
+                    .criteria_mapper
+                    .minimal_indices(required)
+                    .map(|criteria_idx| JsonWhyCriteria {
+                        criteria: self.criteria_mapper.criteria_name(criteria_idx).to_owned(),
+                        origins: origins(pkgidx, criteria_idx),
+                    })
+                    .collect(),
+            });
+        }
+
+        JsonWhy { packages }
+    }
+
+    /// The criteria which the package was successfully vetted for.
+    fn satisfied_criteria(&self, pkgidx: PackageIdx) -> CriteriaSet {
+        let mut satisfied = self.criteria_mapper.no_criteria();
+        if let Some(result) = &self.results[pkgidx] {
+            for (criteria_idx, search_result) in result.search_results.iter().enumerate() {
+                if search_result.is_ok() {
+                    satisfied.set_criteria(criteria_idx);
+                }
+            }
+        }
+        satisfied
+    }
+
+    /// The criteria required of the package which it couldn't be vetted for.
+    /// Unlike the failures in the conclusion, these are also known when the
+    /// check failed due to a violation conflict.
+    fn missing_criteria(&self, pkgidx: PackageIdx) -> CriteriaSet {
+        let mut missing = self.requirements[pkgidx].clone();
+        missing.clear_criteria(&self.satisfied_criteria(pkgidx));
+        missing
+    }
+
+    /// The audits, exemptions and other entries which were used to vet the
+    /// package for the given criteria.
+    fn vet_evidence(
+        &self,
+        store: &Store,
+        pkgidx: PackageIdx,
+        criteria: &CriteriaSet,
+    ) -> Result<Vec<VetEvidence>, MissingEdgeOriginError> {
+        let package = &self.graph.nodes[pkgidx];
+        let Some(result) = &self.results[pkgidx] else {
+            return Ok(vec![]);
+        };
+
+        let mut origins = SortedSet::new();
+        for criteria_idx in self.criteria_mapper.minimal_indices(criteria) {
+            if let Ok(path) = &result.search_results[criteria_idx] {
+                origins.extend(path.iter().cloned());
+            }
+        }
+
+        let source = |import: Option<&ImportName>| match import {
+            Some(import_name) => (
+                VetEvidenceKind::Import,
+                format!(" imported from {import_name}"),
+            ),
+            None => (VetEvidenceKind::Audit, String::new()),
+        };
+
+        origins
+            .iter()
+            .map(|origin| {
+                Ok(match origin.entry(store, package.name)? {
+                    DeltaEdgeEntry::Audit { import, entry, .. } => {
+                        let (kind, source) = source(import);
+                        VetEvidence {
+                            kind,
+                            description: format!("{}{source}", audit_description(entry)),
+                            auditors: entry.who.iter().map(|who| who.to_string()).collect(),
+                        }
+                    }
+                    DeltaEdgeEntry::WildcardAudit {
+                        import,
+                        entry,
+                        publisher,
+                    } => {
+                        let (kind, source) = source(import);
+                        VetEvidence {
+                            kind,
+                            description: format!(
+                                "wildcard audit of versions published by {} ({}){source}",
+                                publisher.user_login,
+                                criteria_list(&entry.criteria)
+                            ),
+                            auditors: entry.who.iter().map(|who| who.to_string()).collect(),
+                        }
+                    }
+                    DeltaEdgeEntry::Trusted { publisher, .. } => VetEvidence {
+                        kind: VetEvidenceKind::TrustedPublisher,
+                        description: format!("trusted publisher {}", publisher.user_login),
+                        auditors: vec![],
+                    },
+                    DeltaEdgeEntry::Exemption(entry) => VetEvidence {
+                        kind: VetEvidenceKind::Exemption,
+                        description: format!(
+                            "exemption {} ({})",
+                            entry.version,
+                            criteria_list(&entry.criteria)
+                        ),
+                        auditors: vec![],
+                    },
+                    DeltaEdgeEntry::FreshExemption(version) => VetEvidence {
+                        kind: VetEvidenceKind::Exemption,
+                        description: format!("exemption {version}"),
+                        auditors: vec![],
+                    },
+                    DeltaEdgeEntry::Unpublished(entry) => VetEvidence {
+                        kind: VetEvidenceKind::Unpublished,
+                        description: format!(
+                            "unpublished version {} audited as {}",
+                            entry.version, entry.audited_as
+                        ),
+                        auditors: vec![],
+                    },
+                })
+            })
+            .collect()
+    }
+}
+
+/// An audit, exemption or other entry which was used to vet a package.
+struct VetEvidence {
+    kind: VetEvidenceKind,
+    description: String,
+    /// The people who performed the audit, if any.
+    auditors: Vec<String>,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord)]
+enum VetEvidenceKind {
+    Audit,
+    Import,
+    TrustedPublisher,
+    Exemption,
+    Unpublished,
+}
+
+impl VetEvidenceKind {
+    fn name(self) -> &'static str {
+        match self {
+            VetEvidenceKind::Audit => "audit",
+            VetEvidenceKind::Import => "import",
+            VetEvidenceKind::TrustedPublisher => "trusted-publisher",
+            VetEvidenceKind::Exemption => "exemption",
+            VetEvidenceKind::Unpublished => "unpublished",
+        }
+    }
+}
+
+/// Print the output of [`ResolveReport::compute_explain`] for humans.
+pub fn print_explain_human(
+    out: &Arc<dyn Out>,
+    explain: &JsonExplain,
+) -> Result<(), std::io::Error> {
+    let version_name = |version: &Option<VetVersion>| match version {
+        Some(version) => version.to_string(),
+        None => "root".to_owned(),
+    };
+
+    for (idx, package) in explain.packages.iter().enumerate() {
+        if idx > 0 {
+            writeln!(out);
+        }
+        writeln!(
+            out,
+            "{}",
+            out.style()
+                .bold()
+                .apply_to(format_args!("{}:{}", package.name, package.version))
+        );
+        if !package.third_party {
+            writeln!(out, "  first-party package, which isn't vetted");
+            continue;
+        }
+        if package.violation_conflict {
+            writeln!(
+                out,
+                "  conflicts with a violation, run `cargo vet check` for details"
+            );
+            continue;
+        }
+        for violation in &package.disputed_violations {
+            writeln!(
+                out,
+                "  ignoring disputed violation against {} ({}) from {}",
+                violation.violation,
+                violation.criteria.join(", "),
+                violation.import
+            );
+            writeln!(out, "    notes: {}", violation.notes);
+        }
+        if package.criteria.is_empty() {
+            writeln!(out, "  no criteria required or satisfied");
+        }
+
+        for criteria in &package.criteria {
+            let required = if criteria.required { " (required)" } else { "" };
+            match &criteria.result {
+                JsonExplainResult::Vetted { path } => {
+                    writeln!(out, "  {}{required}: vetted by", criteria.criteria);
+                    for edge in path {
+                        let source = match &edge.import {
+                            Some(import_name) => {
+                                format!("in {}, imported from {import_name}", edge.file)
+                            }
+                            None => format!("in {}", edge.file),
+                        };
+                        writeln!(
+                            out,
+                            "    {} -> {}: {} ({}) {source}",
+                            version_name(&edge.from),
+                            edge.to,
+                            edge.kind,
+                            edge.criteria.join(", ")
+                        );
+                        if !edge.capped_from.is_empty() {
+                            writeln!(
+                                out,
+                                "      capped from: {} by the import's max-criteria",
+                                edge.capped_from.join(", ")
+                            );
+                        }
+                        if !edge.who.is_empty() {
+                            writeln!(out, "      who: {}", edge.who.join(", "));
+                        }
+                        if let Some(notes) = &edge.notes {
+                            writeln!(out, "      notes: {notes}");
+                        }
+                    }
+                }
+                JsonExplainResult::Unvetted {
+                    reachable_from_root,
+                    reachable_from_target,
+                } => {
+                    let versions = |versions: &[Option<VetVersion>]| {
+                        versions
+                            .iter()
+                            .map(version_name)
+                            .collect::<Vec<_>>()
+                            .join(", ")
+                    };
+                    writeln!(out, "  {}{required}: not vetted", criteria.criteria);
+                    writeln!(
+                        out,
+                        "    reachable from root: {}",
+                        versions(reachable_from_root)
+                    );
+                    writeln!(
+                        out,
+                        "    reachable from {}: {}",
+                        package.version,
+                        versions(reachable_from_target)
+                    );
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Print the output of [`ResolveReport::compute_why`] for humans.
+pub fn print_why_human(out: &Arc<dyn Out>, why: &JsonWhy) -> Result<(), std::io::Error> {
+    for (idx, package) in why.packages.iter().enumerate() {
+        if idx > 0 {
+            writeln!(out);
+        }
+        writeln!(
+            out,
+            "{}",
+            out.style()
+                .bold()
+                .apply_to(format_args!("{}:{}", package.name, package.version))
+        );
+        if package.criteria.is_empty() {
+            writeln!(out, "  no criteria required");
+        }
+
+        for criteria in &package.criteria {
+            writeln!(out, "  requires {} because", criteria.criteria);
+            for origin in &criteria.origins {
+                let criteria = origin.criteria.join(", ");
+                let origin_package = format!("{}:{}", origin.package.name, origin.package.version);
+                let reason = match &origin.reason[..] {
+                    "root" => format!("{origin_package} is a root, which requires {criteria}"),
+                    "criteria" => format!("{origin_package} has a policy with criteria = {criteria}"),
+                    "default-dev-criteria" => format!(
+                        "{origin_package} requires {criteria} of dev-dependencies by default"
+                    ),
+                    "dev-criteria" => {
+                        format!("{origin_package} has a policy with dev-criteria = {criteria}")
+                    }
+                    "platform-criteria" => format!(
+                        "{origin_package} only uses {} on platforms with platform-criteria = {criteria}",
+                        origin.path.first().map_or(&package.name, |step| &step.name)
+                    ),
+                    _ => format!(
+                        "{origin_package} has a policy with dependency-criteria for {} = {criteria}",
+                        origin.path.first().map_or(&package.name, |step| &step.name)
+                    ),
+                };
+                match &origin.feature_config {
+                    Some(name) => writeln!(out, "    {reason} (in feature config {name})"),
+                    None => writeln!(out, "    {reason}"),
+                }
+                for step in &origin.path {
+                    writeln!(
+                        out,
+                        "      -> {}:{} ({})",
+                        step.name, step.version, step.kind
+                    );
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
+
+fn criteria_list(criteria: &[Spanned<CriteriaName>]) -> String {
+    criteria
+        .iter()
+        .map(|c| &c[..])
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+fn audit_description(entry: &AuditEntry) -> String {
+    let kind = match &entry.kind {
+        AuditKind::Full { version } => format!("audit {version}"),
+        AuditKind::Delta { from, to } => format!("audit {from} -> {to}"),
+        AuditKind::Violation { violation } => format!("violation {violation}"),
+    };
+    format!("{kind} ({})", criteria_list(&entry.criteria))
+}
+
+fn violation_description(conflict: &ViolationConflict) -> String {
+    let with_source = |description: String, source: &Option<ImportName>| match source {
+        Some(import_name) => format!("{description} imported from {import_name}"),
+        None => description,
+    };
+    match conflict {
+        ViolationConflict::UnauditedConflict {
+            violation_source,
+            violation,
+            exemptions,
+        } => format!(
+            "{} conflicts with exemption {} ({})",
+            with_source(audit_description(violation), violation_source),
+            exemptions.version,
+            criteria_list(&exemptions.criteria)
+        ),
+        ViolationConflict::AuditConflict {
+            violation_source,
+            violation,
+            audit_source,
+            audit,
+        } => format!(
+            "{} conflicts with {}",
+            with_source(audit_description(violation), violation_source),
+            with_source(audit_description(audit), audit_source)
+        ),
+    }
+}
+
+/// Escape text for use in XML attributes and element content.
+fn xml_escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+const SARIF_UNVETTED: &str = "unvetted-dependency";
+const SARIF_VIOLATION_CONFLICT: &str = "violation-conflict";
+const SARIF_POLICY_NEEDS_VERSION: &str = "policy-needs-version";
+const SARIF_UNUSED_POLICY: &str = "unused-policy";
+
+fn sarif_result(
+    rule_id: &str,
+    message: String,
+    location: SarifLocation,
+    related_locations: Vec<SarifLocation>,
+) -> SarifResult {
+    SarifResult {
+        rule_id: rule_id.to_owned(),
+        level: "error".to_owned(),
+        message: SarifMessage { text: message },
+        locations: vec![location],
+        related_locations,
+    }
+}
+
+fn sarif_location_message(location: &mut SarifLocation, message: &str) {
+    location.message = Some(SarifMessage {
+        text: message.to_owned(),
+    });
+}
+
+/// Computes the locations of SARIF results within `Cargo.lock` and the store.
+struct SarifLocator<'a> {
+    workspace_root: &'a Path,
+    store_path: &'a Path,
+    store: &'a Store,
+    lockfile: Option<String>,
+}
+
+impl<'a> SarifLocator<'a> {
+    /// The location of `path`, relative to the workspace root if possible.
+    fn location(&self, path: &Path, region: Option<SarifRegion>) -> SarifLocation {
+        let uri = match path.strip_prefix(self.workspace_root) {
+            Ok(relative) => relative.to_string_lossy().replace('\\', "/"),
+            Err(_) => Url::from_file_path(path)
+                .map(String::from)
+                .unwrap_or_else(|()| path.to_string_lossy().into_owned()),
+        };
+        SarifLocation {
+            physical_location: SarifPhysicalLocation {
+                artifact_location: SarifArtifactLocation { uri },
+                region,
+            },
+            message: None,
+        }
+    }
+
+    /// The `[[package]]` entry for the given package in `Cargo.lock`.
+    fn lockfile_entry(&self, name: PackageStr<'_>, version: &VetVersion) -> SarifLocation {
+        // Packages from alternate registries are named with their registry in
+        // cargo-vet, but not in `Cargo.lock`.
+        let name = format::split_qualified_package_name(name).map_or(name, |(_, name)| name);
+        let name_line = format!("name = \"{name}\"");
+        let version_line = format!("version = \"{}\"", version.semver);
+        let region = self.lockfile.as_deref().and_then(|lockfile| {
+            // Compare whole lines, as a `Cargo.lock` checked out on Windows may
+            // have CRLF line endings.
+            let mut offset = 0;
+            let lines: Vec<(usize, &str)> = lockfile
+                .split_inclusive('\n')
+                .map(|line| {
+                    let start = offset;
+                    offset += line.len();
+                    (start, line.trim_end_matches(['\r', '\n']))
+                })
+                .collect();
+            let entry = lines
+                .windows(2)
+                .find(|pair| pair[0].1 == name_line && pair[1].1 == version_line)?;
+            sarif_region(lockfile, entry[0].0, entry[1].0 + entry[1].1.len())
+        });
+        self.location(&self.workspace_root.join("Cargo.lock"), region)
+    }
+
+    /// A span of one of the store's files.
+    fn store_file(&self, source: &SourceFile, span: Option<SourceSpan>) -> SarifLocation {
+        let region = span.and_then(|span| {
+            sarif_region(source.source(), span.offset(), span.offset() + span.len())
+        });
+        self.location(&self.store_path.join(source.name()), region)
+    }
+
+    /// The first of `headers` found in one of the store's files.
+    fn store_table(&self, source: &SourceFile, headers: &[String]) -> SarifLocation {
+        let span = headers.iter().find_map(|header| {
+            let start = source.source().find(&header[..])?;
+            Some(SourceSpan::from((start, header.len())))
+        });
+        self.store_file(source, span)
+    }
+
+    /// The audit entry for a local audit, or the import for an imported one.
+    fn audit(&self, source: Option<&ImportName>, audit: &AuditEntry) -> SarifLocation {
+        match source {
+            None => self.store_file(
+                &self.store.audits_src,
+                audit.criteria.first().map(Spanned::span),
+            ),
+            Some(import_name) => self.store_table(
+                &self.store.config_src,
+                &[
+                    format!("[imports.{import_name}]"),
+                    format!("[imports.\"{import_name}\"]"),
+                ],
+            ),
+        }
+    }
+}
+
+/// Convert a range of byte offsets in `source` into a SARIF region. Returns
+/// `None` for empty ranges, such as the spans of entries which weren't parsed
+/// from `source`.
+fn sarif_region(source: &str, start: usize, end: usize) -> Option<SarifRegion> {
+    if start >= end || source.get(start..end).is_none() {
+        return None;
+    }
+    let position = |offset: usize| {
+        let before = &source[..offset];
+        let line_start = before.rfind('\n').map_or(0, |idx| idx + 1);
+        (
+            before.matches('\n').count() + 1,
+            before[line_start..].chars().count() + 1,
+        )
+    };
+    let (start_line, start_column) = position(start);
+    let (end_line, end_column) = position(end);
+    Some(SarifRegion {
+        start_line,
+        start_column,
+        end_line,
+        end_column,
+    })
 }
 
 impl Success {
     pub fn print_human(
         &self,
         out: &Arc<dyn Out>,
-        _report: &ResolveReport<'_>,
+        report: &ResolveReport<'_>,
         _cfg: &Config,
     ) -> Result<(), std::io::Error> {
         let fully_audited_count = self.vetted_fully.len();
@@ -2216,7 +4383,12 @@ impl Success {
             + (exemptions_count != 0) as usize;
 
         // Print out a summary of how we succeeded
-        if count_count == 0 {
+        if count_count == 0 && report.baseline_failures.is_some() {
+            writeln!(
+                out,
+                "Vetting Succeeded (because there are no new third-party dependencies)"
+            );
+        } else if count_count == 0 {
             writeln!(
                 out,
                 "Vetting Succeeded (because you have no third-party dependencies)"
@@ -2280,10 +4452,14 @@ impl Suggest {
                         .clone()
                         .unwrap_or_else(|| "UNKNOWN".into());
                     let parents = FormatShortList::string(item.notable_parents.clone());
-                    let diffstat = match &item.suggested_diff.from {
+                    let mut diffstat = match &item.suggested_diff.from {
                         Some(_) => format!("{}", item.suggested_diff.diffstat),
                         None => format!("{} lines", item.suggested_diff.diffstat.count()),
                     };
+                    let cost = &item.suggested_diff.cost;
+                    if cost.weighted != cost.raw {
+                        diffstat.push_str(&format!(" ({} weighted)", cost.weighted));
+                    }
                     (cmd, publisher, parents, diffstat, item)
                 })
                 .collect::<Vec<_>>();
@@ -2344,6 +4520,22 @@ impl Suggest {
                             }),
                     );
                 }
+                if !item.suggested_diff.risks.is_empty() {
+                    let kinds = item
+                        .suggested_diff
+                        .risks
+                        .iter()
+                        .map(|risk| risk.kind.to_string())
+                        .collect::<Vec<_>>();
+                    writeln!(
+                        out,
+                        "      {}",
+                        dim.clone().apply_to(format_args!(
+                            "NOTE: introduces {} - see the risk indicators when auditing",
+                            FormatShortList::new(kinds)
+                        )),
+                    );
+                }
                 if let Some(hint) = &item.trust_hint {
                     let trust = if hint.trusted_by.len() == 1 {
                         "trusts"
@@ -2384,7 +4576,11 @@ impl Suggest {
             writeln!(out);
         }
 
-        writeln!(out, "estimated audit backlog: {} lines", self.total_lines);
+        write!(out, "estimated audit backlog: {} lines", self.total_lines);
+        if self.total_weighted_lines != self.total_lines {
+            write!(out, " ({} weighted)", self.total_weighted_lines);
+        }
+        writeln!(out);
 
         if !self.warnings.is_empty() {
             writeln!(out);
@@ -2535,6 +4731,7 @@ impl FailForViolationConflict {
     }
 }
 
+#[allow(clippy::too_many_arguments)]
 async fn suggest_delta(
     metadata: &cargo_metadata::Metadata,
     network: Option<&Network>,
@@ -2542,12 +4739,17 @@ async fn suggest_delta(
     package_name: PackageStr<'_>,
     package_version: &VetVersion,
     failures: impl Iterator<Item = &SearchFailure>,
+    refresh_from: &[Option<VetVersion>],
+    weights: &DiffWeights,
     warnings: &RefCell<Vec<String>>,
 ) -> Option<(DiffRecommendation, Option<DiffRecommendation>)> {
     // Fetch the set of known versions from crates.io so we know which versions
     // we'll have sources for.
     let known_versions = if let Some(network) = network {
-        cache.published_versions(network, package_name).await.ok()
+        cache
+            .published_versions(network, metadata, package_name)
+            .await
+            .ok()
     } else {
         None
     };
@@ -2557,6 +4759,23 @@ async fn suggest_delta(
         from_root: SortedSet<&'a Option<VetVersion>>,
         from_target: SortedSet<&'a Option<VetVersion>>,
     }
+    let version_has_sources = |ver: &&Option<VetVersion>| -> bool {
+        // We always have sources for an empty crate.
+        let Some(ver) = ver else {
+            return true;
+        };
+        // We only have git sources for the package itself.
+        if ver.git_rev.is_some() {
+            return ver == package_version;
+        }
+        // We have sources if the version has been published to crates.io.
+        //
+        // For testing fallbacks or when we're offline, assume we always
+        // have sources if the index is unavailable.
+        known_versions
+            .as_ref()
+            .map_or(true, |versions| versions.contains_key(&ver.semver))
+    };
     let mut reachable = None::<Reachable<'_>>;
     for SearchFailure {
         reachable_from_root,
@@ -2574,23 +4793,6 @@ async fn suggest_delta(
             from_root.retain(|ver| reachable_from_root.contains(ver));
             from_target.retain(|ver| reachable_from_target.contains(ver));
         } else {
-            let version_has_sources = |ver: &&Option<VetVersion>| -> bool {
-                // We always have sources for an empty crate.
-                let Some(ver) = ver else {
-                    return true;
-                };
-                // We only have git sources for the package itself.
-                if ver.git_rev.is_some() {
-                    return ver == package_version;
-                }
-                // We have sources if the version has been published to crates.io.
-                //
-                // For testing fallbacks or when we're offline, assume we always
-                // have sources if the index is unavailable.
-                known_versions
-                    .as_ref()
-                    .map_or(true, |versions| versions.contains_key(&ver.semver))
-            };
             reachable = Some(Reachable {
                 from_root: reachable_from_root
                     .iter()
@@ -2604,6 +4806,19 @@ async fn suggest_delta(
         }
     }
 
+    // Versions with stale full audits are no longer reachable from the root,
+    // but are still the best starting points for a delta to refresh them.
+    if let Some(Reachable {
+        from_root,
+        from_target,
+    }) = &mut reachable
+    {
+        for ver in refresh_from.iter().filter(version_has_sources) {
+            from_target.remove(ver);
+            from_root.insert(ver);
+        }
+    }
+
     let Some(Reachable {
         from_root,
         from_target,
@@ -2619,6 +4834,11 @@ async fn suggest_delta(
                     deletions: 0,
                     files_changed: 0,
                 },
+                cost: DiffCost {
+                    raw: 0,
+                    weighted: 0,
+                },
+                risks: vec![],
             },
             None,
         ));
@@ -2683,8 +4903,13 @@ async fn suggest_delta(
             .fetch_and_diffstat_package(metadata, network, package_name, &delta)
             .await
         {
-            Ok(diffstat) => Some(DiffRecommendation {
-                diffstat,
+            Ok(diff) => Some(DiffRecommendation {
+                cost: DiffCost {
+                    raw: diff.diffstat.count(),
+                    weighted: weights.weigh(&diff),
+                },
+                diffstat: diff.diffstat,
+                risks: diff.risks,
                 from: delta.from.clone(),
                 to: delta.to.clone(),
             }),
@@ -2712,11 +4937,26 @@ async fn suggest_delta(
     let recommendation = diffstats
         .into_iter()
         .flatten()
-        .min_by_key(|diff| diff.diffstat.count())?;
+        .min_by_key(|diff| diff.cost.weighted)?;
 
     Some((recommendation, extra_diffstat))
 }
 
+/// The versions of a package with stale full audits, which make good starting
+/// points for a delta audit to refresh them.
+fn stale_audit_versions(store: &Store, package: PackageStr<'_>) -> Vec<Option<VetVersion>> {
+    std::iter::once(&store.audits)
+        .chain(store.imported_audits().values())
+        .filter_map(|audits_file| audits_file.audits.get(package))
+        .flatten()
+        .filter(|entry| entry.is_stale)
+        .filter_map(|entry| match &entry.kind {
+            AuditKind::Full { version } => Some(Some(version.clone())),
+            _ => None,
+        })
+        .collect()
+}
+
 /// Resolve which entries in the store and imports.lock are required to
 /// successfully audit this package. May return `None` if the package cannot
 /// successfully vet given the restrictions placed upon it.
@@ -2900,7 +5140,14 @@ pub(crate) fn get_store_updates(
         Some(&store.config.policy),
     );
     let criteria_mapper = CriteriaMapper::new(&store.audits.criteria);
-    let requirements = resolve_requirements(&graph, &store.config.policy, &criteria_mapper);
+    let (requirements, _) = resolve_feature_requirements(
+        &graph,
+        &cfg.feature_metadata,
+        &cfg.target_cfgs,
+        cfg.cli.filter_graph.as_ref(),
+        &store.config,
+        &criteria_mapper,
+    );
 
     let mut required_entries = SortedMap::new();
     for package in &graph.nodes {
@@ -3177,7 +5424,9 @@ pub(crate) fn get_store_updates(
                         .map(|n| n.to_owned().into())
                         .collect(),
                     suggest: true,
+                    end: entry.end.clone(),
                     notes: None,
+                    is_expired: entry.is_expired,
                 });
                 useful_criteria = original_criteria;
             }
@@ -3190,7 +5439,9 @@ pub(crate) fn get_store_updates(
                     .map(|n| n.to_owned().into())
                     .collect(),
                 suggest: entry.suggest,
+                end: entry.end.clone(),
                 notes: entry.notes.clone(),
+                is_expired: entry.is_expired,
             });
         }
         if !new_exemptions.is_empty() {
@@ -3213,6 +5464,27 @@ pub(crate) fn get_store_updates(
                 break;
             };
 
+            // Keep the end date of the existing exemptions for this version, so
+            // that regenerating exemptions doesn't turn an expiring (or
+            // expired) exemption into a permanent one.
+            let existing: Vec<_> = store
+                .config
+                .exemptions
+                .get(pkgname)
+                .into_iter()
+                .flatten()
+                .filter(|exemption| &exemption.version == version)
+                .collect();
+            let end = if existing.iter().any(|exemption| exemption.end.is_none()) {
+                None
+            } else {
+                existing
+                    .iter()
+                    .filter_map(|exemption| exemption.end.clone())
+                    .max()
+            };
+            let is_expired = end.as_ref().is_some_and(|end| **end < cfg.today());
+
             all_new_exemptions
                 .entry(pkgname.to_owned())
                 .or_default()
@@ -3223,7 +5495,9 @@ pub(crate) fn get_store_updates(
                         .map(|n| n.to_owned().into())
                         .collect(),
                     suggest: true,
+                    end,
                     notes: None,
+                    is_expired,
                 });
         }
     }
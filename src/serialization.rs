@@ -303,6 +303,8 @@ pub mod audit {
         version: Option<VetVersion>,
         delta: Option<Delta>,
         violation: Option<VersionReq>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        date: Option<chrono::NaiveDate>,
         importable: Option<bool>,
         notes: Option<String>,
         #[serde(rename = "aggregated-from")]
@@ -336,10 +338,12 @@ pub mod audit {
                 criteria: val.criteria,
                 kind: kind?,
                 importable: val.importable.unwrap_or(true),
+                date: val.date,
                 aggregated_from: val.aggregated_from,
                 // By default, always read entries as non-fresh. The import code
                 // will set this flag to true for imported entries.
                 is_fresh_import: false,
+                is_stale: false,
             })
         }
     }
@@ -365,6 +369,7 @@ pub mod audit {
                 version,
                 delta,
                 violation,
+                date: val.date,
                 importable: if val.importable { None } else { Some(false) },
                 aggregated_from: val.aggregated_from,
             }
@@ -825,8 +830,17 @@ mod test {
                     version: StoreVersion { major: 1, minor: 0 },
                 },
                 default_criteria: get_default_criteria(),
+                on_fetch_failure: Default::default(),
+                diff_weights: Default::default(),
+                stale_audits: None,
                 imports: SortedMap::new(),
+                registries: SortedMap::new(),
+                workspaces: Vec::new(),
+                filter_platforms: Vec::new(),
+                platform_criteria: SortedMap::new(),
+                feature_configs: SortedMap::new(),
                 policy,
+                disputes: SortedMap::new(),
                 exemptions: SortedMap::new(),
             },
             None,
@@ -14,6 +14,7 @@ use futures_util::future::{join_all, try_join_all};
 use miette::SourceOffset;
 use reqwest::Url;
 use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
 use similar::{udiff::unified_diff, Algorithm};
 use tar::Archive;
 use tracing::{error, info, log::warn, trace};
@@ -22,23 +23,27 @@ use crate::{
     cli::FetchMode,
     criteria::CriteriaMapper,
     errors::{
-        AggregateError, BadFormatError, BadWildcardEndDateError, CacheAcquireError,
-        CacheCommitError, CertifyError, CommandError, CrateInfoError, CriteriaChangeError,
-        CriteriaChangeErrors, DiffError, DownloadError, FetchAndDiffError,
-        FetchAuditAggregateError, FetchAuditError, FetchError, FetchRegistryError, FlockError,
-        InvalidCriteriaError, JsonParseError, LoadJsonError, LoadTomlError, SourceFile,
-        StoreAcquireError, StoreCommitError, StoreCreateError, StoreJsonError, StoreTomlError,
-        StoreValidateError, StoreValidateErrors, TomlParseError, UnpackCheckoutError, UnpackError,
+        AggregateError, BadExemptionEndDateError, BadFormatError, BadWildcardEndDateError,
+        CacheAcquireError, CacheCommitError, CertifyError, CommandError, CrateInfoError,
+        CriteriaChangeError, CriteriaChangeErrors, CriteriaDefinitionError, DiffError,
+        DownloadError, FetchAndDiffError, FetchAuditAggregateError, FetchAuditError, FetchError,
+        FetchRegistryError, FlockError, ImportSourceError, InvalidCriteriaError, JsonParseError,
+        LoadJsonError, LoadTomlError, SignatureError, SourceFile, StoreAcquireError,
+        StoreCommitError, StoreCreateError, StoreJsonError, StoreTomlError, StoreValidateError,
+        StoreValidateErrors, TomlParseError, UnknownDisputeImportError, UnpackCheckoutError,
+        UnpackError,
     },
     flock::{FileLock, Filesystem},
     format::{
-        self, AuditEntry, AuditedDependencies, AuditsFile, CommandHistory, ConfigFile,
+        self, AuditEntry, AuditKind, AuditedDependencies, AuditsFile, CommandHistory, ConfigFile,
         CratesAPICrate, CratesCache, CratesCacheEntry, CratesCacheUser, CratesCacheVersionDetails,
         CratesPublisher, CratesUserId, CriteriaEntry, CriteriaMap, CriteriaName, CriteriaStr,
-        Delta, DiffCache, DiffStat, FastMap, FastSet, FetchCommand, ForeignAuditsFile, ImportName,
-        ImportsFile, MetaConfig, PackageName, PackageStr, RegistryEntry, RegistryFile, SortedMap,
-        StoreVersion, TrustEntry, TrustedPackages, UnpublishedEntry, VetVersion, WildcardAudits,
-        WildcardEntry, SAFE_TO_DEPLOY, SAFE_TO_RUN,
+        Delta, DiffCache, DiffStat, FastMap, FastSet, FetchCommand, FetchFailurePolicy,
+        FileDiffStat, ForeignAuditsFile, ImportName, ImportsFile, MetaConfig, PackageDiff,
+        PackageName, PackageStr, RegistryEntry, RegistryFile, RemoteImport, RiskIndicator,
+        RiskKind, SortedMap, SortedSet, StoreVersion, TrustEntry, TrustedPackages,
+        UnpublishedEntry, VetVersion, ViolationDispute, WildcardAudits, WildcardEntry,
+        SAFE_TO_DEPLOY, SAFE_TO_RUN,
     },
     network::Network,
     out::{progress_bar, IncProgressOnDrop},
@@ -54,6 +59,7 @@ const CACHE_EMPTY_PACKAGE: &str = "empty";
 const CACHE_REGISTRY_SRC: &str = "src";
 const CACHE_REGISTRY_CACHE: &str = "cache";
 const CACHE_VET_LOCK: &str = ".vet-lock";
+const CACHE_GIT_IMPORTS: &str = "git-imports";
 
 // Files which are allowed to appear in the root of the cache directory, and
 // will not be GC'd
@@ -65,6 +71,7 @@ const CACHE_ALLOWED_FILES: &[&str] = &[
     CACHE_REGISTRY_SRC,
     CACHE_REGISTRY_CACHE,
     CACHE_VET_LOCK,
+    CACHE_GIT_IMPORTS,
 ];
 
 // Various cargo values
@@ -78,9 +85,17 @@ const CARGO_OK_BODY: &str = "ok";
 
 pub const DEFAULT_STORE: &str = "supply-chain";
 
-const AUDITS_TOML: &str = "audits.toml";
-const CONFIG_TOML: &str = "config.toml";
-const IMPORTS_LOCK: &str = "imports.lock";
+pub(crate) const AUDITS_TOML: &str = "audits.toml";
+pub(crate) const CONFIG_TOML: &str = "config.toml";
+pub(crate) const IMPORTS_LOCK: &str = "imports.lock";
+
+// Suffix added to the URL of an imported audits.toml to find its signature.
+const SIGNATURE_SUFFIX: &str = ".minisig";
+
+// Prefix of imports which are fetched from a git repository, and the path of
+// the audits file within the repository if none is specified.
+const GIT_IMPORT_PREFIX: &str = "git+";
+const GIT_IMPORT_DEFAULT_PATH: &str = "supply-chain/audits.toml";
 
 // Files which are skipped when counting changes for diffs.
 const DIFF_SKIP_PATHS: &[&str] = &["Cargo.lock", ".cargo_vcs_info.json", ".cargo-ok"];
@@ -88,6 +103,9 @@ const DIFF_SKIP_PATHS: &[&str] = &["Cargo.lock", ".cargo_vcs_info.json", ".cargo
 // FIXME: This is a completely arbitrary number, and may be too high or too low.
 const MAX_CONCURRENT_DIFFS: usize = 40;
 
+// Url of the crates.io sparse index.
+const CRATES_IO_INDEX: &str = "https://index.crates.io/";
+
 // Url of the registry.
 pub const REGISTRY_URL: &str =
     "https://raw.githubusercontent.com/mozilla/cargo-vet/main/registry.toml";
@@ -166,8 +184,17 @@ impl Store {
             config: ConfigFile {
                 cargo_vet: Default::default(),
                 default_criteria: format::get_default_criteria(),
+                on_fetch_failure: Default::default(),
+                diff_weights: Default::default(),
+                stale_audits: None,
                 imports: SortedMap::new(),
+                registries: SortedMap::new(),
+                workspaces: Vec::new(),
+                filter_platforms: Vec::new(),
+                platform_criteria: SortedMap::new(),
+                feature_configs: SortedMap::new(),
                 policy: Default::default(),
+                disputes: SortedMap::new(),
                 exemptions: SortedMap::new(),
             },
             imports: ImportsFile {
@@ -193,6 +220,18 @@ impl Store {
         metacfg.store_path().as_path_unlocked().exists()
     }
 
+    /// Read the store's config.toml without acquiring the store.
+    ///
+    /// This is needed before the store can be acquired, as the metadata must
+    /// be merged with the metadata of the other `workspaces` sharing the
+    /// store, and updated to use qualified names for packages from alternate
+    /// `registries`.
+    pub fn read_config(metacfg: &MetaConfig) -> Result<ConfigFile, StoreAcquireError> {
+        let path = metacfg.store_path().as_path_unlocked().join(CONFIG_TOML);
+        let (_, config): (_, ConfigFile) = load_toml(CONFIG_TOML, File::open(path)?)?;
+        Ok(config)
+    }
+
     pub fn acquire_offline(cfg: &Config) -> Result<Self, StoreAcquireError> {
         let root = cfg.metacfg.store_path();
 
@@ -220,7 +259,7 @@ impl Store {
         let (imports_src, imports): (_, ImportsFile) =
             load_toml(IMPORTS_LOCK, lock.read_imports()?)?;
 
-        let store = Self {
+        let mut store = Self {
             lock: Some(lock),
             config,
             audits,
@@ -234,6 +273,9 @@ impl Store {
         // Check that the store isn't corrupt
         store.validate(cfg.today(), cfg.cli.locked)?;
 
+        store.mark_stale_audits(&cfg.metadata, cfg.today());
+        store.mark_expired_exemptions(cfg.today());
+
         Ok(store)
     }
 
@@ -257,6 +299,7 @@ impl Store {
             ))?;
 
             this.validate(cfg.today(), cfg.cli.locked)?;
+            this.mark_stale_audits(&cfg.metadata, cfg.today());
         }
         Ok(this)
     }
@@ -275,10 +318,20 @@ impl Store {
         // If this command isn't locked, and the network is available, fetch the
         // live state of imported audits.
         let local_criteria_mapper = CriteriaMapper::new(&self.audits.criteria);
-        let fetched_audits =
-            fetch_imported_audits(network, &local_criteria_mapper, &self.config).await?;
-        let mut live_imports =
-            process_imported_audits(fetched_audits, &self.imports, allow_criteria_changes)?;
+        let fetched_audits = fetch_imported_audits(
+            network,
+            cache,
+            &local_criteria_mapper,
+            &self.config,
+            &self.imports,
+        )
+        .await?;
+        let mut live_imports = process_imported_audits(
+            fetched_audits,
+            &self.config,
+            &self.imports,
+            allow_criteria_changes,
+        )?;
         import_unpublished_entries(
             &cfg.metadata,
             network,
@@ -334,15 +387,17 @@ impl Store {
         network: &Network,
         allow_criteria_changes: bool,
     ) -> Result<Self, StoreAcquireError> {
+        let cache = Cache::acquire(cfg).map_err(Box::new)?;
         let local_criteria_mapper = CriteriaMapper::new(&audits.criteria);
         let fetched_audits = tokio::runtime::Handle::current().block_on(fetch_imported_audits(
             network,
+            &cache,
             &local_criteria_mapper,
             &config,
+            &imports,
         ))?;
         let mut live_imports =
-            process_imported_audits(fetched_audits, &imports, allow_criteria_changes)?;
-        let cache = Cache::acquire(cfg).map_err(Box::new)?;
+            process_imported_audits(fetched_audits, &config, &imports, allow_criteria_changes)?;
         tokio::runtime::Handle::current()
             .block_on(import_unpublished_entries(
                 &cfg.metadata,
@@ -384,19 +439,19 @@ impl Store {
         Ok(store)
     }
 
-    #[cfg(test)]
-    pub fn mock_acquire(
+    /// Load a store from the contents of its files, without acquiring a lock
+    /// or validating it. This is used to inspect other revisions of the store,
+    /// such as when diffing it with `cargo vet store-diff`.
+    pub fn from_sources(
         config: &str,
         audits: &str,
         imports: &str,
-        today: chrono::NaiveDate,
-        check_file_formatting: bool,
     ) -> Result<Self, StoreAcquireError> {
         let (config_src, config): (_, ConfigFile) = load_toml(CONFIG_TOML, config.as_bytes())?;
         let (audits_src, audits): (_, AuditsFile) = load_toml(AUDITS_TOML, audits.as_bytes())?;
         let (imports_src, imports): (_, ImportsFile) = load_toml(IMPORTS_LOCK, imports.as_bytes())?;
 
-        let store = Self {
+        Ok(Self {
             lock: None,
             config,
             imports,
@@ -405,13 +460,97 @@ impl Store {
             config_src,
             audits_src,
             imports_src,
-        };
+        })
+    }
+
+    #[cfg(test)]
+    pub fn mock_acquire(
+        config: &str,
+        audits: &str,
+        imports: &str,
+        today: chrono::NaiveDate,
+        check_file_formatting: bool,
+    ) -> Result<Self, StoreAcquireError> {
+        let store = Self::from_sources(config, audits, imports)?;
 
         store.validate(today, check_file_formatting)?;
 
         Ok(store)
     }
 
+    /// Mark the full audits which are too old to satisfy the criteria in the
+    /// `stale-audits` policy, given the versions of each crate in use.
+    ///
+    /// A full audit is stale if it was recorded before the policy's cutoff, the
+    /// newest version of the crate in use isn't semver-compatible with the
+    /// audited version, and no delta audit from the audited version has been
+    /// recorded since the cutoff to refresh it.
+    pub fn mark_stale_audits(
+        &mut self,
+        metadata: &cargo_metadata::Metadata,
+        today: chrono::NaiveDate,
+    ) {
+        let Some(policy) = &self.config.stale_audits else {
+            return;
+        };
+        let cutoff = today - chrono::Months::new(12 * policy.max_age_years);
+
+        let mut newest_versions: FastMap<&str, &semver::Version> = FastMap::new();
+        for package in &metadata.packages {
+            let newest = newest_versions
+                .entry(&package.name)
+                .or_insert(&package.version);
+            if *newest < &package.version {
+                *newest = &package.version;
+            }
+        }
+
+        let imported_audits = match &mut self.live_imports {
+            Some(live_imports) => &mut live_imports.audits,
+            None => &mut self.imports.audits,
+        };
+        let mut all_audits: Vec<&mut SortedMap<PackageName, Vec<AuditEntry>>> =
+            vec![&mut self.audits.audits];
+        all_audits.extend(imported_audits.values_mut().map(|file| &mut file.audits));
+
+        let mut refreshed = FastSet::new();
+        for audits in &all_audits {
+            for (package, entries) in audits.iter() {
+                for entry in entries {
+                    if let AuditKind::Delta { from, .. } = &entry.kind {
+                        if entry.date.is_some_and(|date| date >= cutoff) {
+                            refreshed.insert((package.clone(), from.clone()));
+                        }
+                    }
+                }
+            }
+        }
+
+        for audits in all_audits {
+            for (package, entries) in audits.iter_mut() {
+                let Some(newest) = newest_versions.get(&package[..]) else {
+                    continue;
+                };
+                for entry in entries {
+                    let AuditKind::Full { version } = &entry.kind else {
+                        continue;
+                    };
+                    entry.is_stale = entry.date.is_some_and(|date| date < cutoff)
+                        && !is_semver_compatible(&version.semver, newest)
+                        && !refreshed.contains(&(package.clone(), version.clone()));
+                }
+            }
+        }
+    }
+
+    /// Mark the exemptions whose end date is before `today`, so that they're
+    /// no longer used to vet packages.
+    pub fn mark_expired_exemptions(&mut self, today: chrono::NaiveDate) {
+        for entry in self.config.exemptions.values_mut().flatten() {
+            entry.is_expired = entry.expires_before(today);
+        }
+    }
+
     /// Create a clone of the store for use to resolve `suggest`.
     ///
     /// If `clear_exemptions` is passed, this cloned store will not contain
@@ -462,6 +601,41 @@ impl Store {
         }
     }
 
+    /// Returns the imported violations of `package` which have been disputed
+    /// in the config, along with the import they came from and the dispute.
+    pub fn disputed_violations(
+        &self,
+        package: PackageStr<'_>,
+    ) -> Vec<(&ImportName, &AuditEntry, &ViolationDispute)> {
+        let Some(disputes) = self.config.disputes.get(package) else {
+            return vec![];
+        };
+        let mut disputed = vec![];
+        for (import, audits_file) in self.imported_audits() {
+            for entry in audits_file.audits.get(package).into_iter().flatten() {
+                if let Some(dispute) = disputes.iter().find(|d| d.matches(import, entry)) {
+                    disputed.push((import, entry, dispute));
+                }
+            }
+        }
+        disputed
+    }
+
+    /// Returns the disputes in the config which don't match any imported
+    /// violation, and so have no effect.
+    pub fn unmatched_disputes(&self) -> Vec<(&PackageName, &ViolationDispute)> {
+        let mut unmatched = vec![];
+        for (package, disputes) in &self.config.disputes {
+            let disputed = self.disputed_violations(package);
+            for dispute in disputes {
+                if !disputed.iter().any(|(_, _, d)| std::ptr::eq(*d, dispute)) {
+                    unmatched.push((package, dispute));
+                }
+            }
+        }
+        unmatched
+    }
+
     /// Returns the set of publisher information which should be operated upon.
     ///
     /// If the store was acquired unlocked, whis may include publisher
@@ -545,7 +719,6 @@ impl Store {
         // * check that lockfile and imports aren't desync'd (catch new/removed import urls)
         //
         // * check that each CriteriaEntry has 'description' or 'description_url'
-        // * check that no one is trying to shadow builtin criteria (safe-to-run, safe-to-deploy)
         // * check that all 'audits' entries are well-formed
         // * check that all package names are valid (with crates.io...?)
         // * check that all reviews have a 'who' (currently an Option to stub it out)
@@ -592,6 +765,51 @@ impl Store {
                     &mut errors,
                     &entry.criteria,
                 );
+
+                if let Some(end) = entry.end.as_ref().filter(|end| ***end > max_end_date) {
+                    errors.push(StoreValidateError::BadExemptionEndDate(
+                        BadExemptionEndDateError {
+                            source_code: self.config_src.clone(),
+                            span: Spanned::span(end),
+                            date: **end,
+                            max: max_end_date,
+                        },
+                    ))
+                }
+            }
+        }
+        if let Some(stale_audits) = &self.config.stale_audits {
+            check_criteria(
+                &self.config_src,
+                &valid_criteria,
+                &mut errors,
+                &stale_audits.criteria,
+            );
+        }
+        for import in self.config.imports.values() {
+            check_criteria(
+                &self.config_src,
+                &valid_criteria,
+                &mut errors,
+                &import.max_criteria,
+            );
+        }
+        for (package, disputes) in &self.config.disputes {
+            for dispute in disputes {
+                if !self.config.imports.contains_key(&*dispute.import) {
+                    errors.push(StoreValidateError::UnknownDisputeImport(
+                        UnknownDisputeImportError {
+                            source_code: self.config_src.clone(),
+                            span: Spanned::span(&dispute.import),
+                            import: dispute.import.to_string(),
+                        },
+                    ));
+                }
+                if dispute.notes.trim().is_empty() {
+                    errors.push(StoreValidateError::EmptyDisputeNotes {
+                        package: package.clone(),
+                    });
+                }
             }
         }
         for (_name, _version, policy) in &self.config.policy {
@@ -611,8 +829,23 @@ impl Store {
                 check_criteria(&self.config_src, &valid_criteria, &mut errors, dep_criteria);
             }
         }
+        for (_platform, platform_criteria) in &self.config.platform_criteria {
+            check_criteria(
+                &self.config_src,
+                &valid_criteria,
+                &mut errors,
+                platform_criteria,
+            );
+        }
+        for (_name, feature_config) in &self.config.feature_configs {
+            check_criteria(
+                &self.config_src,
+                &valid_criteria,
+                &mut errors,
+                feature_config.criteria.as_ref().unwrap_or(&no_criteria),
+            );
+        }
         for (_new_criteria, entry) in &self.audits.criteria {
-            // TODO: check that new_criteria isn't shadowing a builtin criteria
             check_criteria(
                 &self.audits_src,
                 &valid_criteria,
@@ -620,9 +853,24 @@ impl Store {
                 &entry.implies,
             );
         }
+        let diff_weights = &self.config.diff_weights;
+        for &weight in [diff_weights.unsafe_lines, diff_weights.proc_macro]
+            .iter()
+            .chain(diff_weights.files.iter().map(|rule| &rule.weight))
+        {
+            if !weight.is_finite() || weight < 0.0 {
+                errors.push(StoreValidateError::BadDiffWeight { weight });
+            }
+        }
+
+        // Unknown implied criteria were reported above, but shadowed built-ins
+        // and implies cycles would otherwise panic when building the mapper.
+        match CriteriaMapper::try_new(&self.audits.criteria) {
+            Ok(_) | Err(CriteriaDefinitionError::UnknownImplied { .. }) => {}
+            Err(err) => errors.push(StoreValidateError::CriteriaDefinition(err)),
+        }
         for (_package, entries) in &self.audits.audits {
             for entry in entries {
-                // TODO: check that new_criteria isn't shadowing a builtin criteria
                 check_criteria(
                     &self.audits_src,
                     &valid_criteria,
@@ -728,6 +976,15 @@ impl Store {
                     return true;
                 }
             }
+            // Similarly, if it has any entries which are no longer allowed to
+            // be imported, it needs to be regenerated.
+            if config.filters_entries() {
+                let mut filtered = audits_file.clone();
+                filter_imported_entries(config, &mut filtered);
+                if filtered != *audits_file {
+                    return true;
+                }
+            }
         }
 
         false
@@ -793,13 +1050,17 @@ impl Store {
                         let existing_entry = self.config.imports.get(&name);
                         fetch_imported_audit(
                             network,
+                            cache,
                             &local_criteria_mapper,
                             &name,
                             &entry.url,
+                            &[],
+                            &[],
                             existing_entry.map(|e| &e.exclude[..]).unwrap_or(&[]),
                             existing_entry
                                 .map(|e| &e.criteria_map)
                                 .unwrap_or(&SortedMap::new()),
+                            existing_entry.map(|e| &e.public_key[..]).unwrap_or(&[]),
                         )
                         .await
                         .map_err(|error| {
@@ -842,10 +1103,276 @@ impl Store {
     }
 }
 
+/// The name of the directory in the cache holding the clone of `repo`.
+fn git_clone_name(repo: &str) -> String {
+    let base_name: String = repo
+        .trim_end_matches('/')
+        .rsplit('/')
+        .next()
+        .unwrap_or_default()
+        .trim_end_matches(".git")
+        .chars()
+        .filter(|c| c.is_ascii_alphanumeric() || *c == '-' || *c == '_')
+        .collect();
+    let hash = format!("{:x}", Sha256::digest(repo.as_bytes()));
+    format!("{base_name}-{}", &hash[..16])
+}
+
+/// Run a git command in `dir`, returning its output.
+async fn run_git(dir: &Path, args: &[&str]) -> Result<String, CommandError> {
+    let out = tokio::process::Command::new("git")
+        .arg("-C")
+        .arg(dir)
+        .args(args)
+        .env("GIT_TERMINAL_PROMPT", "0")
+        .stderr(std::process::Stdio::inherit())
+        .output()
+        .await
+        .map_err(CommandError::CommandFailed)?;
+    if !out.status.success() {
+        return Err(CommandError::BadStatus(out.status.code().unwrap_or(-1)));
+    }
+    String::from_utf8(out.stdout).map_err(CommandError::BadOutput)
+}
+
+/// Where an imported audits.toml (or a file stored alongside it) is fetched
+/// from.
+enum ImportSource {
+    /// An `https://` or `file://` URL.
+    Url(Url),
+    /// A file within a git repository, specified as
+    /// `git+{repo}#path={path}&rev={rev}`.
+    Git {
+        repo: String,
+        path: String,
+        rev: Option<String>,
+    },
+}
+
+impl ImportSource {
+    fn parse(url: &str) -> Result<Self, url::ParseError> {
+        let Some(repo) = url.strip_prefix(GIT_IMPORT_PREFIX) else {
+            return Url::parse(url).map(ImportSource::Url);
+        };
+        let mut repo = Url::parse(repo)?;
+        let mut path = GIT_IMPORT_DEFAULT_PATH.to_owned();
+        let mut rev = None;
+        for (key, value) in url::form_urlencoded::parse(repo.fragment().unwrap_or("").as_bytes()) {
+            match &key[..] {
+                "path" => path = value.into_owned(),
+                "rev" => rev = Some(value.into_owned()),
+                _ => warn!("ignoring unknown key '{key}' in git import {url}"),
+            }
+        }
+        repo.set_fragment(None);
+        Ok(ImportSource::Git {
+            repo: repo.into(),
+            path,
+            rev,
+        })
+    }
+
+    /// Whether this source is a file on the local machine, either directly or
+    /// in a local git repository.
+    fn is_local(&self) -> bool {
+        match self {
+            ImportSource::Url(url) => url.scheme() == "file",
+            ImportSource::Git { repo, .. } => {
+                Url::parse(repo).is_ok_and(|url| url.scheme() == "file")
+            }
+        }
+    }
+
+    /// Parse the import URL `url` of the import `name`, fetching git sources
+    /// from the commit `rev` instead of their own rev, if one is specified.
+    fn parse_pinned(name: &str, url: &str, rev: Option<&str>) -> Result<Self, FetchAuditError> {
+        let source = ImportSource::parse(url).map_err(|error| FetchAuditError::InvalidUrl {
+            import_url: url.to_owned(),
+            import_name: name.to_owned(),
+            error,
+        })?;
+        match (source, rev) {
+            (source, None) => Ok(source),
+            (ImportSource::Git { repo, path, .. }, Some(rev)) => Ok(ImportSource::Git {
+                repo,
+                path,
+                rev: Some(rev.to_owned()),
+            }),
+            (ImportSource::Url(_), Some(_)) => Err(FetchAuditError::RevPinNotGit {
+                import_name: name.to_owned(),
+                import_url: url.to_owned(),
+            }),
+        }
+    }
+
+    /// The source of the file stored next to this one, with `suffix` appended
+    /// to its name.
+    fn with_suffix(&self, suffix: &str) -> Self {
+        match self {
+            ImportSource::Url(url) => {
+                let mut url = url.clone();
+                url.set_path(&format!("{}{suffix}", url.path()));
+                ImportSource::Url(url)
+            }
+            ImportSource::Git { repo, path, rev } => ImportSource::Git {
+                repo: repo.clone(),
+                path: format!("{path}{suffix}"),
+                rev: rev.clone(),
+            },
+        }
+    }
+
+    /// Fetch the file, naming the resulting `SourceFile` with `name`.
+    async fn fetch(
+        &self,
+        network: &Network,
+        cache: &Cache,
+        name: &str,
+    ) -> Result<SourceFile, ImportSourceError> {
+        match self {
+            // Local files are read directly, so that they can be used without a
+            // web server. Only imports from the local config can name them, as
+            // `fetch_registry` drops registry entries with local URLs.
+            ImportSource::Url(url) if url.scheme() == "file" => {
+                let target = url
+                    .to_file_path()
+                    .map_err(|()| DownloadError::FailedToReadFile {
+                        target: url.to_string().into(),
+                        error: std::io::Error::new(
+                            std::io::ErrorKind::InvalidInput,
+                            "not a local file path",
+                        ),
+                    })?;
+                let contents = tokio::fs::read_to_string(&target)
+                    .await
+                    .map_err(|error| DownloadError::FailedToReadFile { target, error })?;
+                Ok(SourceFile::new(url.as_str(), contents))
+            }
+            ImportSource::Url(url) => Ok(network.download_source_file_cached(url.clone()).await?),
+            ImportSource::Git { repo, path, rev } => {
+                let contents = cache.fetch_git_file(repo, rev.as_deref(), path).await?;
+                Ok(SourceFile::new(name, contents))
+            }
+        }
+    }
+}
+
+/// Fetch the current sha256 digest of each of an import's URLs, to be used as
+/// its pins. Git sources are fetched from the commits in `revs`, if specified.
+pub async fn fetch_import_pins(
+    network: &Network,
+    cache: &Cache,
+    name: &str,
+    urls: &[String],
+    revs: &[String],
+) -> Result<Vec<String>, FetchAuditError> {
+    check_pin_count(name, "rev", revs, urls)?;
+    try_join_all(urls.iter().enumerate().map(|(idx, url)| async move {
+        let source = ImportSource::parse_pinned(name, url, revs.get(idx).map(|rev| &rev[..]))?;
+        let audit_source = source.fetch(network, cache, url).await?;
+        Ok(sha256_digest(&audit_source))
+    }))
+    .await
+}
+
+/// Fetch the commit which each of an import's git URLs currently refers to, to
+/// be used as its rev pins.
+pub async fn fetch_import_revs(
+    cache: &Cache,
+    name: &str,
+    urls: &[String],
+) -> Result<Vec<String>, FetchAuditError> {
+    try_join_all(urls.iter().map(|url| async move {
+        match ImportSource::parse_pinned(name, url, None)? {
+            ImportSource::Git { repo, rev, .. } => {
+                Ok(cache.fetch_git_commit(&repo, rev.as_deref()).await?)
+            }
+            ImportSource::Url(_) => Err(FetchAuditError::RevPinNotGit {
+                import_name: name.to_owned(),
+                import_url: url.clone(),
+            }),
+        }
+    }))
+    .await
+}
+
+/// Whether `url` is an import URL for a file in a git repository.
+pub fn is_git_import(url: &str) -> bool {
+    url.starts_with(GIT_IMPORT_PREFIX)
+}
+
+/// Check that an import has either no pins of the given `kind`, or one for
+/// each of its URLs.
+fn check_pin_count(
+    name: &str,
+    kind: &'static str,
+    pins: &[String],
+    urls: &[String],
+) -> Result<(), FetchAuditError> {
+    if !pins.is_empty() && pins.len() != urls.len() {
+        return Err(FetchAuditError::PinCount {
+            import_name: name.to_owned(),
+            kind,
+            pins: pins.len(),
+            urls: urls.len(),
+        });
+    }
+    Ok(())
+}
+
+fn sha256_digest(source: &SourceFile) -> String {
+    format!("{:x}", Sha256::digest(source.source().as_bytes()))
+}
+
+/// Check that the detached minisign signature at `signature_source` is a valid
+/// signature of `audit_source` by one of `public_keys`.
+async fn verify_signature(
+    network: &Network,
+    cache: &Cache,
+    signature_source: &ImportSource,
+    audit_source: &SourceFile,
+    public_keys: &[String],
+) -> Result<(), SignatureError> {
+    let public_keys = public_keys
+        .iter()
+        .map(|public_key| {
+            minisign_verify::PublicKey::from_base64(public_key).map_err(|error| {
+                SignatureError::InvalidPublicKey {
+                    public_key: public_key.clone(),
+                    error,
+                }
+            })
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let signature = signature_source
+        .fetch(
+            network,
+            cache,
+            &format!("{}{SIGNATURE_SUFFIX}", audit_source.name()),
+        )
+        .await
+        .map_err(|error| SignatureError::Missing(Box::new(error)))?;
+    let signature = minisign_verify::Signature::decode(signature.source())
+        .map_err(SignatureError::Malformed)?;
+
+    // Only accept the current prehashed signature format.
+    if public_keys.iter().any(|public_key| {
+        public_key
+            .verify(audit_source.source().as_bytes(), &signature, false)
+            .is_ok()
+    }) {
+        Ok(())
+    } else {
+        Err(SignatureError::Untrusted)
+    }
+}
+
 /// Process imported audits from the network, generating a `LiveImports`
 /// description of the live state of imported audits.
 fn process_imported_audits(
     fetched_audits: Vec<(ImportName, AuditsFile)>,
+    config: &ConfigFile,
     imports_lock: &ImportsFile,
     allow_criteria_changes: bool,
 ) -> Result<ImportsFile, CriteriaChangeErrors> {
@@ -857,6 +1384,10 @@ fn process_imported_audits(
     let mut changed_criteria = Vec::new();
 
     for (import_name, mut audits_file) in fetched_audits {
+        if let Some(import) = config.imports.get(&import_name) {
+            filter_imported_entries(import, &mut audits_file);
+        }
+
         if let Some(existing_audits_file) = imports_lock.audits.get(&import_name) {
             update_import_freshness(
                 &mut audits_file,
@@ -898,6 +1429,38 @@ fn process_imported_audits(
     Ok(new_imports)
 }
 
+/// Remove the entries which the config doesn't allow to be imported from
+/// `import`, either because they weren't performed by one of its `auditors`,
+/// or because its wildcard audits or trusted entries are excluded.
+///
+/// The `max-criteria` limit is applied by the resolver instead, so that it can
+/// report which imported audits were capped.
+fn filter_imported_entries(import: &RemoteImport, audits_file: &mut AuditsFile) {
+    for entries in audits_file.audits.values_mut() {
+        // Violations are kept no matter who recorded them, as narrowing who
+        // we trust must never weaken the checks we make.
+        entries.retain(|entry| {
+            matches!(entry.kind, AuditKind::Violation { .. }) || import.allows_audit_by(&entry.who)
+        });
+    }
+    audits_file.audits.retain(|_, entries| !entries.is_empty());
+
+    if import.exclude_wildcard_audits {
+        audits_file.wildcard_audits.clear();
+    } else {
+        for entries in audits_file.wildcard_audits.values_mut() {
+            entries.retain(|entry| import.allows_audit_by(&entry.who));
+        }
+        audits_file
+            .wildcard_audits
+            .retain(|_, entries| !entries.is_empty());
+    }
+
+    if import.exclude_trusted {
+        audits_file.trusted.clear();
+    }
+}
+
 fn update_import_freshness(
     audits_file: &mut AuditsFile,
     existing_audits_file: &AuditsFile,
@@ -951,46 +1514,87 @@ fn update_import_freshness(
 
 /// Fetch all declared imports from the network, mapping criteria to the local
 /// namespace, and filling in any criteria descriptions.
+///
+/// Pinned imports which have changed upstream keep using the audits recorded
+/// for them in `imports_lock`.
 async fn fetch_imported_audits(
     network: &Network,
+    cache: &Cache,
     local_criteria_mapper: &CriteriaMapper,
     config: &ConfigFile,
+    imports_lock: &ImportsFile,
 ) -> Result<Vec<(ImportName, AuditsFile)>, Box<FetchAuditError>> {
-    let progress_bar = progress_bar("Fetching", "imported audits", config.imports.len() as u64);
-    try_join_all(config.imports.iter().map(|(name, import)| async {
-        let _guard = IncProgressOnDrop(&progress_bar, 1);
-        let audit_file = fetch_imported_audit(
+    let progress_bar = &progress_bar("Fetching", "imported audits", config.imports.len() as u64);
+    try_join_all(config.imports.iter().map(|(name, import)| async move {
+        let _guard = IncProgressOnDrop(progress_bar, 1);
+        let result = fetch_imported_audit(
             network,
+            cache,
             local_criteria_mapper,
             name,
             &import.url,
+            &import.sha256,
+            &import.rev,
             &import.exclude,
             &import.criteria_map,
+            &import.public_key,
         )
-        .await
-        .map_err(Box::new)?;
+        .await;
+        let audit_file = match (result, imports_lock.audits.get(name)) {
+            (Err(FetchAuditError::PinMismatch { .. }), Some(locked)) => {
+                warn!(
+                    "'{name}' has changed upstream, so its pinned audits will be used. \
+                    Run `cargo vet import --update {name}` to review the changes."
+                );
+                locked.clone()
+            }
+            (
+                Err(error @ (FetchAuditError::Download(_) | FetchAuditError::Source(_))),
+                Some(locked),
+            ) if config.on_fetch_failure == FetchFailurePolicy::UseLock => {
+                warn!(
+                    "{:?}",
+                    miette::Report::from(error)
+                        .wrap_err(format!("using the audits in imports.lock for '{name}'"))
+                );
+                locked.clone()
+            }
+            (result, _) => result.map_err(Box::new)?,
+        };
         Ok::<_, Box<FetchAuditError>>((name.clone(), audit_file))
     }))
     .await
 }
 
+#[allow(clippy::too_many_arguments)]
 async fn fetch_imported_audit(
     network: &Network,
+    cache: &Cache,
     local_criteria_mapper: &CriteriaMapper,
     name: &str,
     urls: &[String],
+    pins: &[String],
+    revs: &[String],
     exclude: &[PackageName],
     criteria_map: &CriteriaMap,
+    public_keys: &[String],
 ) -> Result<AuditsFile, FetchAuditError> {
+    check_pin_count(name, "sha256", pins, urls)?;
+    check_pin_count(name, "rev", revs, urls)?;
+
     // Fetch all imported URLs, and then aggregate them.
-    let sources = try_join_all(urls.iter().map(|url| async {
+    let sources = try_join_all(urls.iter().enumerate().map(|(idx, url)| async move {
         fetch_single_imported_audit(
             network,
+            cache,
             local_criteria_mapper,
             name,
             url,
+            pins.get(idx).map(|pin| &pin[..]),
+            revs.get(idx).map(|rev| &rev[..]),
             exclude,
             criteria_map,
+            public_keys,
         )
         .await
         .map(|audits_file| (url.clone(), audits_file))
@@ -1030,20 +1634,55 @@ async fn fetch_imported_audit(
 
 /// Fetch a single AuditsFile from the network, filling in any criteria
 /// descriptions.
+///
+/// If a `rev` is specified, git sources are fetched from that commit. If a `pin`
+/// is specified, the AuditsFile must have that sha256 digest, and if any
+/// `public_keys` are specified, the AuditsFile must have a valid detached
+/// signature from one of them.
+#[allow(clippy::too_many_arguments)]
 async fn fetch_single_imported_audit(
     network: &Network,
+    cache: &Cache,
     local_criteria_mapper: &CriteriaMapper,
     name: &str,
     url: &str,
+    pin: Option<&str>,
+    rev: Option<&str>,
     exclude: &[PackageName],
     criteria_map: &CriteriaMap,
+    public_keys: &[String],
 ) -> Result<AuditsFile, FetchAuditError> {
-    let parsed_url = Url::parse(url).map_err(|error| FetchAuditError::InvalidUrl {
-        import_url: url.to_owned(),
-        import_name: name.to_owned(),
-        error,
-    })?;
-    let audit_source = network.download_source_file_cached(parsed_url).await?;
+    let source = ImportSource::parse_pinned(name, url, rev)?;
+    let audit_source = source.fetch(network, cache, url).await?;
+
+    if let Some(pin) = pin {
+        let actual = sha256_digest(&audit_source);
+        if !actual.eq_ignore_ascii_case(pin) {
+            return Err(FetchAuditError::PinMismatch {
+                import_name: name.to_owned(),
+                import_url: url.to_owned(),
+                expected: pin.to_owned(),
+                actual,
+            });
+        }
+    }
+
+    if !public_keys.is_empty() {
+        let signature_source = source.with_suffix(SIGNATURE_SUFFIX);
+        verify_signature(
+            network,
+            cache,
+            &signature_source,
+            &audit_source,
+            public_keys,
+        )
+        .await
+        .map_err(|error| FetchAuditError::BadSignature {
+            import_name: name.to_owned(),
+            import_url: url.to_owned(),
+            error,
+        })?;
+    }
 
     let mut audit_file = foreign_audit_source_to_local_warn(name, audit_source)?;
 
@@ -1194,15 +1833,28 @@ pub(crate) fn foreign_audit_file_to_local(
             }
         })
         .collect();
-    let valid_criteria: Vec<CriteriaName> = criteria.keys().cloned().collect();
+    let valid_criteria = loop {
+        let valid_criteria: Vec<CriteriaName> = criteria.keys().cloned().collect();
 
-    // Remove any unknown criteria from implies sets, to ensure we don't run
-    // into errors later on in the resolver.
-    for entry in criteria.values_mut() {
-        entry
-            .implies
-            .retain(|criteria_name| is_known_criteria(&valid_criteria, criteria_name));
-    }
+        // Remove any unknown criteria from implies sets, to ensure we don't run
+        // into errors later on in the resolver.
+        for entry in criteria.values_mut() {
+            entry
+                .implies
+                .retain(|criteria_name| is_known_criteria(&valid_criteria, criteria_name));
+        }
+
+        // Ignore any criteria which shadow a built-in criteria or imply
+        // themselves, as we'd be unable to build a mapper for them.
+        let invalid = match CriteriaMapper::try_new(&criteria) {
+            Ok(_) => break valid_criteria,
+            Err(CriteriaDefinitionError::Duplicate(name))
+            | Err(CriteriaDefinitionError::ImpliesItself(name))
+            | Err(CriteriaDefinitionError::UnknownImplied { criteria: name, .. }) => name,
+        };
+        criteria.remove(&invalid);
+        ignored_criteria.push(invalid);
+    };
 
     let mut ignored_audits = Vec::new();
     let audits: AuditedDependencies = foreign_audit_file
@@ -1426,7 +2078,9 @@ async fn import_unpublished_entries(
         // If we have no versions for the crate, it cannot be
         // audit-as-crates-io, so treat it as an error.
         // FIXME: better errors here?
-        let versions = cache.published_versions(network, &package.name).await?;
+        let versions = cache
+            .published_versions(network, metadata, &package.name)
+            .await?;
 
         // Pick which verison of the crate we'd audit as. We prefer the exact
         // version of the crate, followed by the largest version below, and then
@@ -1521,7 +2175,9 @@ async fn import_publisher_versions(
             .packages
             .iter()
             .filter(|pkg| {
-                relevant_packages.contains(&pkg.name) && pkg.is_third_party(&config_file.policy)
+                relevant_packages.contains(&pkg.name)
+                    && pkg.is_third_party(&config_file.policy)
+                    && !pkg.is_alternate_registry()
             })
             .map(|pkg| &pkg.name[..])
             .collect()
@@ -1534,10 +2190,22 @@ async fn import_publisher_versions(
             let progress = &progress;
             async move {
                 let _inc_progress = IncProgressOnDrop(progress, 1);
-                cache
-                    .crates_io_info(Some(network), pkg_name)
-                    .await
-                    .map(|entry| (pkg_name, entry))
+                match cache.crates_io_info(Some(network), pkg_name).await {
+                    Ok(entry) => Ok((pkg_name, Some(entry))),
+                    Err(error @ CrateInfoError::Download(_))
+                        if config_file.on_fetch_failure == FetchFailurePolicy::UseLock
+                            && imports_lock.publisher.contains_key(pkg_name) =>
+                    {
+                        warn!(
+                            "{:?}",
+                            miette::Report::from(error).wrap_err(format!(
+                                "using the publishers in imports.lock for '{pkg_name}'"
+                            ))
+                        );
+                        Ok((pkg_name, None))
+                    }
+                    Err(error) => Err(error),
+                }
             }
         }))
         .await?
@@ -1547,6 +2215,21 @@ async fn import_publisher_versions(
     // information in the cache, to ensure we're fetching consistent user
     // information.
     for (pkg_name, entry) in relevant_publishers {
+        let Some(entry) = entry else {
+            // Fall back to the locked publishers, none of which are fresh.
+            live_imports.publisher.insert(
+                pkg_name.to_owned(),
+                imports_lock.publisher[pkg_name]
+                    .iter()
+                    .map(|publisher| CratesPublisher {
+                        is_fresh_import: false,
+                        ..publisher.clone()
+                    })
+                    .collect(),
+            );
+            continue;
+        };
+
         // Fill in the live imports table with the relevant information.
         let nonfresh_versions: FastSet<_> = imports_lock
             .publisher
@@ -1587,7 +2270,7 @@ async fn import_publisher_versions(
 pub async fn fetch_registry(network: &Network) -> Result<RegistryFile, FetchRegistryError> {
     let registry_url = Url::parse(REGISTRY_URL).unwrap();
     let registry_source = network.download_source_file_cached(registry_url).await?;
-    let registry_file: RegistryFile = toml::de::from_str(registry_source.source())
+    let mut registry_file: RegistryFile = toml::de::from_str(registry_source.source())
         .map_err(|error| {
             let (line, col) = error.line_col().unwrap_or((0, 0));
             TomlParseError {
@@ -1597,9 +2280,53 @@ pub async fn fetch_registry(network: &Network) -> Result<RegistryFile, FetchRegi
             }
         })
         .map_err(LoadTomlError::from)?;
+
+    // Local files may only be imported from the local config, so ignore any
+    // registry entries which point at them.
+    registry_file.registry.retain(|name, entry| {
+        let is_local = entry
+            .url
+            .iter()
+            .any(|url| ImportSource::parse(url).is_ok_and(|source| source.is_local()));
+        if is_local {
+            warn!("ignoring registry entry '{name}' which refers to local files");
+        }
+        !is_local
+    });
     Ok(registry_file)
 }
 
+/// Locate the sparse index of the alternate registry a package is from, using
+/// the package's source in the cargo metadata.
+fn alternate_registry_index(
+    metadata: &cargo_metadata::Metadata,
+    package: PackageStr<'_>,
+) -> Result<Url, CrateInfoError> {
+    metadata
+        .packages
+        .iter()
+        .filter(|p| p.name == package)
+        .find_map(|p| {
+            let index = p.source.as_ref()?.repr.strip_prefix("sparse+")?;
+            // Make sure the index is treated as a directory when joining paths.
+            if index.ends_with('/') {
+                Url::parse(index).ok()
+            } else {
+                Url::parse(&format!("{index}/")).ok()
+            }
+        })
+        .ok_or_else(|| CrateInfoError::UnknownRegistryIndex {
+            name: package.to_owned(),
+        })
+}
+
+/// The name to use for a package within the cache directory. The qualified
+/// names of packages from alternate registries can't be used directly, as `:`
+/// isn't allowed in paths on Windows.
+fn cache_package_name(package: PackageStr<'_>) -> String {
+    package.replace(format::REGISTRY_SEPARATOR, "+")
+}
+
 pub fn user_info_map(imports: &ImportsFile) -> FastMap<CratesUserId, CratesCacheUser> {
     let mut user_info = FastMap::new();
     for publisher in imports.publisher.values().flatten() {
@@ -1624,13 +2351,18 @@ struct CacheState {
     crates_cache: CratesCache,
     /// Package versions and sha256 checksums from index.crates.io
     published_versions: FastMap<PackageName, Arc<tokio::sync::OnceCell<PublishedVersions>>>,
+    /// The `config.json` of each alternate registry's sparse index.
+    registry_configs: FastMap<Url, Arc<tokio::sync::OnceCell<Arc<crates_index::IndexConfig>>>>,
     /// Used to avoid multiple requests to the crates.io API for crate metadata.
     /// Results are populated into crates_cache.
     crates_io_info_fetches: FastMap<PackageName, Arc<tokio::sync::OnceCell<Arc<CratesCacheEntry>>>>,
     /// Paths for unpacked packages from this version.
     fetched_packages: FastMap<(PackageName, VetVersion), Arc<tokio::sync::OnceCell<PathBuf>>>,
     /// Computed diffstats from this version.
-    diffed: FastMap<(PackageName, Delta), Arc<tokio::sync::OnceCell<DiffStat>>>,
+    diffed: FastMap<(PackageName, Delta), Arc<tokio::sync::OnceCell<PackageDiff>>>,
+    /// Locks over the cached clone of each git repository which audits are
+    /// imported from.
+    git_repos: FastMap<String, Arc<tokio::sync::Mutex<()>>>,
 }
 
 /// The cache where we store globally shared artifacts like fetched packages and diffstats
@@ -1712,9 +2444,11 @@ impl Cache {
                     command_history: CommandHistory::default(),
                     crates_cache: CratesCache::default(),
                     published_versions: FastMap::new(),
+                    registry_configs: FastMap::new(),
                     crates_io_info_fetches: FastMap::new(),
                     fetched_packages: FastMap::new(),
                     diffed: FastMap::new(),
+                    git_repos: FastMap::new(),
                 }),
             });
         }
@@ -1780,13 +2514,116 @@ impl Cache {
                 command_history,
                 crates_cache: publisher_cache,
                 published_versions: FastMap::new(),
+                registry_configs: FastMap::new(),
                 crates_io_info_fetches: FastMap::new(),
                 fetched_packages: FastMap::new(),
                 diffed: FastMap::new(),
+                git_repos: FastMap::new(),
             }),
         })
     }
 
+    /// Fetch the contents of the file at `path` in the git repository `repo`,
+    /// at `rev` (or `HEAD` if not specified).
+    async fn fetch_git_file(
+        &self,
+        repo: &str,
+        rev: Option<&str>,
+        path: &str,
+    ) -> Result<String, ImportSourceError> {
+        let commit = self.fetch_git_commit(repo, rev).await?;
+        let Some(root) = &self.root else {
+            return Err(ImportSourceError::NoCache);
+        };
+        let clone_dir = root.join(CACHE_GIT_IMPORTS).join(git_clone_name(repo));
+        run_git(&clone_dir, &["show", &format!("{commit}:{path}")])
+            .await
+            .map_err(|error| ImportSourceError::Git {
+                repo: repo.to_owned(),
+                rev: commit,
+                path: path.to_owned(),
+                error,
+            })
+    }
+
+    /// Fetch the commit `rev` (or `HEAD` if not specified) refers to in the git
+    /// repository `repo`, returning its commit id.
+    ///
+    /// Repositories are shallowly fetched into a bare clone in the cache, and
+    /// commits which have already been fetched are used without going online.
+    async fn fetch_git_commit(
+        &self,
+        repo: &str,
+        rev: Option<&str>,
+    ) -> Result<String, ImportSourceError> {
+        let Some(root) = &self.root else {
+            return Err(ImportSourceError::NoCache);
+        };
+        let rev = rev.unwrap_or("HEAD");
+        // Make sure the revision can't be mistaken for an option to git.
+        if rev.starts_with('-') {
+            return Err(ImportSourceError::InvalidRev {
+                repo: repo.to_owned(),
+                rev: rev.to_owned(),
+            });
+        }
+        let git_error = |error| ImportSourceError::GitFetch {
+            repo: repo.to_owned(),
+            rev: rev.to_owned(),
+            error,
+        };
+
+        // Only fetch into each clone from a single task at a time.
+        let repo_lock = {
+            // NOTE: Don't .await while this is held, or we might deadlock!
+            let mut guard = self.state.lock().unwrap();
+            guard.git_repos.entry(repo.to_owned()).or_default().clone()
+        };
+        let _repo_guard = repo_lock.lock().await;
+
+        let clone_dir = root.join(CACHE_GIT_IMPORTS).join(git_clone_name(repo));
+        if !clone_dir.exists() {
+            tokio::fs::create_dir_all(&clone_dir)
+                .await
+                .map_err(|error| git_error(CommandError::CommandFailed(error)))?;
+            run_git(&clone_dir, &["init", "--quiet", "--bare"])
+                .await
+                .map_err(git_error)?;
+        }
+
+        let is_commit_id = rev.len() == 40 && rev.bytes().all(|b| b.is_ascii_hexdigit());
+        if is_commit_id
+            && run_git(
+                &clone_dir,
+                &["cat-file", "-e", &format!("{rev}^{{commit}}")],
+            )
+            .await
+            .is_ok()
+        {
+            Ok(rev.to_owned())
+        } else {
+            run_git(
+                &clone_dir,
+                &[
+                    "fetch",
+                    "--quiet",
+                    "--force",
+                    "--depth=1",
+                    "--end-of-options",
+                    repo,
+                    rev,
+                ],
+            )
+            .await
+            .map_err(git_error)?;
+            Ok(run_git(&clone_dir, &["rev-parse", "FETCH_HEAD"])
+                .await
+                .map_err(git_error)?
+                .trim()
+                .to_owned())
+        }
+    }
+
     #[tracing::instrument(skip(self, metadata, network), err)]
     pub async fn fetch_package(
         &self,
@@ -1818,7 +2655,7 @@ impl Cache {
                 if let Some(git_rev) = &version.git_rev {
                     let repacked_src = root.join(CACHE_REGISTRY_SRC).join(format!(
                         "{}-{}.git.{}",
-                        package,
+                        cache_package_name(package),
                         version.semver,
                         version.git_rev.as_ref().unwrap()
                     ));
@@ -1847,10 +2684,12 @@ impl Cache {
 
                 let version = &version.semver;
 
-                let dir_name = format!("{package}-{version}");
+                let dir_name = format!("{}-{version}", cache_package_name(package));
+                let alternate_registry = format::split_qualified_package_name(package);
 
-                // First try to get a cached copy from cargo's registry.
-                if let Ok(cargo_home) = home::cargo_home() {
+                // First try to get a cached copy from cargo's registry. We only
+                // know where to find crates.io packages in this cache.
+                if let (Ok(cargo_home), None) = (home::cargo_home(), alternate_registry) {
                     // Check both the sparse and git registry caches.
                     for registry in [CARGO_REGISTRY_CRATES_IO_HTTP, CARGO_REGISTRY_CRATES_IO_GIT] {
                         let fetched_src = cargo_home
@@ -1902,8 +2741,24 @@ impl Cache {
                         })?;
 
                         // We don't have it, so download it
-                        let url =
-                            format!("https://crates.io/api/v1/crates/{package}/{version}/download");
+                        let url = match alternate_registry {
+                            Some((_, name)) => {
+                                let index = alternate_registry_index(metadata, package)?;
+                                let config = self.registry_config(network, &index).await?;
+                                config.download_url(name, &version.to_string()).ok_or_else(
+                                    || FetchError::NoDownloadUrl {
+                                        package: package.to_owned(),
+                                        version: version.clone(),
+                                        index: Box::new(index),
+                                    },
+                                )?
+                            }
+                            None => network
+                                .crates_io_api_url(&format!(
+                                    "v1/crates/{package}/{version}/download"
+                                ))
+                                .into(),
+                        };
                         let url = Url::parse(&url).map_err(|error| FetchError::InvalidUrl {
                             url: url.clone(),
                             error,
@@ -1964,7 +2819,7 @@ impl Cache {
         version1: &Path,
         version2: &Path,
         has_git_rev: bool,
-    ) -> Result<(DiffStat, Vec<(PathBuf, PathBuf)>), DiffError> {
+    ) -> Result<(PackageDiff, Vec<(PathBuf, PathBuf)>), DiffError> {
         let _permit = self
             .diff_semaphore
             .acquire()
@@ -1999,6 +2854,18 @@ impl Cache {
             insertions: 0,
             deletions: 0,
         };
+        let mut files = Vec::new();
+        let mut risks = SortedMap::<RiskKind, RiskIndicator>::new();
+        let mut add_risk = |kind, occurrences, path: &str| {
+            let risk = risks.entry(kind).or_insert_with(|| RiskIndicator {
+                kind,
+                occurrences: 0,
+                files: Vec::new(),
+            });
+            risk.occurrences += occurrences;
+            risk.files.push(path.to_owned());
+        };
+        let manifest = read_manifest_info(version2);
         let mut to_compare = Vec::new();
 
         // Thanks to the `-z` flag the output takes the rough format of:
@@ -2031,24 +2898,64 @@ impl Cache {
                 continue;
             }
 
-            to_compare.push((from_s.into(), to_s.into()));
-
             diffstat.files_changed += 1;
 
-            match changes_s.trim().split_once('\t') {
-                Some(("-", "-")) => {} // binary diff
-                Some((insertions_s, deletions_s)) => {
-                    diffstat.insertions += insertions_s
+            let path = rel_path
+                .components()
+                .map(|c| c.as_os_str().to_string_lossy())
+                .collect::<Vec<_>>()
+                .join("/");
+
+            let (insertions, deletions) = match changes_s.trim().split_once('\t') {
+                Some(("-", "-")) => {
+                    // binary diff
+                    if to_s != "/dev/null" {
+                        add_risk(RiskKind::Binary, 1, &path);
+                    }
+                    (0, 0)
+                }
+                Some((insertions_s, deletions_s)) => (
+                    insertions_s
                         .parse::<u64>()
-                        .map_err(|_| DiffError::InvalidOutput)?;
-                    diffstat.deletions += deletions_s
+                        .map_err(|_| DiffError::InvalidOutput)?,
+                    deletions_s
                         .parse::<u64>()
-                        .map_err(|_| DiffError::InvalidOutput)?;
-                }
+                        .map_err(|_| DiffError::InvalidOutput)?,
+                ),
                 None => Err(DiffError::InvalidOutput)?,
             };
+            diffstat.insertions += insertions;
+            diffstat.deletions += deletions;
+
+            if manifest.build_script == path && insertions + deletions > 0 {
+                add_risk(RiskKind::BuildScript, insertions + deletions, &path);
+            }
+            let mut unsafe_lines = 0;
+            if insertions > 0 && path.ends_with(".rs") {
+                for (kind, occurrences) in scan_added_lines(Path::new(from_s), Path::new(to_s)) {
+                    if kind == RiskKind::Unsafe {
+                        unsafe_lines = occurrences;
+                    }
+                    add_risk(kind, occurrences, &path);
+                }
+            }
+            files.push(FileDiffStat {
+                path,
+                insertions,
+                deletions,
+                unsafe_lines,
+            });
+
+            to_compare.push((from_s.into(), to_s.into()));
         }
-        Ok((diffstat, to_compare))
+
+        let diff = PackageDiff {
+            diffstat,
+            files,
+            proc_macro: manifest.proc_macro,
+            risks: risks.into_values().collect(),
+        };
+        Ok((diff, to_compare))
     }
 
     #[tracing::instrument(skip(self, metadata, network), err)]
@@ -2058,7 +2965,7 @@ impl Cache {
         network: Option<&Network>,
         package: PackageStr<'_>,
         delta: &Delta,
-    ) -> Result<DiffStat, FetchAndDiffError> {
+    ) -> Result<PackageDiff, FetchAndDiffError> {
         // Lock the mutex to extract a reference to the OnceCell which we'll use
         // to asynchronously synchronize on and diff the package only once in a
         // single execution.
@@ -2070,7 +2977,7 @@ impl Cache {
             let mut guard = self.state.lock().unwrap();
 
             // Check if the value has already been cached.
-            let DiffCache::V2 { diffs } = &guard.diff_cache;
+            let DiffCache::V4 { diffs } = &guard.diff_cache;
             if let Some(cached) = diffs
                 .get(package)
                 .and_then(|cache| cache.get(delta))
@@ -2091,10 +2998,21 @@ impl Cache {
                 let to_len: u64 = delta.to.semver.major * delta.to.semver.major;
                 let diff = to_len as i64 - from_len as i64;
                 let count = diff.unsigned_abs();
-                return Ok(DiffStat {
-                    files_changed: 1,
-                    insertions: if diff > 0 { count } else { 0 },
-                    deletions: if diff < 0 { count } else { 0 },
+                let (insertions, deletions) = if diff > 0 { (count, 0) } else { (0, count) };
+                return Ok(PackageDiff {
+                    diffstat: DiffStat {
+                        files_changed: 1,
+                        insertions,
+                        deletions,
+                    },
+                    files: vec![FileDiffStat {
+                        path: "src/lib.rs".to_owned(),
+                        insertions,
+                        deletions,
+                        unsafe_lines: 0,
+                    }],
+                    proc_macro: false,
+                    risks: vec![],
                 });
             }
 
@@ -2105,7 +3023,7 @@ impl Cache {
                 .clone()
         };
 
-        let diffstat = once_cell
+        let diff = once_cell
             .get_or_try_init(|| async {
                 let from = match &delta.from {
                     Some(from) => self.fetch_package(metadata, network, package, from).await?,
@@ -2118,24 +3036,24 @@ impl Cache {
                 // Have fetches, do a real diffstat
                 // NOTE: We'll never pick a 'from' version with a git_rev, so we
                 // don't need to check for that here.
-                let (diffstat, _) = self
+                let (diff, _) = self
                     .diffstat_package(&from, &to, delta.to.git_rev.is_some())
                     .await?;
 
                 // Record the cache result in the diffcache
                 {
                     let mut guard = self.state.lock().unwrap();
-                    let DiffCache::V2 { diffs } = &mut guard.diff_cache;
+                    let DiffCache::V4 { diffs } = &mut guard.diff_cache;
                     diffs
                         .entry(package.to_string())
                         .or_default()
-                        .insert(delta.clone(), diffstat.clone());
+                        .insert(delta.clone(), diff.clone());
                 }
 
-                Ok::<_, FetchAndDiffError>(diffstat)
+                Ok::<_, FetchAndDiffError>(diff)
             })
             .await?;
-        Ok(diffstat.clone())
+        Ok(diff.clone())
     }
 
     /// Run a garbage-collection pass over the cache, removing any files which
@@ -2292,6 +3210,58 @@ impl Cache {
         guard.command_history.last_fetch = Some(last_fetch);
     }
 
+    /// Get the diffs of a package which have already been computed and cached,
+    /// without fetching anything.
+    pub fn get_cached_diffs(&self, package: PackageStr<'_>) -> SortedMap<Delta, PackageDiff> {
+        let guard = self.state.lock().unwrap();
+        let DiffCache::V4 { diffs } = &guard.diff_cache;
+        diffs.get(package).cloned().unwrap_or_default()
+    }
+
+    /// Get the files which have been marked as reviewed in the diff viewer
+    /// for the given delta.
+    pub fn get_reviewed_files(&self, package: PackageStr<'_>, delta: &Delta) -> SortedSet<String> {
+        let guard = self.state.lock().unwrap();
+        guard
+            .command_history
+            .reviewed_files
+            .get(package)
+            .and_then(|deltas| deltas.get(delta))
+            .cloned()
+            .unwrap_or_default()
+    }
+
+    /// Record whether a file has been reviewed in the diff viewer for the
+    /// given delta.
+    pub fn set_file_reviewed(
+        &self,
+        package: PackageStr<'_>,
+        delta: &Delta,
+        path: &str,
+        reviewed: bool,
+    ) {
+        let mut guard = self.state.lock().unwrap();
+        let deltas = guard
+            .command_history
+            .reviewed_files
+            .entry(package.to_owned())
+            .or_default();
+        let files = deltas.entry(delta.clone()).or_default();
+        if reviewed {
+            files.insert(path.to_owned());
+        } else {
+            files.remove(path);
+        }
+
+        // Don't keep around empty entries.
+        if files.is_empty() {
+            deltas.remove(delta);
+            if deltas.is_empty() {
+                guard.command_history.reviewed_files.remove(package);
+            }
+        }
+    }
+
     pub fn select_fetch_mode(
         &self,
         chosen_mode: Option<FetchMode>,
@@ -2321,14 +3291,31 @@ impl Cache {
             .unwrap_or(FetchMode::DiffRs)
     }
 
-    /// For a given package, fetch the list of versions published on crates.io,
-    /// along with the corresponding index entry.
+    /// For a given package, fetch the list of versions published on crates.io
+    /// (or the alternate registry the package is from), along with the
+    /// corresponding index entry.
     ///
-    /// This information will be pulled live from index.crates.io each time
-    /// cargo-vet is run, but is cached in-memory.
+    /// This information will be pulled live from the registry's sparse index
+    /// each time cargo-vet is run, but is cached in-memory.
     pub async fn published_versions(
         &self,
         network: &Network,
+        metadata: &cargo_metadata::Metadata,
+        package: PackageStr<'_>,
+    ) -> Result<PublishedVersions, CrateInfoError> {
+        let index = if format::split_qualified_package_name(package).is_some() {
+            alternate_registry_index(metadata, package)?
+        } else {
+            Url::parse(CRATES_IO_INDEX).unwrap()
+        };
+        self.index_versions(network, &index, package).await
+    }
+
+    /// Fetch the list of versions of a package from the given sparse index.
+    async fn index_versions(
+        &self,
+        network: &Network,
+        index: &Url,
         package: PackageStr<'_>,
     ) -> Result<PublishedVersions, CrateInfoError> {
         // Lock the mutex to extract a reference to the OnceCell which we'll use
@@ -2349,22 +3336,27 @@ impl Cache {
         // have some limited support for interacting with this cache.
         let res: Result<_, CrateInfoError> = once_cell
             .get_or_try_init(|| async {
+                // Packages from alternate registries are listed in the index
+                // under their unqualified name.
+                let name =
+                    format::split_qualified_package_name(package).map_or(package, |(_, name)| name);
+
                 // Crate names can only be a subset of ascii (valid rust
                 // identifier characters and `-`), so using `len()` and indexing
                 // will result in valid counts/characters.
-                let mut url = String::from("https://index.crates.io/");
+                let mut path = String::new();
                 use std::fmt::Write;
-                match package.len() {
-                    1 => write!(url, "1/{package}"),
-                    2 => write!(url, "2/{package}"),
-                    3 => write!(url, "3/{}/{package}", &package[0..1]),
-                    _ => write!(url, "{}/{}/{package}", &package[0..2], &package[2..4]),
+                match name.len() {
+                    1 => write!(path, "1/{name}"),
+                    2 => write!(path, "2/{name}"),
+                    3 => write!(path, "3/{}/{name}", &name[0..1]),
+                    _ => write!(path, "{}/{}/{name}", &name[0..2], &name[2..4]),
                 }
                 .expect("writing to a String should not fail");
                 // Crate index always use lowercases, but crate name may contain
                 // uppercase characters.
-                url.make_ascii_lowercase();
-                let url = Url::parse(&url).expect("invalid crate name");
+                path.make_ascii_lowercase();
+                let url = index.join(&path).expect("invalid crate name");
 
                 // Fetch the crate's entry from the index
                 let response = match network.download(url).await {
@@ -2413,6 +3405,34 @@ impl Cache {
         Ok(res.clone())
     }
 
+    /// Fetch the `config.json` for an alternate registry's sparse index, which
+    /// describes where packages can be downloaded from.
+    async fn registry_config(
+        &self,
+        network: &Network,
+        index: &Url,
+    ) -> Result<Arc<crates_index::IndexConfig>, CrateInfoError> {
+        let once_cell = {
+            // NOTE: Don't .await while this is held, or we might deadlock!
+            let mut guard = self.state.lock().unwrap();
+            guard
+                .registry_configs
+                .entry(index.clone())
+                .or_default()
+                .clone()
+        };
+
+        let config = once_cell
+            .get_or_try_init(|| async {
+                let url = index.join("config.json").expect("invalid index url");
+                let response = network.download(url).await?;
+                let config: crates_index::IndexConfig = load_json(&response[..])?;
+                Ok::<_, CrateInfoError>(Arc::new(config))
+            })
+            .await?;
+        Ok(config.clone())
+    }
+
     /// Get up-to-date crates.io information for a given crate.
     ///
     /// The result is cached to avoid unnecessary calls to the crates.io API,
@@ -2423,6 +3443,13 @@ impl Cache {
         network: Option<&Network>,
         package: PackageStr<'_>,
     ) -> Result<Arc<CratesCacheEntry>, CrateInfoError> {
+        // Packages from alternate registries are never on crates.io.
+        if format::split_qualified_package_name(package).is_some() {
+            return Err(CrateInfoError::DoesNotExist {
+                name: package.to_owned(),
+            });
+        }
+
         // If we don't have network access, we're forced to assume that the
         // cache is up to date.
         let Some(network) = network else {
@@ -2439,7 +3466,9 @@ impl Cache {
         // we do, we don't need to update anything.
         // This will also check if the crate does not exist, in which case we'll
         // early-return with an error.
-        let published_versions = self.published_versions(network, package).await?;
+        let published_versions = self
+            .index_versions(network, &Url::parse(CRATES_IO_INDEX).unwrap(), package)
+            .await?;
 
         // Lock the mutex. We'll either detect that we already have metadata for
         // all published versions, or we'll extract a reference to the OnceCell
@@ -2468,8 +3497,7 @@ impl Cache {
         once_cell
             .get_or_try_init(|| async {
                 info!("fetching crate metadata for crate {}", package);
-                let url = Url::parse(&format!("https://crates.io/api/v1/crates/{}", package))
-                    .expect("invalid crate name");
+                let url = network.crates_io_api_url(&format!("v1/crates/{package}"));
                 let response = network.download(url).await?;
                 let result = load_json::<CratesAPICrate>(&response[..])?;
 
@@ -2625,6 +3653,110 @@ fn unpack_package(tarball: &File, unpack_dir: &Path) -> Result<(), UnpackError>
     Ok(())
 }
 
+/// Whether a crate could be upgraded between the two versions without breaking
+/// semver compatibility.
+fn is_semver_compatible(a: &semver::Version, b: &semver::Version) -> bool {
+    a.major == b.major
+        && (a.major != 0 || (a.minor == b.minor && (a.minor != 0 || a.patch == b.patch)))
+}
+
+/// Patterns in added lines of Rust source which are worth reviewing first.
+/// They match whole path segments (see `contains_path`), so that e.g. grouped
+/// imports like `use std::{net::TcpStream, process::{Command, Stdio}}` are
+/// caught, but `internet::` isn't mistaken for `net::`.
+const RISK_PATTERNS: &[(RiskKind, &str)] = &[
+    (RiskKind::ProcessSpawn, "process::Command"),
+    (RiskKind::ProcessSpawn, "process::{"),
+    (RiskKind::ProcessSpawn, "Command::new"),
+    (RiskKind::Network, "std::net"),
+    (RiskKind::Network, "net::"),
+    (RiskKind::Network, "TcpStream"),
+    (RiskKind::Network, "TcpListener"),
+    (RiskKind::Network, "UdpSocket"),
+    (RiskKind::Ffi, "extern \"C\""),
+    (RiskKind::Ffi, "extern \"system\""),
+    (RiskKind::IncludeBytes, "include_bytes!"),
+];
+
+/// Whether `line` contains `pattern` starting at the beginning of an
+/// identifier, and, if the pattern ends in one, ending at the end of it.
+fn contains_path(line: &str, pattern: &str) -> bool {
+    let is_ident = |c: char| c.is_alphanumeric() || c == '_';
+    let ends_in_ident = pattern.ends_with(is_ident);
+    line.match_indices(pattern).any(|(start, _)| {
+        let starts_word = !line[..start].ends_with(is_ident);
+        let ends_word = !ends_in_ident || !line[start + pattern.len()..].starts_with(is_ident);
+        starts_word && ends_word
+    })
+}
+
+/// Count the lines added between two versions of a Rust source file which
+/// match each kind of risk. Either path may be `/dev/null` if the file was
+/// added or removed.
+fn scan_added_lines(from: &Path, to: &Path) -> SortedMap<RiskKind, u64> {
+    let read = |path: &Path| {
+        if path == Path::new("/dev/null") {
+            String::new()
+        } else {
+            fs::read_to_string(path).unwrap_or_default()
+        }
+    };
+    let (old, new) = (read(from), read(to));
+    let mut counts = SortedMap::new();
+    for change in similar::TextDiff::from_lines(&old, &new).iter_all_changes() {
+        if change.tag() != similar::ChangeTag::Insert {
+            continue;
+        }
+        let line = change.value();
+        let mut kinds: SortedSet<RiskKind> = RISK_PATTERNS
+            .iter()
+            .filter(|(_, pattern)| contains_path(line, pattern))
+            .map(|&(kind, _)| kind)
+            .collect();
+        if line
+            .split(|c: char| !c.is_alphanumeric() && c != '_')
+            .any(|word| word == "unsafe")
+        {
+            kinds.insert(RiskKind::Unsafe);
+        }
+        for kind in kinds {
+            *counts.entry(kind).or_default() += 1;
+        }
+    }
+    counts
+}
+
+/// The parts of an unpacked package's manifest relevant to weighting and
+/// scanning its diffs.
+struct ManifestInfo {
+    proc_macro: bool,
+    /// The path of the build script relative to the package root, which is
+    /// `build.rs` unless the manifest specifies otherwise.
+    build_script: String,
+}
+
+fn read_manifest_info(root: &Path) -> ManifestInfo {
+    let manifest = fs::read_to_string(root.join(CARGO_TOML_FILE))
+        .ok()
+        .and_then(|manifest| toml::from_str::<toml::Value>(&manifest).ok());
+    let get = |table: &str, key: &str| {
+        manifest
+            .as_ref()
+            .and_then(|manifest| manifest.get(table))
+            .and_then(|table| table.get(key))
+    };
+    ManifestInfo {
+        proc_macro: ["proc-macro", "proc_macro"]
+            .iter()
+            .any(|key| get("lib", key).and_then(|v| v.as_bool()).unwrap_or(false)),
+        build_script: get("package", "build")
+            .and_then(|v| v.as_str())
+            .unwrap_or("build.rs")
+            .trim_start_matches("./")
+            .to_owned(),
+    }
+}
+
 fn create_unpack_lock(unpack_dir: &Path) -> Result<(), io::Error> {
     let lockfile = unpack_dir.join(CARGO_OK_FILE);
 
@@ -2683,7 +3815,7 @@ async fn unpack_checkout(
     try_join_all(stdout.lines().map(|target| async move {
         // We'll be ignoring diffs for each of the skipped paths, so we can
         // ignore these if cargo reports them.
-        if DIFF_SKIP_PATHS.iter().any(|&p| p == target) {
+        if DIFF_SKIP_PATHS.contains(&target) {
             return Ok(());
         }
 
@@ -0,0 +1,694 @@
+//! Semantic diffs between two revisions of a store, for `cargo vet store-diff`.
+//!
+//! Rather than diffing the store's files textually, this compares the entries
+//! which affect vetting (audits, exemptions, imports, policy criteria and
+//! trusted publishers) so that reviewers can see at a glance how a change
+//! weakens or strengthens the supply-chain.
+
+use std::sync::Arc;
+
+use crate::criteria::CriteriaMapper;
+use crate::format::{
+    AuditEntry, AuditKind, CriteriaMap, CriteriaName, ExemptedDependency, JsonStoreDiff,
+    JsonStoreDiffAudit, JsonStoreDiffExemption, JsonStoreDiffImport, JsonStoreDiffPolicy,
+    JsonStoreDiffTrusted, PolicyEntry, RemoteImport, SortedMap, SortedSet, StoreDiffChange,
+    TrustEntry, VetVersion, WildcardEntry,
+};
+use crate::out::Out;
+use crate::serialization::spanned::Spanned;
+use crate::storage::Store;
+
+/// Compute the changes to the supply-chain between the `old` and `new`
+/// revisions of a store.
+pub fn diff_stores(old: &Store, new: &Store) -> JsonStoreDiff {
+    // Criteria are compared using the definitions from the new store, so that
+    // e.g. replacing `safe-to-run` with `safe-to-deploy` is a broadening. If
+    // the definitions are broken or criteria were removed, changes are just
+    // reported as changes.
+    let mapper = CriteriaMapper::try_new(&new.audits.criteria).ok();
+    let mut diff = JsonStoreDiff::default();
+    diff_audits(old, new, &mut diff);
+    diff_exemptions(old, new, mapper.as_ref(), &mut diff);
+    diff_imports(old, new, mapper.as_ref(), &mut diff);
+    diff_policy(old, new, mapper.as_ref(), &mut diff);
+    diff_trusted(old, new, &mut diff);
+    diff
+}
+
+fn criteria_names(criteria: &[Spanned<CriteriaName>]) -> Vec<CriteriaName> {
+    let mut names: Vec<CriteriaName> = criteria.iter().map(|c| c.to_string()).collect();
+    names.sort();
+    names.dedup();
+    names
+}
+
+/// Determine how a list of criteria changed, or `None` if it didn't.
+fn compare_criteria(
+    mapper: Option<&CriteriaMapper>,
+    old: &[CriteriaName],
+    new: &[CriteriaName],
+) -> Option<StoreDiffChange> {
+    if old == new {
+        return None;
+    }
+    let Some(mapper) = mapper.filter(|mapper| {
+        old.iter()
+            .chain(new)
+            .all(|name| mapper.all_criteria_names().any(|known| known == name))
+    }) else {
+        return Some(StoreDiffChange::Changed);
+    };
+    let old_set = mapper.criteria_from_list(old);
+    let new_set = mapper.criteria_from_list(new);
+    match (new_set.contains(&old_set), old_set.contains(&new_set)) {
+        (true, true) => None,
+        (true, false) => Some(StoreDiffChange::Broadened),
+        (false, true) => Some(StoreDiffChange::Narrowed),
+        (false, false) => Some(StoreDiffChange::Changed),
+    }
+}
+
+/// Added and removed entries between two lists, compared with `same`.
+fn added_and_removed<'a, T>(
+    old: &'a [T],
+    new: &'a [T],
+    same: impl Fn(&T, &T) -> bool,
+) -> Vec<(StoreDiffChange, &'a T)> {
+    let mut result = vec![];
+    for o in old {
+        if !new.iter().any(|n| same(o, n)) {
+            result.push((StoreDiffChange::Removed, o));
+        }
+    }
+    for n in new {
+        if !old.iter().any(|o| same(o, n)) {
+            result.push((StoreDiffChange::Added, n));
+        }
+    }
+    result
+}
+
+fn each_package<'a, T>(
+    old: &'a SortedMap<String, Vec<T>>,
+    new: &'a SortedMap<String, Vec<T>>,
+) -> impl Iterator<Item = (&'a String, &'a [T], &'a [T])> {
+    old.keys()
+        .chain(new.keys())
+        .collect::<SortedSet<_>>()
+        .into_iter()
+        .map(|name| {
+            (
+                name,
+                old.get(name).map_or(&[][..], |v| &v[..]),
+                new.get(name).map_or(&[][..], |v| &v[..]),
+            )
+        })
+}
+
+fn diff_audits(old: &Store, new: &Store, diff: &mut JsonStoreDiff) {
+    let same_audit = |a: &AuditEntry, b: &AuditEntry| {
+        a.same_audit_as(b) && criteria_names(&a.criteria) == criteria_names(&b.criteria)
+    };
+    for (name, old_audits, new_audits) in each_package(&old.audits.audits, &new.audits.audits) {
+        for (change, audit) in added_and_removed(old_audits, new_audits, same_audit) {
+            let (kind, target) = match &audit.kind {
+                AuditKind::Full { version } => ("full", version.to_string()),
+                AuditKind::Delta { from, to } => ("delta", format!("{from} -> {to}")),
+                AuditKind::Violation { violation } => ("violation", violation.to_string()),
+            };
+            diff.audits.push(JsonStoreDiffAudit {
+                change,
+                name: name.clone(),
+                kind: kind.to_owned(),
+                target,
+                criteria: criteria_names(&audit.criteria),
+                who: audit.who.iter().map(|who| who.to_string()).collect(),
+            });
+        }
+    }
+
+    let same_wildcard = |a: &WildcardEntry, b: &WildcardEntry| {
+        a.user_id == b.user_id && criteria_names(&a.criteria) == criteria_names(&b.criteria)
+    };
+    for (name, old_audits, new_audits) in
+        each_package(&old.audits.wildcard_audits, &new.audits.wildcard_audits)
+    {
+        for (change, audit) in added_and_removed(old_audits, new_audits, same_wildcard) {
+            diff.audits.push(JsonStoreDiffAudit {
+                change,
+                name: name.clone(),
+                kind: "wildcard".to_owned(),
+                target: audit.user_id.to_string(),
+                criteria: criteria_names(&audit.criteria),
+                who: audit.who.iter().map(|who| who.to_string()).collect(),
+            });
+        }
+    }
+}
+
+fn diff_exemptions(
+    old: &Store,
+    new: &Store,
+    mapper: Option<&CriteriaMapper>,
+    diff: &mut JsonStoreDiff,
+) {
+    for (name, old_exemptions, new_exemptions) in
+        each_package(&old.config.exemptions, &new.config.exemptions)
+    {
+        // Exemptions are compared by version, merging the criteria of
+        // multiple exemptions for the same version.
+        let by_version = |exemptions: &[ExemptedDependency]| {
+            let mut result: SortedMap<VetVersion, Vec<Spanned<CriteriaName>>> = SortedMap::new();
+            for exemption in exemptions {
+                result
+                    .entry(exemption.version.clone())
+                    .or_default()
+                    .extend(exemption.criteria.iter().cloned());
+            }
+            result
+        };
+        let old_versions = by_version(old_exemptions);
+        let new_versions = by_version(new_exemptions);
+        let versions: SortedSet<&VetVersion> =
+            old_versions.keys().chain(new_versions.keys()).collect();
+        for version in versions {
+            let old_criteria = old_versions
+                .get(version)
+                .map_or(vec![], |c| criteria_names(c));
+            let new_criteria = new_versions
+                .get(version)
+                .map_or(vec![], |c| criteria_names(c));
+            let change = if !old_versions.contains_key(version) {
+                StoreDiffChange::Added
+            } else if !new_versions.contains_key(version) {
+                StoreDiffChange::Removed
+            } else if let Some(change) = compare_criteria(mapper, &old_criteria, &new_criteria) {
+                change
+            } else {
+                continue;
+            };
+            diff.exemptions.push(JsonStoreDiffExemption {
+                change,
+                name: name.clone(),
+                version: version.clone(),
+                old_criteria,
+                new_criteria,
+            });
+        }
+    }
+}
+
+fn diff_imports(
+    old: &Store,
+    new: &Store,
+    mapper: Option<&CriteriaMapper>,
+    diff: &mut JsonStoreDiff,
+) {
+    let names: SortedSet<&String> = old
+        .config
+        .imports
+        .keys()
+        .chain(new.config.imports.keys())
+        .collect();
+    for name in names {
+        let (old_import, new_import) =
+            match (old.config.imports.get(name), new.config.imports.get(name)) {
+                (Some(old_import), Some(new_import)) => (old_import, new_import),
+                (None, Some(import)) => {
+                    diff.imports
+                        .push(import_entry(StoreDiffChange::Added, name, import));
+                    continue;
+                }
+                (Some(import), None) => {
+                    diff.imports
+                        .push(import_entry(StoreDiffChange::Removed, name, import));
+                    continue;
+                }
+                (None, None) => continue,
+            };
+
+        // Each field which changed is reported separately, and is broadened
+        // if it makes the import trusted for more than it used to be.
+        let mut push = |field: &str,
+                        key: Option<&String>,
+                        change: Option<StoreDiffChange>,
+                        old_value: Vec<String>,
+                        new_value: Vec<String>| {
+            if let Some(change) = change {
+                diff.imports.push(JsonStoreDiffImport {
+                    field: Some(field.to_owned()),
+                    key: key.cloned(),
+                    old_value: Some(old_value),
+                    new_value: Some(new_value),
+                    ..import_entry(change, name, new_import)
+                });
+            }
+        };
+
+        push(
+            "url",
+            None,
+            compare_sets(&old_import.url, &new_import.url),
+            old_import.url.clone(),
+            new_import.url.clone(),
+        );
+        // Removing a pin makes the import follow upstream changes again.
+        for (field, old_pins, new_pins) in [
+            ("sha256", &old_import.sha256, &new_import.sha256),
+            ("rev", &old_import.rev, &new_import.rev),
+        ] {
+            let change = if old_pins == new_pins {
+                None
+            } else if new_pins.is_empty() {
+                Some(StoreDiffChange::Broadened)
+            } else if old_pins.is_empty() {
+                Some(StoreDiffChange::Narrowed)
+            } else {
+                Some(StoreDiffChange::Changed)
+            };
+            push(field, None, change, old_pins.clone(), new_pins.clone());
+        }
+        // Excluding fewer crates imports more audits, so the sets are compared
+        // the other way around.
+        push(
+            "exclude",
+            None,
+            compare_sets(&new_import.exclude, &old_import.exclude),
+            old_import.exclude.clone(),
+            new_import.exclude.clone(),
+        );
+
+        let foreign_criteria: SortedSet<&String> = old_import
+            .criteria_map
+            .keys()
+            .chain(new_import.criteria_map.keys())
+            .map(|name| &**name)
+            .collect();
+        for foreign in foreign_criteria {
+            let old_criteria = criteria_map_entry(&old_import.criteria_map, foreign);
+            let new_criteria = criteria_map_entry(&new_import.criteria_map, foreign);
+            let change = match (&old_criteria, &new_criteria) {
+                (None, None) => None,
+                (None, Some(_)) => Some(StoreDiffChange::Broadened),
+                (Some(_), None) => Some(StoreDiffChange::Narrowed),
+                (Some(old_criteria), Some(new_criteria)) => {
+                    compare_criteria(mapper, old_criteria, new_criteria)
+                }
+            };
+            push(
+                "criteria-map",
+                Some(foreign),
+                change,
+                old_criteria.unwrap_or_default(),
+                new_criteria.unwrap_or_default(),
+            );
+        }
+
+        // An empty `max-criteria`, `auditors` or `public-key` places no
+        // restriction on what is imported.
+        let old_max_criteria = criteria_names(&old_import.max_criteria);
+        let new_max_criteria = criteria_names(&new_import.max_criteria);
+        push(
+            "max-criteria",
+            None,
+            compare_restriction(&old_max_criteria, &new_max_criteria, |old, new| {
+                compare_criteria(mapper, old, new)
+            }),
+            old_max_criteria,
+            new_max_criteria,
+        );
+        for (field, old_list, new_list) in [
+            ("auditors", &old_import.auditors, &new_import.auditors),
+            ("public-key", &old_import.public_key, &new_import.public_key),
+        ] {
+            push(
+                field,
+                None,
+                compare_restriction(old_list, new_list, compare_sets),
+                old_list.clone(),
+                new_list.clone(),
+            );
+        }
+
+        for (field, old_excluded, new_excluded) in [
+            (
+                "exclude-wildcard-audits",
+                old_import.exclude_wildcard_audits,
+                new_import.exclude_wildcard_audits,
+            ),
+            (
+                "exclude-trusted",
+                old_import.exclude_trusted,
+                new_import.exclude_trusted,
+            ),
+        ] {
+            let change = match (old_excluded, new_excluded) {
+                (true, false) => Some(StoreDiffChange::Broadened),
+                (false, true) => Some(StoreDiffChange::Narrowed),
+                _ => None,
+            };
+            push(
+                field,
+                None,
+                change,
+                vec![old_excluded.to_string()],
+                vec![new_excluded.to_string()],
+            );
+        }
+    }
+}
+
+fn import_entry(change: StoreDiffChange, name: &str, import: &RemoteImport) -> JsonStoreDiffImport {
+    JsonStoreDiffImport {
+        change,
+        name: name.to_owned(),
+        url: import.url.clone(),
+        field: None,
+        key: None,
+        old_value: None,
+        new_value: None,
+    }
+}
+
+fn criteria_map_entry(criteria_map: &CriteriaMap, foreign: &str) -> Option<Vec<CriteriaName>> {
+    criteria_map
+        .iter()
+        .find(|(name, _)| ***name == foreign)
+        .map(|(_, criteria)| criteria_names(criteria))
+}
+
+/// Determine how a list changed when compared as a set, where gaining entries
+/// is a broadening, or `None` if it didn't.
+fn compare_sets(old: &[String], new: &[String]) -> Option<StoreDiffChange> {
+    let old: SortedSet<&String> = old.iter().collect();
+    let new: SortedSet<&String> = new.iter().collect();
+    match (new.is_superset(&old), old.is_superset(&new)) {
+        (true, true) => None,
+        (true, false) => Some(StoreDiffChange::Broadened),
+        (false, true) => Some(StoreDiffChange::Narrowed),
+        (false, false) => Some(StoreDiffChange::Changed),
+    }
+}
+
+/// Determine how a restriction changed, where an empty list is unrestricted
+/// and non-empty lists are compared with `compare`.
+fn compare_restriction<T>(
+    old: &[T],
+    new: &[T],
+    compare: impl FnOnce(&[T], &[T]) -> Option<StoreDiffChange>,
+) -> Option<StoreDiffChange> {
+    match (old.is_empty(), new.is_empty()) {
+        (true, true) => None,
+        (true, false) => Some(StoreDiffChange::Narrowed),
+        (false, true) => Some(StoreDiffChange::Broadened),
+        (false, false) => compare(old, new),
+    }
+}
+
+fn diff_policy(
+    old: &Store,
+    new: &Store,
+    mapper: Option<&CriteriaMapper>,
+    diff: &mut JsonStoreDiff,
+) {
+    type PolicyKey = (String, Option<VetVersion>);
+    let collect = |store: &Store| -> SortedMap<PolicyKey, PolicyEntry> {
+        (&store.config.policy)
+            .into_iter()
+            .map(|(name, version, entry)| ((name.clone(), version.cloned()), entry.clone()))
+            .collect()
+    };
+    let old_policy = collect(old);
+    let new_policy = collect(new);
+    let keys: SortedSet<&PolicyKey> = old_policy.keys().chain(new_policy.keys()).collect();
+
+    for key in keys {
+        let old_entry = old_policy.get(key);
+        let new_entry = new_policy.get(key);
+        let mut push = |field: &str,
+                        dependency: Option<&String>,
+                        old_criteria: Option<&Vec<Spanned<CriteriaName>>>,
+                        new_criteria: Option<&Vec<Spanned<CriteriaName>>>| {
+            let old_criteria = old_criteria.map(|c| criteria_names(c));
+            let new_criteria = new_criteria.map(|c| criteria_names(c));
+            let change = match (&old_criteria, &new_criteria) {
+                (None, None) => return,
+                (None, Some(_)) => StoreDiffChange::Added,
+                (Some(_), None) => StoreDiffChange::Removed,
+                (Some(old_criteria), Some(new_criteria)) => {
+                    match compare_criteria(mapper, old_criteria, new_criteria) {
+                        Some(_) => StoreDiffChange::Changed,
+                        None => return,
+                    }
+                }
+            };
+            diff.policy.push(JsonStoreDiffPolicy {
+                change,
+                name: key.0.clone(),
+                version: key.1.clone(),
+                field: field.to_owned(),
+                dependency: dependency.cloned(),
+                old_criteria,
+                new_criteria,
+            });
+        };
+
+        push(
+            "criteria",
+            None,
+            old_entry.and_then(|e| e.criteria.as_ref()),
+            new_entry.and_then(|e| e.criteria.as_ref()),
+        );
+        push(
+            "dev-criteria",
+            None,
+            old_entry.and_then(|e| e.dev_criteria.as_ref()),
+            new_entry.and_then(|e| e.dev_criteria.as_ref()),
+        );
+        let dependencies: SortedSet<&String> = old_entry
+            .into_iter()
+            .chain(new_entry)
+            .flat_map(|e| e.dependency_criteria.keys().map(|dep| &**dep))
+            .collect();
+        for dependency in dependencies {
+            push(
+                "dependency-criteria",
+                Some(dependency),
+                dependency_criteria(old_entry, dependency),
+                dependency_criteria(new_entry, dependency),
+            );
+        }
+    }
+}
+
+fn dependency_criteria<'a>(
+    entry: Option<&'a PolicyEntry>,
+    dependency: &str,
+) -> Option<&'a Vec<Spanned<CriteriaName>>> {
+    entry?
+        .dependency_criteria
+        .iter()
+        .find(|(dep, _)| ***dep == dependency)
+        .map(|(_, criteria)| criteria)
+}
+
+fn diff_trusted(old: &Store, new: &Store, diff: &mut JsonStoreDiff) {
+    let same_trust = |a: &TrustEntry, b: &TrustEntry| {
+        a.user_id == b.user_id && criteria_names(&a.criteria) == criteria_names(&b.criteria)
+    };
+    for (name, old_trusted, new_trusted) in each_package(&old.audits.trusted, &new.audits.trusted) {
+        for (change, entry) in added_and_removed(old_trusted, new_trusted, same_trust) {
+            diff.trusted.push(JsonStoreDiffTrusted {
+                change,
+                name: name.clone(),
+                user_id: entry.user_id,
+                criteria: criteria_names(&entry.criteria),
+            });
+        }
+    }
+}
+
+/// Print the changes to the supply-chain for humans, optionally formatted as
+/// markdown for a pull request comment.
+pub fn print_store_diff(out: &Arc<dyn Out>, diff: &JsonStoreDiff, markdown: bool) {
+    if diff.is_empty() {
+        writeln!(out, "No changes to the supply-chain.");
+        return;
+    }
+
+    let code = |s: &str| {
+        if markdown {
+            format!("`{s}`")
+        } else {
+            s.to_owned()
+        }
+    };
+    let criteria = |criteria: &[CriteriaName]| {
+        if criteria.is_empty() {
+            "no criteria".to_owned()
+        } else {
+            criteria
+                .iter()
+                .map(|c| code(c))
+                .collect::<Vec<_>>()
+                .join(", ")
+        }
+    };
+    let policy_criteria = |c: &Option<Vec<CriteriaName>>| match c {
+        Some(c) => criteria(c),
+        None => "the default".to_owned(),
+    };
+
+    let mut sections: Vec<(&str, Vec<(StoreDiffChange, String)>)> = vec![];
+    sections.push((
+        "Audits",
+        diff.audits
+            .iter()
+            .map(|audit| {
+                let what = match &audit.kind[..] {
+                    "violation" => format!("violation against {}", code(&audit.name)),
+                    "wildcard" => format!("wildcard audit of {}", code(&audit.name)),
+                    kind => format!("{kind} audit of {}", code(&audit.name)),
+                };
+                let target = if audit.kind == "wildcard" {
+                    format!("for crates.io user {}", audit.target)
+                } else {
+                    code(&audit.target)
+                };
+                let mut line = format!("{what} {target} for {}", criteria(&audit.criteria));
+                if !audit.who.is_empty() {
+                    line += &format!(" (by {})", audit.who.join(", "));
+                }
+                (audit.change, line)
+            })
+            .collect(),
+    ));
+    sections.push((
+        "Exemptions",
+        diff.exemptions
+            .iter()
+            .map(|exemption| {
+                let package = code(&format!("{}:{}", exemption.name, exemption.version));
+                let line = match exemption.change {
+                    StoreDiffChange::Added => {
+                        format!("{package} for {}", criteria(&exemption.new_criteria))
+                    }
+                    StoreDiffChange::Removed => {
+                        format!("{package} for {}", criteria(&exemption.old_criteria))
+                    }
+                    _ => format!(
+                        "{package} from {} to {}",
+                        criteria(&exemption.old_criteria),
+                        criteria(&exemption.new_criteria)
+                    ),
+                };
+                (exemption.change, line)
+            })
+            .collect(),
+    ));
+    sections.push((
+        "Imports",
+        diff.imports
+            .iter()
+            .map(|import| {
+                let list = |values: &[String]| {
+                    if values.is_empty() {
+                        "none".to_owned()
+                    } else {
+                        values
+                            .iter()
+                            .map(|value| code(value))
+                            .collect::<Vec<_>>()
+                            .join(", ")
+                    }
+                };
+                let line = match (&import.field, &import.old_value, &import.new_value) {
+                    (Some(field), Some(old_value), Some(new_value)) => {
+                        let field = match &import.key {
+                            Some(key) => format!("{field} for {}", code(key)),
+                            None => field.clone(),
+                        };
+                        format!(
+                            "{field} of {} from {} to {}",
+                            code(&import.name),
+                            list(old_value),
+                            list(new_value)
+                        )
+                    }
+                    _ => format!("{} from {}", code(&import.name), list(&import.url)),
+                };
+                (import.change, line)
+            })
+            .collect(),
+    ));
+    sections.push((
+        "Policy",
+        diff.policy
+            .iter()
+            .map(|policy| {
+                let package = match &policy.version {
+                    Some(version) => format!("{}:{version}", policy.name),
+                    None => policy.name.clone(),
+                };
+                let field = match &policy.dependency {
+                    Some(dependency) => format!("{} for {}", policy.field, code(dependency)),
+                    None => policy.field.clone(),
+                };
+                let line = format!(
+                    "{field} of {} from {} to {}",
+                    code(&package),
+                    policy_criteria(&policy.old_criteria),
+                    policy_criteria(&policy.new_criteria)
+                );
+                (policy.change, line)
+            })
+            .collect(),
+    ));
+    sections.push((
+        "Trusted publishers",
+        diff.trusted
+            .iter()
+            .map(|trusted| {
+                let line = format!(
+                    "crates.io user {} for {} with {}",
+                    trusted.user_id,
+                    code(&trusted.name),
+                    criteria(&trusted.criteria)
+                );
+                (trusted.change, line)
+            })
+            .collect(),
+    ));
+
+    let mut first = true;
+    for (title, lines) in sections {
+        if lines.is_empty() {
+            continue;
+        }
+        if !first {
+            writeln!(out);
+        }
+        first = false;
+
+        if markdown {
+            writeln!(out, "### {title}");
+            writeln!(out);
+        } else {
+            writeln!(out, "{title}:");
+        }
+        for (change, line) in lines {
+            let change = match change {
+                StoreDiffChange::Added => "added",
+                StoreDiffChange::Removed => "removed",
+                StoreDiffChange::Broadened => "broadened",
+                StoreDiffChange::Narrowed => "narrowed",
+                StoreDiffChange::Changed => "changed",
+            };
+            if markdown {
+                writeln!(out, "- **{change}** {line}");
+            } else {
+                writeln!(out, "  {change:<9}  {line}");
+            }
+        }
+    }
+}
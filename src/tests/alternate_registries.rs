@@ -0,0 +1,162 @@
+use super::*;
+
+use crate::format::{AlternateRegistry, RegistryName};
+
+/// A workspace depending on `thing` from both crates.io and the alternate
+/// registry `internal`.
+fn mock_registry_metadata() -> Metadata {
+    MockMetadata::new(vec![
+        MockPackage {
+            name: "root-package",
+            is_workspace: true,
+            is_first_party: true,
+            deps: vec![dep("thing"), dep_ver("thing", 5)],
+            ..Default::default()
+        },
+        MockPackage {
+            name: "thing",
+            ..Default::default()
+        },
+        MockPackage {
+            name: "thing",
+            version: ver(5),
+            registry: Some("internal"),
+            ..Default::default()
+        },
+    ])
+    .metadata()
+}
+
+fn mock_registries() -> SortedMap<RegistryName, AlternateRegistry> {
+    [(
+        "internal".to_owned(),
+        AlternateRegistry {
+            index: "sparse+https://internal.example.com/index/".to_owned(),
+        },
+    )]
+    .into_iter()
+    .collect()
+}
+
+#[test]
+fn unlisted_registry_is_first_party() {
+    // (Pass) Packages from a registry which isn't listed in config.toml are
+    // treated as first-party, and don't need audits.
+
+    let _enter = TEST_RUNTIME.enter();
+
+    let metadata = mock_registry_metadata();
+    let (config, audits, imports) = files_full_audited(&metadata);
+
+    let store = Store::mock(config, audits, imports);
+
+    assert_report_snapshot!("alternate-registry-unlisted", metadata, store);
+}
+
+#[test]
+fn listed_registry_is_third_party() {
+    // (Fail) Packages from a registry listed in config.toml are third-party,
+    // and are named by vet using their qualified name.
+
+    let _enter = TEST_RUNTIME.enter();
+
+    let mut metadata = mock_registry_metadata();
+    crate::qualify_registry_packages(&mut metadata, &mock_registries());
+    let (mut config, audits, imports) = files_no_exemptions(&metadata);
+    config.registries = mock_registries();
+
+    let store = Store::mock(config, audits, imports);
+
+    assert_report_snapshot!("alternate-registry-listed", metadata, store);
+}
+
+#[test]
+fn crates_io_audit_does_not_apply() {
+    // (Fail) An audit for the crates.io package with the same name and version
+    // isn't used for the package from the alternate registry.
+
+    let _enter = TEST_RUNTIME.enter();
+
+    let mut metadata = mock_registry_metadata();
+    crate::qualify_registry_packages(&mut metadata, &mock_registries());
+    let (mut config, mut audits, imports) = files_full_audited(&metadata);
+    config.registries = mock_registries();
+
+    audits.audits.remove("internal:thing");
+    audits
+        .audits
+        .get_mut("thing")
+        .unwrap()
+        .push(full_audit(ver(5), DEFAULT_CRIT));
+
+    let store = Store::mock(config, audits, imports);
+
+    assert_report_snapshot!("alternate-registry-crates-io-audit", metadata, store);
+}
+
+#[test]
+fn qualified_audit_applies() {
+    // (Pass) An audit recorded with the qualified name is used for the package
+    // from the alternate registry.
+
+    let _enter = TEST_RUNTIME.enter();
+
+    let mut metadata = mock_registry_metadata();
+    crate::qualify_registry_packages(&mut metadata, &mock_registries());
+    let (mut config, audits, imports) = files_full_audited(&metadata);
+    config.registries = mock_registries();
+
+    assert!(audits.audits.contains_key("internal:thing"));
+
+    let store = Store::mock(config, audits, imports);
+
+    assert_report_snapshot!("alternate-registry-qualified-audit", metadata, store);
+}
+
+#[test]
+fn suggest_uses_registry_index() {
+    // (Fail) Suggestions for a package from an alternate registry are based on
+    // the versions published in that registry's index.
+
+    let _enter = TEST_RUNTIME.enter();
+
+    let mut metadata = mock_registry_metadata();
+    crate::qualify_registry_packages(&mut metadata, &mock_registries());
+    let (mut config, mut audits, imports) = files_full_audited(&metadata);
+    config.registries = mock_registries();
+
+    audits.audits.insert(
+        "internal:thing".to_owned(),
+        vec![full_audit(ver(3), DEFAULT_CRIT)],
+    );
+
+    let mut network = Network::new_mock();
+    network.mock_serve(
+        "https://internal.example.com/index/th/in/thing",
+        [3, 4, 5]
+            .iter()
+            .map(|v| {
+                serde_json::to_string(&json!({
+                    "name": "thing",
+                    "vers": format!("{v}.0.0"),
+                    "deps": [],
+                    "cksum": "90527ab4abff2f0608cdb1a78e2349180e1d92059f59b5a65ce2a1a15a499b73",
+                    "features": {},
+                    "yanked": false
+                }))
+                .unwrap()
+            })
+            .collect::<Vec<_>>()
+            .join("\n"),
+    );
+
+    let cfg = mock_cfg(&metadata);
+    let store = Store::mock_online(&cfg, config, audits, imports, &network, false).unwrap();
+
+    assert_report_snapshot!(
+        "alternate-registry-suggest",
+        metadata,
+        store,
+        Some(&network)
+    );
+}
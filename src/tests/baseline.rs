@@ -0,0 +1,236 @@
+use super::*;
+
+/// `MockMetadata::simple`, but with `third-party2` depended on by the root
+/// package through `third_party2`, or not at all if it's `None`.
+fn simple_with_third_party2(third_party2: Option<MockDependency>, dev_dep: bool) -> MockMetadata {
+    let mut root_deps = vec![];
+    let mut root_dev_deps = vec![];
+    let third_party2 = third_party2.map(|third_party2| {
+        let package = MockPackage {
+            name: "third-party2",
+            version: third_party2.version.clone(),
+            ..Default::default()
+        };
+        if dev_dep {
+            root_dev_deps.push(third_party2);
+        } else {
+            root_deps.push(third_party2);
+        }
+        package
+    });
+    root_deps.push(dep("first-party"));
+
+    MockMetadata::new(
+        [
+            MockPackage {
+                name: "root-package",
+                is_workspace: true,
+                is_first_party: true,
+                deps: root_deps,
+                dev_deps: root_dev_deps,
+                ..Default::default()
+            },
+            MockPackage {
+                name: "first-party",
+                is_first_party: true,
+                deps: vec![dep("third-party1")],
+                ..Default::default()
+            },
+            MockPackage {
+                name: "third-party1",
+                deps: vec![dep("transitive-third-party1")],
+                ..Default::default()
+            },
+            MockPackage {
+                name: "transitive-third-party1",
+                ..Default::default()
+            },
+        ]
+        .into_iter()
+        .chain(third_party2)
+        .collect(),
+    )
+}
+
+fn get_baseline_reports(
+    metadata: &Metadata,
+    store: &Store,
+    baseline_metadata: &Metadata,
+    baseline_store: &Store,
+) -> String {
+    let baseline = crate::resolver::resolve_baseline(
+        baseline_metadata,
+        &SortedMap::new(),
+        &SortedMap::new(),
+        None,
+        baseline_store,
+    );
+    let report = crate::resolver::resolve_with_baseline(
+        metadata,
+        &SortedMap::new(),
+        &SortedMap::new(),
+        None,
+        store,
+        Some(&baseline),
+    );
+    let (human, json) = get_reports(metadata, report, store, None);
+    format!("{human}\n{json}")
+}
+
+#[test]
+fn baseline_new_package() {
+    // (Fail) Only the package which was added since the baseline is reported,
+    // and the others are ignored because they already failed to vet.
+
+    let _enter = TEST_RUNTIME.enter();
+    let mock = MockMetadata::simple();
+    let baseline_mock = simple_with_third_party2(None, false);
+
+    let metadata = mock.metadata();
+    let (config, audits, imports) = builtin_files_no_exemptions(&metadata);
+    let store = Store::mock(config, audits, imports);
+
+    let baseline_metadata = baseline_mock.metadata();
+    let (config, audits, imports) = builtin_files_no_exemptions(&baseline_metadata);
+    let baseline_store = Store::mock(config, audits, imports);
+
+    insta::assert_snapshot!(get_baseline_reports(
+        &metadata,
+        &store,
+        &baseline_metadata,
+        &baseline_store
+    ));
+}
+
+#[test]
+fn baseline_upgraded_package() {
+    // (Fail) A package which was upgraded since the baseline is reported.
+
+    let _enter = TEST_RUNTIME.enter();
+    let mock = MockMetadata::simple();
+    let baseline_mock = simple_with_third_party2(Some(dep_ver("third-party2", 5)), false);
+
+    let metadata = mock.metadata();
+    let (config, audits, imports) = builtin_files_no_exemptions(&metadata);
+    let store = Store::mock(config, audits, imports);
+
+    let baseline_metadata = baseline_mock.metadata();
+    let (config, audits, imports) = builtin_files_no_exemptions(&baseline_metadata);
+    let baseline_store = Store::mock(config, audits, imports);
+
+    insta::assert_snapshot!(get_baseline_reports(
+        &metadata,
+        &store,
+        &baseline_metadata,
+        &baseline_store
+    ));
+}
+
+#[test]
+fn baseline_nothing_new() {
+    // (Pass) Every unvetted package already failed to vet in the baseline.
+
+    let _enter = TEST_RUNTIME.enter();
+    let mock = MockMetadata::simple();
+
+    let metadata = mock.metadata();
+    let (config, audits, imports) = builtin_files_no_exemptions(&metadata);
+    let store = Store::mock(config, audits, imports);
+    let (config, audits, imports) = builtin_files_no_exemptions(&metadata);
+    let baseline_store = Store::mock(config, audits, imports);
+
+    insta::assert_snapshot!(get_baseline_reports(
+        &metadata,
+        &store,
+        &metadata,
+        &baseline_store
+    ));
+}
+
+#[test]
+fn baseline_removed_audit() {
+    // (Fail) A package which was vetted in the baseline is reported once its
+    // audit is removed, even though its version didn't change.
+
+    let _enter = TEST_RUNTIME.enter();
+    let mock = MockMetadata::simple();
+
+    let metadata = mock.metadata();
+    let (config, mut audits, imports) = builtin_files_full_audited(&metadata);
+    audits.audits.remove("third-party1");
+    let store = Store::mock(config, audits, imports);
+    let (config, audits, imports) = builtin_files_full_audited(&metadata);
+    let baseline_store = Store::mock(config, audits, imports);
+
+    insta::assert_snapshot!(get_baseline_reports(
+        &metadata,
+        &store,
+        &metadata,
+        &baseline_store
+    ));
+}
+
+#[test]
+fn baseline_dev_dep_promoted() {
+    // (Fail) A package which only needed to be safe-to-run as a dev-dependency
+    // in the baseline is reported once it becomes a normal dependency.
+
+    let _enter = TEST_RUNTIME.enter();
+    let mock = simple_with_third_party2(Some(dep("third-party2")), false);
+    let baseline_mock = simple_with_third_party2(Some(dep("third-party2")), true);
+
+    let metadata = mock.metadata();
+    let (config, mut audits, imports) = builtin_files_full_audited(&metadata);
+    audits.audits.insert(
+        "third-party2".to_owned(),
+        vec![full_audit(ver(DEFAULT_VER), SAFE_TO_RUN)],
+    );
+    let store = Store::mock(config, audits.clone(), imports.clone());
+
+    let baseline_metadata = baseline_mock.metadata();
+    let baseline_store = Store::mock(
+        builtin_files_full_audited(&baseline_metadata).0,
+        audits,
+        imports,
+    );
+
+    insta::assert_snapshot!(get_baseline_reports(
+        &metadata,
+        &store,
+        &baseline_metadata,
+        &baseline_store
+    ));
+}
+
+#[test]
+fn baseline_violation_conflict() {
+    // (Fail) The cost of the change isn't reported as nothing when the check
+    // failed due to a violation conflict.
+
+    let _enter = TEST_RUNTIME.enter();
+    let mock = MockMetadata::simple();
+
+    let metadata = mock.metadata();
+    let violation_ver = VersionReq::parse(&format!("={DEFAULT_VER}")).unwrap();
+    let files = || {
+        let (config, mut audits, imports) = builtin_files_full_audited(&metadata);
+        audits
+            .audits
+            .get_mut("third-party1")
+            .unwrap()
+            .push(violation(violation_ver.clone(), SAFE_TO_RUN));
+        (config, audits, imports)
+    };
+
+    let (config, audits, imports) = files();
+    let store = Store::mock(config, audits, imports);
+    let (config, audits, imports) = files();
+    let baseline_store = Store::mock(config, audits, imports);
+
+    insta::assert_snapshot!(get_baseline_reports(
+        &metadata,
+        &store,
+        &metadata,
+        &baseline_store
+    ));
+}
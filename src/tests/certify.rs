@@ -1,4 +1,5 @@
 use super::*;
+use crate::format::{Delta, DiffStat, PackageDiff, RiskIndicator, RiskKind};
 use std::fmt::Write;
 
 #[test]
@@ -138,6 +139,7 @@ fn mock_simple_certify_flow() {
         &mut store,
         None,
         None,
+        &SortedMap::new(),
     )
     .expect("do_cmd_certify failed");
 
@@ -200,6 +202,7 @@ fn mock_delta_certify_flow() {
         &mut store,
         None,
         None,
+        &SortedMap::new(),
     )
     .expect("do_cmd_certify failed");
 
@@ -210,6 +213,87 @@ fn mock_delta_certify_flow() {
     insta::assert_snapshot!("mock-delta-certify-flow", result);
 }
 
+#[test]
+fn mock_delta_certify_risk_indicators() {
+    // Risk indicators found while diffing the delta are shown in the editor.
+
+    let mock = MockMetadata::simple();
+
+    let _enter = TEST_RUNTIME.enter();
+    let metadata = mock.metadata();
+
+    let (config, audits, imports) = files_inited(&metadata);
+
+    let mut store = Store::mock(config, audits, imports);
+
+    let output = BasicTestOutput::with_callbacks(
+        |_| Ok("\n".to_owned()),
+        |_| {
+            Ok("I, testing, certify that I have audited the changes from version 10.0.0 to 10.0.1 of third-party1 in accordance with the above criteria.\n".to_owned())
+        },
+    );
+
+    let cfg = mock_cfg_args(
+        &metadata,
+        [
+            "cargo",
+            "vet",
+            "certify",
+            "third-party1",
+            "10.0.0",
+            "10.0.1",
+            "--who",
+            "testing",
+            "--criteria",
+            "safe-to-deploy",
+        ],
+    );
+    let sub_args = if let Some(crate::cli::Commands::Certify(sub_args)) = &cfg.cli.command {
+        sub_args
+    } else {
+        unreachable!();
+    };
+
+    let delta = Delta {
+        from: Some(ver(10)),
+        to: "10.0.1".parse().unwrap(),
+    };
+    let diff = PackageDiff {
+        diffstat: DiffStat {
+            files_changed: 2,
+            insertions: 12,
+            deletions: 3,
+        },
+        files: vec![],
+        proc_macro: false,
+        risks: vec![
+            RiskIndicator {
+                kind: RiskKind::Unsafe,
+                occurrences: 2,
+                files: vec!["src/lib.rs".to_owned()],
+            },
+            RiskIndicator {
+                kind: RiskKind::BuildScript,
+                occurrences: 5,
+                files: vec!["build.rs".to_owned()],
+            },
+        ],
+    };
+
+    crate::do_cmd_certify(
+        &output.clone().as_dyn(),
+        &cfg,
+        sub_args,
+        &mut store,
+        None,
+        None,
+        &[(delta, diff)].into_iter().collect(),
+    )
+    .expect("do_cmd_certify failed");
+
+    insta::assert_snapshot!(output.to_string());
+}
+
 #[test]
 fn mock_delta_git_certify_flow() {
     let mock = MockMetadata::simple_local_git();
@@ -266,6 +350,7 @@ fn mock_delta_git_certify_flow() {
         &mut store,
         None,
         None,
+        &SortedMap::new(),
     )
     .expect("do_cmd_certify failed");
 
@@ -355,6 +440,7 @@ fn mock_prune_non_importable_audit() {
         &mut store,
         None,
         None,
+        &SortedMap::new(),
     )
     .expect("do_cmd_certify failed");
 
@@ -437,6 +523,7 @@ fn mock_collapse_non_importable_audits() {
         &mut store,
         None,
         None,
+        &SortedMap::new(),
     )
     .expect("do_cmd_certify failed");
 
@@ -511,6 +598,7 @@ fn mock_collapse_with_full_audit() {
         &mut store,
         None,
         None,
+        &SortedMap::new(),
     )
     .expect("do_cmd_certify failed");
 
@@ -593,6 +681,7 @@ fn mock_dont_collapse_incompatible_criteria_non_importable_audits() {
         &mut store,
         None,
         None,
+        &SortedMap::new(),
     )
     .expect("do_cmd_certify failed");
 
@@ -671,6 +760,7 @@ fn mock_wildcard_certify_flow() {
         &mut store,
         Some(&network),
         None,
+        &SortedMap::new(),
     )
     .expect("do_cmd_certify failed");
 
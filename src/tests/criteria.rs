@@ -0,0 +1,294 @@
+use crate::criteria::CriteriaMapper;
+
+use super::*;
+
+/// Custom criteria `criteria-0` through `criteria-{count - 1}`, each implying
+/// the one before it.
+fn chained_criteria(count: usize) -> SortedMap<CriteriaName, CriteriaEntry> {
+    (0..count)
+        .map(|idx| {
+            let implies = if idx == 0 {
+                vec![]
+            } else {
+                vec![format!("criteria-{}", idx - 1)]
+            };
+            (
+                format!("criteria-{idx}"),
+                criteria_implies("custom criteria", implies),
+            )
+        })
+        .collect()
+}
+
+#[test]
+fn criteria_set_beyond_64() {
+    // Criteria sets work with more criteria than fit in a single word.
+
+    let mapper = CriteriaMapper::new(&chained_criteria(150));
+    assert_eq!(mapper.len(), 152);
+
+    let strongest = mapper.criteria_from_list(["criteria-149"]);
+    let weakest = mapper.criteria_from_list(["criteria-0"]);
+    assert!(strongest.contains(&weakest));
+    assert!(!weakest.contains(&strongest));
+    assert_eq!(strongest.indices().count(), 150);
+    assert_eq!(
+        mapper.criteria_names(&strongest).collect::<Vec<_>>(),
+        vec!["criteria-149"]
+    );
+
+    let mut set = mapper.no_criteria();
+    assert!(set.is_empty());
+    set.set_criteria(140);
+    set.set_criteria(3);
+    assert_eq!(set.indices().collect::<Vec<_>>(), vec![3, 140]);
+    set.clear_criteria(&strongest);
+    assert!(set.is_empty());
+
+    let all = mapper.all_criteria();
+    assert_eq!(all.indices().count(), 152);
+    assert!(all.contains(&strongest));
+}
+
+#[test]
+fn many_criteria() {
+    // (Pass) Audits for custom criteria beyond the 64th satisfy policies.
+
+    let _enter = TEST_RUNTIME.enter();
+    let mock = MockMetadata::simple();
+
+    let metadata = mock.metadata();
+    let (mut config, mut audits, imports) = builtin_files_full_audited(&metadata);
+
+    audits.criteria = chained_criteria(100);
+    for package in ["third-party1", "transitive-third-party1"] {
+        audits.audits.insert(
+            package.to_owned(),
+            vec![full_audit_m(
+                ver(DEFAULT_VER),
+                ["criteria-99", SAFE_TO_DEPLOY],
+            )],
+        );
+    }
+    config.policy.insert(
+        "first-party".to_owned(),
+        dep_policy([("third-party1", ["criteria-70"])]),
+    );
+
+    let store = Store::mock(config, audits, imports);
+
+    assert_report_snapshot!("many_criteria", metadata, store);
+}
+
+// Not real tests, but benchmarks of resolving a large graph and of the
+// `CriteriaSet` operations the resolver relies on. Run them with:
+//
+// ```text
+// cargo test --release bench_ -- --ignored --nocapture --test-threads 1
+// ```
+//
+// As a baseline, resolving the 2000-package graph with the built-in criteria
+// took 23-24.5ms per iteration with the previous `u64`-based `CriteriaSet`
+// (at 833a75b), and 24-27ms with the growable one, on the same machine.
+// `bench_criteria_set` compares the set operations directly against a copy of
+// the `u64` implementation.
+
+const BENCH_PACKAGES: usize = 2000;
+
+/// Resolve a graph of `BENCH_PACKAGES` third-party packages, each depending on
+/// the next three and audited with a full audit and two deltas for
+/// safe-to-deploy, and for the strongest of `custom_criteria` chained custom
+/// criteria if there are any.
+fn bench_resolve(label: &str, custom_criteria: usize) {
+    const ITERATIONS: u32 = 20;
+
+    let _enter = TEST_RUNTIME.enter();
+
+    let names: Vec<&'static str> = (0..BENCH_PACKAGES)
+        .map(|idx| &*Box::leak(format!("third-party{idx}").into_boxed_str()))
+        .collect();
+    let mut packages = vec![MockPackage {
+        name: "root-package",
+        is_workspace: true,
+        is_first_party: true,
+        deps: names.iter().step_by(50).map(|&name| dep(name)).collect(),
+        ..Default::default()
+    }];
+    for (idx, &name) in names.iter().enumerate() {
+        packages.push(MockPackage {
+            name,
+            deps: names[idx + 1..]
+                .iter()
+                .take(3)
+                .map(|&name| dep(name))
+                .collect(),
+            ..Default::default()
+        });
+    }
+    let mock = MockMetadata::new(packages);
+
+    let metadata = mock.metadata();
+    let (mut config, mut audits, imports) = builtin_files_inited(&metadata);
+    let mut criteria = vec![SAFE_TO_DEPLOY.to_owned()];
+    if let Some(strongest) = custom_criteria.checked_sub(1) {
+        audits.criteria = chained_criteria(custom_criteria);
+        criteria.push(format!("criteria-{strongest}"));
+        config
+            .policy
+            .insert("root-package".to_owned(), self_policy(criteria.clone()));
+    }
+    let audit = |kind| AuditEntry {
+        kind,
+        criteria: criteria.iter().map(|c| c.clone().into()).collect(),
+        ..full_audit(ver(1), SAFE_TO_DEPLOY)
+    };
+    for &name in &names {
+        audits.audits.insert(
+            name.to_owned(),
+            vec![
+                audit(AuditKind::Full { version: ver(1) }),
+                audit(AuditKind::Delta {
+                    from: ver(1),
+                    to: ver(5),
+                }),
+                audit(AuditKind::Delta {
+                    from: ver(5),
+                    to: ver(DEFAULT_VER),
+                }),
+            ],
+        );
+    }
+    let store = Store::mock(config, audits, imports);
+
+    let start = std::time::Instant::now();
+    for _ in 0..ITERATIONS {
+        let report = crate::resolver::resolve(&metadata, None, &store);
+        assert!(!report.has_errors());
+    }
+    println!(
+        "{label}: resolved {BENCH_PACKAGES} packages in {:?} per iteration",
+        start.elapsed() / ITERATIONS
+    );
+}
+
+#[test]
+#[ignore]
+fn bench_resolve_large_graph() {
+    bench_resolve("built-in criteria", 0);
+}
+
+#[test]
+#[ignore]
+fn bench_resolve_large_graph_many_criteria() {
+    bench_resolve("70 custom criteria", 70);
+}
+
+/// The operations the resolver performs on criteria sets, so that
+/// `CriteriaSet` can be compared against the `u64` it replaced.
+trait BenchCriteriaSet: Clone {
+    fn none(count: usize) -> Self;
+    fn set_criteria(&mut self, idx: usize);
+    fn unioned_with(&mut self, other: &Self);
+    fn contains(&self, other: &Self) -> bool;
+    fn has_criteria(&self, idx: usize) -> bool;
+    fn count(&self) -> usize;
+}
+
+impl BenchCriteriaSet for crate::criteria::CriteriaSet {
+    fn none(count: usize) -> Self {
+        Self::none(count)
+    }
+    fn set_criteria(&mut self, idx: usize) {
+        self.set_criteria(idx)
+    }
+    fn unioned_with(&mut self, other: &Self) {
+        self.unioned_with(other)
+    }
+    fn contains(&self, other: &Self) -> bool {
+        self.contains(other)
+    }
+    fn has_criteria(&self, idx: usize) -> bool {
+        self.has_criteria(idx)
+    }
+    fn count(&self) -> usize {
+        self.indices().count()
+    }
+}
+
+/// The `u64`-based `CriteriaSet` from before the 64-criteria limit was lifted.
+#[derive(Clone)]
+struct U64CriteriaSet(u64);
+
+impl BenchCriteriaSet for U64CriteriaSet {
+    fn none(_count: usize) -> Self {
+        U64CriteriaSet(0)
+    }
+    fn set_criteria(&mut self, idx: usize) {
+        self.0 |= 1 << idx;
+    }
+    fn unioned_with(&mut self, other: &Self) {
+        self.0 |= other.0;
+    }
+    fn contains(&self, other: &Self) -> bool {
+        (self.0 & other.0) == other.0
+    }
+    fn has_criteria(&self, idx: usize) -> bool {
+        (self.0 & (1 << idx)) != 0
+    }
+    fn count(&self) -> usize {
+        let mut raw = self.0;
+        std::iter::from_fn(move || {
+            if raw == 0 {
+                None
+            } else {
+                let next = raw.trailing_zeros() as usize;
+                raw &= !(1 << next);
+                Some(next)
+            }
+        })
+        .count()
+    }
+}
+
+fn bench_criteria_set_ops<S: BenchCriteriaSet>(label: &str, criteria: usize) {
+    const SETS: usize = 4096;
+    const ITERATIONS: u32 = 200;
+
+    let sets: Vec<S> = (0..SETS)
+        .map(|idx| {
+            let mut set = S::none(criteria);
+            set.set_criteria(idx % criteria);
+            set.set_criteria((idx * 7) % criteria);
+            set
+        })
+        .collect();
+
+    let start = std::time::Instant::now();
+    let mut hits = 0;
+    for _ in 0..ITERATIONS {
+        let mut acc = S::none(criteria);
+        for (idx, set) in sets.iter().enumerate() {
+            let mut set = set.clone();
+            set.unioned_with(&sets[(idx + 1) % SETS]);
+            hits += set.contains(&acc) as usize;
+            hits += set.has_criteria(idx % criteria) as usize;
+            hits += set.count();
+            acc.unioned_with(std::hint::black_box(&set));
+        }
+    }
+    std::hint::black_box(hits);
+    println!(
+        "{label} with {criteria} criteria: {:?} per {SETS} sets",
+        start.elapsed() / ITERATIONS
+    );
+}
+
+#[test]
+#[ignore]
+fn bench_criteria_set() {
+    for criteria in [2, 64] {
+        bench_criteria_set_ops::<U64CriteriaSet>("u64", criteria);
+        bench_criteria_set_ops::<crate::criteria::CriteriaSet>("CriteriaSet", criteria);
+    }
+    bench_criteria_set_ops::<crate::criteria::CriteriaSet>("CriteriaSet", 150);
+}
@@ -0,0 +1,135 @@
+use std::collections::VecDeque;
+
+use crate::{
+    diff_viewer::{DiffFile, DiffViewer},
+    format::Delta,
+};
+
+use super::*;
+
+fn mock_files() -> Vec<DiffFile> {
+    let old_lib = (1..=30).map(|i| format!("line {i}\n")).collect::<String>();
+    let new_lib = old_lib
+        .replace("line 5\n", "line five\n")
+        .replace("line 15\n", "")
+        .replace("line 25\n", "line 25\nline 25.5\n");
+    vec![
+        DiffFile::new(
+            "Cargo.lock".to_owned(),
+            Some("# This file is automatically @generated by Cargo.\nold\n".to_owned()),
+            Some("# This file is automatically @generated by Cargo.\nnew\n".to_owned()),
+            false,
+        ),
+        DiffFile::new("src/lib.rs".to_owned(), Some(old_lib), Some(new_lib), false),
+        DiffFile::new(
+            "src/new.rs".to_owned(),
+            None,
+            Some("fn main() {}\n".to_owned()),
+            false,
+        ),
+        DiffFile::new(
+            "src/old.rs".to_owned(),
+            Some("fn old() {}\n".to_owned()),
+            None,
+            false,
+        ),
+        DiffFile::new("logo.png".to_owned(), None, None, true),
+    ]
+}
+
+fn mock_delta() -> Delta {
+    Delta {
+        from: Some(ver(1)),
+        to: ver(2),
+    }
+}
+
+#[test]
+fn diff_viewer_print_all() {
+    // Without a terminal, every file's changes are written out in full.
+
+    let output = BasicTestOutput::new();
+    let viewer = DiffViewer::new(
+        &*output,
+        "third-party1",
+        mock_delta(),
+        mock_files(),
+        SortedSet::new(),
+        |_, _| unreachable!("nothing should be reviewed"),
+    );
+    viewer.print_all();
+
+    insta::assert_snapshot!(output.to_string());
+}
+
+#[test]
+fn diff_viewer_interactive() {
+    // Files can be selected, paged through, expanded and marked as reviewed
+    // with single keypresses, with each change to the reviewed files being
+    // persisted.
+
+    let keys = Mutex::new(VecDeque::from(
+        [
+            "enter", "r", "n", "pagedown", "b", "j", "p", "e", "esc", "down", "r", "r", "u", "?",
+            "q",
+        ]
+        .map(String::from),
+    ));
+    let output = BasicTestOutput::with_callbacks(
+        move |_| Ok(keys.lock().unwrap().pop_front().unwrap()),
+        |_| unreachable!("the viewer doesn't edit files"),
+    );
+
+    let persisted = Mutex::new(Vec::new());
+    let mut viewer = DiffViewer::new(
+        &*output,
+        "third-party1",
+        mock_delta(),
+        mock_files(),
+        ["src/new.rs".to_owned()].into_iter().collect(),
+        |path, reviewed| {
+            persisted.lock().unwrap().push((path.to_owned(), reviewed));
+            Ok(())
+        },
+    );
+    viewer.run().unwrap();
+    drop(viewer);
+
+    assert_eq!(
+        persisted.into_inner().unwrap(),
+        vec![
+            ("Cargo.lock".to_owned(), true),
+            ("src/lib.rs".to_owned(), true),
+            ("src/lib.rs".to_owned(), false),
+        ]
+    );
+    insta::assert_snapshot!(output.to_string());
+}
+
+#[test]
+fn diff_viewer_reviewed_files_cache() {
+    // Reviewed files are tracked per package and delta in the cache, and empty
+    // entries are removed.
+
+    let _enter = TEST_RUNTIME.enter();
+    let mock = MockMetadata::simple();
+    let metadata = mock.metadata();
+    let cfg = mock_cfg(&metadata);
+    let cache = crate::storage::Cache::acquire(&cfg).unwrap();
+
+    let delta = mock_delta();
+    cache.set_file_reviewed("third-party1", &delta, "src/lib.rs", true);
+    cache.set_file_reviewed("third-party1", &delta, "Cargo.toml", true);
+    assert_eq!(
+        cache
+            .get_reviewed_files("third-party1", &delta)
+            .into_iter()
+            .collect::<Vec<_>>(),
+        vec!["Cargo.toml", "src/lib.rs"]
+    );
+    assert!(cache.get_reviewed_files("third-party2", &delta).is_empty());
+
+    cache.set_file_reviewed("third-party1", &delta, "src/lib.rs", false);
+    cache.set_file_reviewed("third-party1", &delta, "Cargo.toml", false);
+    assert!(cache.get_reviewed_files("third-party1", &delta).is_empty());
+}
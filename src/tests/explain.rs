@@ -0,0 +1,198 @@
+use super::*;
+
+fn get_explain(metadata: &Metadata, store: &Store, package: &str) -> String {
+    console::set_colors_enabled(false);
+
+    let report = crate::resolver::resolve(metadata, None, store);
+    let explain = report.compute_explain(store, package, None).unwrap();
+
+    let human_output = BasicTestOutput::new();
+    crate::resolver::print_explain_human(&human_output.clone().as_dyn(), &explain).unwrap();
+    let json = serde_json::to_string_pretty(&explain).unwrap();
+    format!("{human_output}\n{json}")
+}
+
+#[test]
+fn explain_delta_path() {
+    // (Pass) The path runs from the root through each audit and exemption.
+
+    let _enter = TEST_RUNTIME.enter();
+    let mock = MockMetadata::simple();
+
+    let metadata = mock.metadata();
+    let (mut config, mut audits, mut imports) = builtin_files_full_audited(&metadata);
+
+    config.exemptions.insert(
+        "third-party1".to_owned(),
+        vec![exemptions(ver(3), SAFE_TO_DEPLOY)],
+    );
+    let mut audit = delta_audit(ver(3), ver(5), SAFE_TO_DEPLOY);
+    audit.who = vec!["Alice <alice@example.com>".to_owned().into()];
+    audit.notes = Some("Only docs changed".to_owned());
+    audits.audits.insert("third-party1".to_owned(), vec![audit]);
+    imports.audits.insert(
+        FOREIGN.to_owned(),
+        AuditsFile {
+            criteria: SortedMap::new(),
+            wildcard_audits: SortedMap::new(),
+            audits: [(
+                "third-party1".to_owned(),
+                vec![delta_audit(ver(5), ver(DEFAULT_VER), SAFE_TO_DEPLOY)],
+            )]
+            .into_iter()
+            .collect(),
+            trusted: SortedMap::new(),
+        },
+    );
+
+    let store = Store::mock(config, audits, imports);
+
+    insta::assert_snapshot!(get_explain(&metadata, &store, "third-party1"));
+}
+
+#[test]
+fn explain_unvetted() {
+    // (Fail) Failures show the versions reachable from either end.
+
+    let _enter = TEST_RUNTIME.enter();
+    let mock = MockMetadata::simple();
+
+    let metadata = mock.metadata();
+    let (config, mut audits, imports) = builtin_files_full_audited(&metadata);
+
+    audits.audits.insert(
+        "third-party1".to_owned(),
+        vec![
+            full_audit(ver(3), SAFE_TO_DEPLOY),
+            delta_audit(ver(5), ver(DEFAULT_VER), SAFE_TO_DEPLOY),
+        ],
+    );
+
+    let store = Store::mock(config, audits, imports);
+
+    insta::assert_snapshot!(get_explain(&metadata, &store, "third-party1"));
+}
+
+#[test]
+fn explain_first_party() {
+    // (Pass) First-party packages aren't vetted.
+
+    let _enter = TEST_RUNTIME.enter();
+    let mock = MockMetadata::simple();
+
+    let metadata = mock.metadata();
+    let (config, audits, imports) = builtin_files_full_audited(&metadata);
+
+    let store = Store::mock(config, audits, imports);
+
+    insta::assert_snapshot!(get_explain(&metadata, &store, "first-party"));
+}
+
+#[test]
+fn explain_disputed_violation() {
+    // (Pass) Disputed violations which apply to the version are shown.
+
+    let _enter = TEST_RUNTIME.enter();
+    let mock = MockMetadata::simple();
+
+    let metadata = mock.metadata();
+    let (mut config, audits, mut imports) = builtin_files_full_audited(&metadata);
+
+    let violation_ver = VersionReq::parse(&format!("={DEFAULT_VER}")).unwrap();
+    config.imports.insert(
+        FOREIGN.to_owned(),
+        crate::format::RemoteImport {
+            url: vec![FOREIGN_URL.to_owned()],
+            ..Default::default()
+        },
+    );
+    imports.audits.insert(
+        FOREIGN.to_owned(),
+        AuditsFile {
+            criteria: SortedMap::new(),
+            wildcard_audits: SortedMap::new(),
+            audits: [(
+                "third-party1".to_owned(),
+                vec![violation(violation_ver.clone(), SAFE_TO_DEPLOY)],
+            )]
+            .into_iter()
+            .collect(),
+            trusted: SortedMap::new(),
+        },
+    );
+    config.disputes.insert(
+        "third-party1".to_owned(),
+        vec![crate::format::ViolationDispute {
+            import: FOREIGN.to_owned().into(),
+            violation: violation_ver,
+            notes: "The reported issue only affects big-endian targets".to_owned(),
+        }],
+    );
+
+    let store = Store::mock(config, audits, imports);
+
+    insta::assert_snapshot!(get_explain(&metadata, &store, "third-party1"));
+}
+
+#[test]
+fn explain_capped_import() {
+    // (Fail) An imported audit only grants the criteria allowed by the import's
+    // `max-criteria`.
+
+    let _enter = TEST_RUNTIME.enter();
+    let mock = MockMetadata::simple();
+
+    let metadata = mock.metadata();
+    let (mut config, mut audits, mut imports) = builtin_files_full_audited(&metadata);
+
+    audits.audits.remove("third-party1");
+    config.imports.insert(
+        FOREIGN.to_owned(),
+        crate::format::RemoteImport {
+            url: vec![FOREIGN_URL.to_owned()],
+            max_criteria: vec![SAFE_TO_RUN.to_owned().into()],
+            ..Default::default()
+        },
+    );
+    imports.audits.insert(
+        FOREIGN.to_owned(),
+        AuditsFile {
+            criteria: SortedMap::new(),
+            wildcard_audits: SortedMap::new(),
+            audits: [(
+                "third-party1".to_owned(),
+                vec![full_audit(ver(DEFAULT_VER), SAFE_TO_DEPLOY)],
+            )]
+            .into_iter()
+            .collect(),
+            trusted: SortedMap::new(),
+        },
+    );
+
+    let store = Store::mock(config, audits, imports);
+
+    insta::assert_snapshot!(get_explain(&metadata, &store, "third-party1"));
+}
+
+#[test]
+fn explain_missing_origin() {
+    // (Error) Explaining a report against a store which lacks the audits it
+    // was resolved with is an error rather than a panic.
+
+    let _enter = TEST_RUNTIME.enter();
+    let mock = MockMetadata::simple();
+
+    let metadata = mock.metadata();
+    let (config, audits, imports) = builtin_files_full_audited(&metadata);
+    let store = Store::mock(config, audits, imports);
+    let report = crate::resolver::resolve(&metadata, None, &store);
+
+    let (config, mut audits, imports) = builtin_files_full_audited(&metadata);
+    audits.audits.remove("third-party1");
+    let other_store = Store::mock(config, audits, imports);
+
+    let error = report
+        .compute_explain(&other_store, "third-party1", None)
+        .unwrap_err();
+    assert_eq!(error.package, "third-party1");
+}
@@ -0,0 +1,352 @@
+use crate::format::{FeatureConfig, FeatureConfigName};
+
+use super::*;
+
+fn release_metadata() -> MockMetadata {
+    //                                    Graph
+    // =======================================================================================
+    //
+    //                         root-package           tool
+    //                              |                   |
+    //                        third-party1          tool-dep
+    //
+    MockMetadata::new(vec![
+        MockPackage {
+            name: "root-package",
+            is_workspace: true,
+            is_first_party: true,
+            deps: vec![dep("third-party1")],
+            ..Default::default()
+        },
+        MockPackage {
+            name: "tool",
+            is_workspace: true,
+            is_first_party: true,
+            deps: vec![dep("tool-dep")],
+            ..Default::default()
+        },
+        MockPackage {
+            name: "third-party1",
+            ..Default::default()
+        },
+        MockPackage {
+            name: "tool-dep",
+            ..Default::default()
+        },
+    ])
+}
+
+fn test_metadata() -> MockMetadata {
+    // The same workspace with an optional feature enabled:
+    //
+    //                         root-package           tool
+    //                         /          \             |
+    //               third-party1     test-only     tool-dep
+    //
+    MockMetadata::new(vec![
+        MockPackage {
+            name: "root-package",
+            is_workspace: true,
+            is_first_party: true,
+            deps: vec![dep("third-party1"), dep("test-only")],
+            ..Default::default()
+        },
+        MockPackage {
+            name: "tool",
+            is_workspace: true,
+            is_first_party: true,
+            deps: vec![dep("tool-dep")],
+            ..Default::default()
+        },
+        MockPackage {
+            name: "third-party1",
+            ..Default::default()
+        },
+        MockPackage {
+            name: "test-only",
+            ..Default::default()
+        },
+        MockPackage {
+            name: "tool-dep",
+            ..Default::default()
+        },
+    ])
+}
+
+fn feature_metadata() -> SortedMap<FeatureConfigName, Metadata> {
+    [
+        ("release".to_owned(), release_metadata().metadata()),
+        ("test".to_owned(), test_metadata().metadata()),
+    ]
+    .into_iter()
+    .collect()
+}
+
+fn union_metadata(feature_metadata: &SortedMap<FeatureConfigName, Metadata>) -> Metadata {
+    let mut metadata = feature_metadata["release"].clone();
+    crate::merge_metadata(&mut metadata, feature_metadata["test"].clone());
+    metadata
+}
+
+fn feature_config(
+    members: &[&str],
+    criteria: impl IntoIterator<Item = impl Into<CriteriaName>>,
+) -> FeatureConfig {
+    FeatureConfig {
+        members: members.iter().map(|m| m.to_string()).collect(),
+        features: vec![],
+        all_features: false,
+        default_features: true,
+        criteria: Some(criteria.into_iter().map(|c| c.into().into()).collect()),
+        notes: None,
+    }
+}
+
+fn set_audit(audits: &mut AuditsFile, name: &str, criteria: CriteriaStr) {
+    audits.audits.insert(
+        name.to_owned(),
+        vec![full_audit(ver(DEFAULT_VER), criteria)],
+    );
+}
+
+fn get_feature_reports(
+    metadata: &Metadata,
+    feature_metadata: &SortedMap<FeatureConfigName, Metadata>,
+    store: &Store,
+) -> String {
+    let report = crate::resolver::resolve_with_baseline(
+        metadata,
+        feature_metadata,
+        &SortedMap::new(),
+        None,
+        store,
+        None,
+    );
+    let (human, json) = get_reports(metadata, report, store, None);
+    format!("{human}\n{json}")
+}
+
+#[test]
+fn feature_configs_per_config_criteria() {
+    // (Pass) A dependency only enabled in the test configuration only needs
+    // that configuration's criteria.
+
+    let _enter = TEST_RUNTIME.enter();
+    let feature_metadata = feature_metadata();
+    let metadata = union_metadata(&feature_metadata);
+    let (mut config, mut audits, imports) = builtin_files_full_audited(&metadata);
+
+    config
+        .feature_configs
+        .insert("release".to_owned(), feature_config(&[], [SAFE_TO_DEPLOY]));
+    config
+        .feature_configs
+        .insert("test".to_owned(), feature_config(&[], [SAFE_TO_RUN]));
+    set_audit(&mut audits, "test-only", SAFE_TO_RUN);
+
+    let store = Store::mock(config, audits, imports);
+
+    insta::assert_snapshot!(get_feature_reports(&metadata, &feature_metadata, &store));
+}
+
+#[test]
+fn feature_configs_strongest_criteria() {
+    // (Fail) A dependency in every configuration needs the strongest criteria
+    // of any of them.
+
+    let _enter = TEST_RUNTIME.enter();
+    let feature_metadata = feature_metadata();
+    let metadata = union_metadata(&feature_metadata);
+    let (mut config, mut audits, imports) = builtin_files_full_audited(&metadata);
+
+    config
+        .feature_configs
+        .insert("release".to_owned(), feature_config(&[], [SAFE_TO_DEPLOY]));
+    config
+        .feature_configs
+        .insert("test".to_owned(), feature_config(&[], [SAFE_TO_RUN]));
+    set_audit(&mut audits, "test-only", SAFE_TO_RUN);
+    set_audit(&mut audits, "third-party1", SAFE_TO_RUN);
+
+    let store = Store::mock(config, audits, imports);
+
+    insta::assert_snapshot!(get_feature_reports(&metadata, &feature_metadata, &store));
+}
+
+#[test]
+fn feature_configs_members() {
+    // (Pass) Workspace members which aren't built in a configuration don't
+    // need its criteria.
+
+    let _enter = TEST_RUNTIME.enter();
+    let feature_metadata = feature_metadata();
+    let metadata = union_metadata(&feature_metadata);
+    let (mut config, mut audits, imports) = builtin_files_full_audited(&metadata);
+
+    config.feature_configs.insert(
+        "release".to_owned(),
+        feature_config(&["root-package"], [SAFE_TO_DEPLOY]),
+    );
+    config
+        .feature_configs
+        .insert("test".to_owned(), feature_config(&[], [SAFE_TO_RUN]));
+    set_audit(&mut audits, "test-only", SAFE_TO_RUN);
+    set_audit(&mut audits, "tool-dep", SAFE_TO_RUN);
+
+    let store = Store::mock(config, audits, imports);
+
+    insta::assert_snapshot!(get_feature_reports(&metadata, &feature_metadata, &store));
+}
+
+#[test]
+fn feature_configs_excluded_member_policy() {
+    // (Pass) The policy criteria of a workspace member which isn't built in any
+    // configuration don't apply to its dependencies.
+
+    let _enter = TEST_RUNTIME.enter();
+    let feature_metadata = feature_metadata();
+    let metadata = union_metadata(&feature_metadata);
+    let (mut config, mut audits, imports) = builtin_files_full_audited(&metadata);
+
+    config.feature_configs.insert(
+        "release".to_owned(),
+        feature_config(&["root-package"], [SAFE_TO_DEPLOY]),
+    );
+    config.feature_configs.insert(
+        "test".to_owned(),
+        feature_config(&["root-package"], [SAFE_TO_RUN]),
+    );
+    config
+        .policy
+        .insert("tool".to_owned(), self_policy([SAFE_TO_DEPLOY]));
+    set_audit(&mut audits, "test-only", SAFE_TO_RUN);
+    set_audit(&mut audits, "tool-dep", SAFE_TO_RUN);
+
+    let store = Store::mock(config, audits, imports);
+
+    insta::assert_snapshot!(get_feature_reports(&metadata, &feature_metadata, &store));
+}
+
+#[test]
+fn feature_configs_excluded_member_dependency_criteria() {
+    // (Pass) The dependency-criteria of a workspace member which isn't built
+    // in any configuration don't apply.
+
+    let _enter = TEST_RUNTIME.enter();
+    let feature_metadata = feature_metadata();
+    let metadata = union_metadata(&feature_metadata);
+    let (mut config, mut audits, imports) = builtin_files_full_audited(&metadata);
+
+    config.feature_configs.insert(
+        "release".to_owned(),
+        feature_config(&["root-package"], [SAFE_TO_DEPLOY]),
+    );
+    config.feature_configs.insert(
+        "test".to_owned(),
+        feature_config(&["root-package"], [SAFE_TO_RUN]),
+    );
+    config.policy.insert(
+        "tool".to_owned(),
+        dep_policy([("tool-dep", [SAFE_TO_DEPLOY])]),
+    );
+    set_audit(&mut audits, "test-only", SAFE_TO_RUN);
+    set_audit(&mut audits, "tool-dep", SAFE_TO_RUN);
+
+    let store = Store::mock(config, audits, imports);
+
+    insta::assert_snapshot!(get_feature_reports(&metadata, &feature_metadata, &store));
+}
+
+#[test]
+fn feature_configs_regenerate_exemptions() {
+    // (Pass) Regenerated exemptions only cover the criteria required by the
+    // configurations each package appears in.
+
+    let _enter = TEST_RUNTIME.enter();
+    let feature_metadata = feature_metadata();
+    let metadata = union_metadata(&feature_metadata);
+    let (mut config, audits, imports) = builtin_files_inited(&metadata);
+
+    config
+        .feature_configs
+        .insert("release".to_owned(), feature_config(&[], [SAFE_TO_DEPLOY]));
+    config
+        .feature_configs
+        .insert("test".to_owned(), feature_config(&[], [SAFE_TO_RUN]));
+
+    let mut store = Store::mock(config, audits, imports);
+    let mut cfg = mock_cfg(&metadata);
+    cfg.feature_metadata = feature_metadata;
+    crate::resolver::update_store(&cfg, &mut store, |_| crate::resolver::UpdateMode {
+        search_mode: crate::resolver::SearchMode::RegenerateExemptions,
+        prune_exemptions: true,
+        prune_non_importable_audits: true,
+        prune_imports: true,
+    });
+
+    insta::assert_snapshot!(toml_edit::ser::to_string_pretty(&store.config.exemptions).unwrap());
+}
+
+#[test]
+fn feature_configs_why() {
+    // `why` traces requirements through each configuration's graph, with its
+    // criteria, and doesn't blame members which aren't built in it.
+
+    let _enter = TEST_RUNTIME.enter();
+    console::set_colors_enabled(false);
+    let feature_metadata = feature_metadata();
+    let metadata = union_metadata(&feature_metadata);
+    let (mut config, audits, imports) = builtin_files_full_audited(&metadata);
+
+    config.feature_configs.insert(
+        "release".to_owned(),
+        feature_config(&["root-package"], [SAFE_TO_DEPLOY]),
+    );
+    config
+        .feature_configs
+        .insert("test".to_owned(), feature_config(&[], [SAFE_TO_RUN]));
+
+    let store = Store::mock(config, audits, imports);
+
+    let report = crate::resolver::resolve_with_baseline(
+        &metadata,
+        &feature_metadata,
+        &SortedMap::new(),
+        None,
+        &store,
+        None,
+    );
+    let output = BasicTestOutput::new();
+    for package in ["third-party1", "test-only", "tool-dep"] {
+        let why = report.compute_why(package, None);
+        crate::resolver::print_why_human(&output.clone().as_dyn(), &why).unwrap();
+    }
+
+    insta::assert_snapshot!(output.to_string());
+}
+
+#[test]
+fn feature_configs_unknown_members() {
+    // A misspelled member is an error, rather than excluding every real
+    // member from the configuration.
+
+    let feature_metadata = feature_metadata();
+    let metadata = union_metadata(&feature_metadata);
+    let (mut config, _, _) = builtin_files_full_audited(&metadata);
+
+    config.feature_configs.insert(
+        "release".to_owned(),
+        feature_config(&["root-package", "tool"], [SAFE_TO_DEPLOY]),
+    );
+    crate::check_feature_config_members(&config, &metadata).unwrap();
+
+    config.feature_configs.insert(
+        "test".to_owned(),
+        feature_config(&["root-pakage", "tool", "tol"], [SAFE_TO_RUN]),
+    );
+    let error = crate::check_feature_config_members(&config, &metadata).unwrap_err();
+    assert_eq!(
+        error.to_string(),
+        "feature configuration 'test' lists packages which aren't workspace members: root-pakage, tol"
+    );
+}
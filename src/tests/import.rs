@@ -2066,3 +2066,676 @@ fn local_wildcard_audit_preferred_to_fresh_import() {
     let output = get_imports_file_changes_noprune(&metadata, &store);
     insta::assert_snapshot!(output);
 }
+
+// A foreign audits.toml, along with a minisign signature of it made with the
+// secret key for `SIGNED_PUBLIC_KEY`.
+const SIGNED_AUDITS_URL: &str = "https://peercompany.co.uk/audits.toml";
+const SIGNED_AUDITS: &str = r#"[[audits.third-party2]]
+who = "Peer Auditor <peer@peercompany.co.uk>"
+criteria = "safe-to-deploy"
+version = "10.0.0"
+"#;
+const SIGNED_PUBLIC_KEY: &str = "RWQBAgMEBQYHCAOhB7/zzhC+HXDdGOdLwJln5NYwm6UNXx3chmQSVTG4";
+const OTHER_PUBLIC_KEY: &str = "RWQIBwYFBAMCASmsuuFBvMrwsi4alNNNC8c2HlJtC/4SyJeUvJMilm3X";
+const SIGNATURE: &str = "untrusted comment: signature from minisign secret key
+RUQBAgMEBQYHCAlUyE6Floc+RnQ/cCchQyAyW+nx1dfJgz815OJC/jDC66P8FkzDISfDejvdpv+XZUy0I74g9F3ZSus7QHhotw4=
+trusted comment: timestamp:1672531200\tfile:audits.toml
+8c80JXjiu1cI8rlwOi0Lrc/O+tpt075R7084UUlCgbhHvbFckLcExzG4uVwhf+pxrIloJZlz6Ad3f25VhnSWCw==
+";
+
+/// Helper for signature tests. Sets up an import of `SIGNED_AUDITS` pinned to
+/// `public_keys`, serving `audits` and `signature` from the mock network.
+fn signed_import_store(
+    metadata: &Metadata,
+    public_keys: &[&str],
+    audits: &str,
+    signature: Option<&str>,
+) -> Result<Store, crate::errors::StoreAcquireError> {
+    let (mut config, mut local_audits, imports) = builtin_files_full_audited(metadata);
+
+    local_audits.audits.remove("third-party2");
+
+    config.imports.insert(
+        FOREIGN.to_owned(),
+        crate::format::RemoteImport {
+            url: vec![SIGNED_AUDITS_URL.to_owned()],
+            public_key: public_keys.iter().map(|&key| key.to_owned()).collect(),
+            ..Default::default()
+        },
+    );
+
+    let cfg = mock_cfg(metadata);
+
+    let mut network = Network::new_mock();
+    network.mock_serve(SIGNED_AUDITS_URL, audits);
+    if let Some(signature) = signature {
+        network.mock_serve(format!("{SIGNED_AUDITS_URL}.minisig"), signature);
+    }
+
+    Store::mock_online(&cfg, config, local_audits, imports, &network, false)
+}
+
+#[test]
+fn signed_peer_import() {
+    // (Pass) Audits signed with a pinned key are imported.
+
+    let _enter = TEST_RUNTIME.enter();
+    let mock = MockMetadata::simple();
+
+    let metadata = mock.metadata();
+    let store = signed_import_store(
+        &metadata,
+        &[OTHER_PUBLIC_KEY, SIGNED_PUBLIC_KEY],
+        SIGNED_AUDITS,
+        Some(SIGNATURE),
+    )
+    .unwrap();
+
+    let output = get_imports_file_changes_prune(&metadata, &store);
+    insta::assert_snapshot!(output);
+}
+
+#[test]
+fn signed_peer_import_missing_signature() {
+    // (Fail) A pinned import without a signature is rejected.
+
+    let _enter = TEST_RUNTIME.enter();
+    let mock = MockMetadata::simple();
+
+    let metadata = mock.metadata();
+    let error = match signed_import_store(&metadata, &[SIGNED_PUBLIC_KEY], SIGNED_AUDITS, None) {
+        Ok(_) => panic!("expected store creation to fail due to missing signature"),
+        Err(err) => miette::Report::from(err),
+    };
+    insta::assert_snapshot!(format!("{error:?}"));
+}
+
+#[test]
+fn signed_peer_import_untrusted_key() {
+    // (Fail) A signature made with a key which isn't pinned is rejected.
+
+    let _enter = TEST_RUNTIME.enter();
+    let mock = MockMetadata::simple();
+
+    let metadata = mock.metadata();
+    let error = match signed_import_store(
+        &metadata,
+        &[OTHER_PUBLIC_KEY],
+        SIGNED_AUDITS,
+        Some(SIGNATURE),
+    ) {
+        Ok(_) => panic!("expected store creation to fail due to untrusted signature"),
+        Err(err) => miette::Report::from(err),
+    };
+    insta::assert_snapshot!(format!("{error:?}"));
+}
+
+#[test]
+fn signed_peer_import_modified() {
+    // (Fail) Audits which were modified after being signed are rejected.
+
+    let _enter = TEST_RUNTIME.enter();
+    let mock = MockMetadata::simple();
+
+    let metadata = mock.metadata();
+    let modified = SIGNED_AUDITS.replace("10.0.0", "11.0.0");
+    let error =
+        match signed_import_store(&metadata, &[SIGNED_PUBLIC_KEY], &modified, Some(SIGNATURE)) {
+            Ok(_) => panic!("expected store creation to fail due to invalid signature"),
+            Err(err) => miette::Report::from(err),
+        };
+    insta::assert_snapshot!(format!("{error:?}"));
+}
+
+// The sha256 digest of `SIGNED_AUDITS`.
+const SIGNED_AUDITS_SHA256: &str =
+    "6db21dab54c27dd135fa8f0c267623d712c67764035c1b4dd5c2fc3df40fe17c";
+
+/// Helper for pinning tests. Sets up an import of `SIGNED_AUDITS` pinned to
+/// `SIGNED_AUDITS_SHA256`, serving `audits` from the mock network. If `locked`
+/// is set, the audit from `SIGNED_AUDITS` is already in imports.lock.
+fn pinned_import_store(
+    metadata: &Metadata,
+    audits: &str,
+    locked: bool,
+) -> Result<Store, crate::errors::StoreAcquireError> {
+    let (mut config, mut local_audits, mut imports) = builtin_files_full_audited(metadata);
+
+    local_audits.audits.remove("third-party2");
+
+    if locked {
+        imports.audits.insert(
+            FOREIGN.to_owned(),
+            AuditsFile {
+                criteria: SortedMap::new(),
+                wildcard_audits: SortedMap::new(),
+                audits: [(
+                    "third-party2".to_owned(),
+                    vec![full_audit(ver(DEFAULT_VER), SAFE_TO_DEPLOY)],
+                )]
+                .into_iter()
+                .collect(),
+                trusted: SortedMap::new(),
+            },
+        );
+    }
+
+    config.imports.insert(
+        FOREIGN.to_owned(),
+        crate::format::RemoteImport {
+            url: vec![SIGNED_AUDITS_URL.to_owned()],
+            sha256: vec![SIGNED_AUDITS_SHA256.to_owned()],
+            ..Default::default()
+        },
+    );
+
+    let cfg = mock_cfg(metadata);
+
+    let mut network = Network::new_mock();
+    network.mock_serve(SIGNED_AUDITS_URL, audits);
+
+    Store::mock_online(&cfg, config, local_audits, imports, &network, false)
+}
+
+#[test]
+fn pinned_peer_import() {
+    // (Pass) Audits matching the pinned sha256 are imported.
+
+    let _enter = TEST_RUNTIME.enter();
+    let mock = MockMetadata::simple();
+
+    let metadata = mock.metadata();
+    let store = pinned_import_store(&metadata, SIGNED_AUDITS, false).unwrap();
+
+    let output = get_imports_file_changes_prune(&metadata, &store);
+    insta::assert_snapshot!(output);
+}
+
+#[test]
+fn pinned_peer_import_changed() {
+    // (Pass) When a pinned import changes upstream, the audits in imports.lock
+    // continue to be used, even though the audit was removed upstream.
+
+    let _enter = TEST_RUNTIME.enter();
+    let mock = MockMetadata::simple();
+
+    let metadata = mock.metadata();
+    let modified = SIGNED_AUDITS.replace("10.0.0", "11.0.0");
+    let store = pinned_import_store(&metadata, &modified, true).unwrap();
+
+    let output = get_imports_file_changes_prune(&metadata, &store);
+    insta::assert_snapshot!(output);
+}
+
+#[test]
+fn pinned_peer_import_changed_unlocked() {
+    // (Fail) A pinned import which doesn't match its pin and has nothing in
+    // imports.lock to fall back to is rejected.
+
+    let _enter = TEST_RUNTIME.enter();
+    let mock = MockMetadata::simple();
+
+    let metadata = mock.metadata();
+    let modified = SIGNED_AUDITS.replace("10.0.0", "11.0.0");
+    let error = match pinned_import_store(&metadata, &modified, false) {
+        Ok(_) => panic!("expected store creation to fail due to pin mismatch"),
+        Err(err) => miette::Report::from(err),
+    };
+    insta::assert_snapshot!(format!("{error:?}"));
+}
+
+/// Helper for local import tests. Sets up an unpinned import from `url`.
+fn local_import_store(cfg: &Config, url: &str) -> Store {
+    let (mut config, mut local_audits, imports) = builtin_files_full_audited(&cfg.metadata);
+
+    local_audits.audits.remove("third-party2");
+
+    config.imports.insert(
+        FOREIGN.to_owned(),
+        crate::format::RemoteImport {
+            url: vec![url.to_owned()],
+            ..Default::default()
+        },
+    );
+
+    Store::mock_online(
+        cfg,
+        config,
+        local_audits,
+        imports,
+        &Network::new_mock(),
+        false,
+    )
+    .unwrap()
+}
+
+/// Run git with the given arguments in `dir`, returning its trimmed output.
+fn git(dir: &std::path::Path, args: &[&str]) -> String {
+    let output = std::process::Command::new("git")
+        .arg("-C")
+        .arg(dir)
+        .args(["-c", "user.name=Test", "-c", "user.email=test@example.com"])
+        .args(args)
+        .output()
+        .unwrap();
+    assert!(output.status.success(), "git {args:?} failed");
+    String::from_utf8(output.stdout).unwrap().trim().to_owned()
+}
+
+#[test]
+fn file_peer_import() {
+    // (Pass) Audits can be imported from a local file.
+
+    let _enter = TEST_RUNTIME.enter();
+    let mock = MockMetadata::simple();
+
+    let metadata = mock.metadata();
+    let dir = tempfile::tempdir().unwrap();
+    let audits_path = dir.path().join("audits.toml");
+    fs::write(&audits_path, SIGNED_AUDITS).unwrap();
+    let url = reqwest::Url::from_file_path(&audits_path).unwrap();
+
+    let store = local_import_store(&mock_cfg(&metadata), url.as_str());
+
+    let output = get_imports_file_changes_prune(&metadata, &store);
+    insta::assert_snapshot!(output);
+}
+
+#[test]
+fn git_peer_import() {
+    // (Pass) Audits can be imported from a file in a git repository, which is
+    // cloned into the cache and used from there once a commit is fetched.
+
+    let _enter = TEST_RUNTIME.enter();
+    let mock = MockMetadata::simple();
+
+    let metadata = mock.metadata();
+    let repo = tempfile::tempdir().unwrap();
+    fs::create_dir(repo.path().join("audits")).unwrap();
+    fs::write(repo.path().join("audits/peer.toml"), SIGNED_AUDITS).unwrap();
+    git(repo.path(), &["init", "--quiet"]);
+    git(repo.path(), &["add", "."]);
+    git(repo.path(), &["commit", "--quiet", "-m", "Add audits"]);
+    let rev = git(repo.path(), &["rev-parse", "HEAD"]);
+    let url = format!(
+        "git+{}#path=audits/peer.toml&rev={rev}",
+        reqwest::Url::from_directory_path(repo.path()).unwrap()
+    );
+
+    let cache_dir = tempfile::tempdir().unwrap();
+    let mut cfg = mock_cfg(&metadata);
+    cfg._rest.mock_cache = false;
+    cfg._rest.cache_dir = cache_dir.path().to_owned();
+
+    let store = local_import_store(&cfg, &url);
+    let output = get_imports_file_changes_prune(&metadata, &store);
+    insta::assert_snapshot!("git_peer_import", output);
+
+    // The commit is now in the cache, so the repository isn't needed anymore.
+    drop(store);
+    repo.close().unwrap();
+
+    let store = local_import_store(&cfg, &url);
+    let output = get_imports_file_changes_prune(&metadata, &store);
+    insta::assert_snapshot!("git_peer_import", output);
+}
+
+#[test]
+fn git_peer_import_rev_pin() {
+    // (Pass) A git import pinned to a rev is fetched from that commit, even
+    // once its URL refers to a newer one.
+
+    let _enter = TEST_RUNTIME.enter();
+    let mock = MockMetadata::simple();
+
+    let metadata = mock.metadata();
+    let repo = tempfile::tempdir().unwrap();
+    fs::create_dir(repo.path().join("audits")).unwrap();
+    fs::write(repo.path().join("audits/peer.toml"), SIGNED_AUDITS).unwrap();
+    git(repo.path(), &["init", "--quiet"]);
+    git(repo.path(), &["add", "."]);
+    git(repo.path(), &["commit", "--quiet", "-m", "Add audits"]);
+    let pinned_rev = git(repo.path(), &["rev-parse", "HEAD"]);
+    fs::write(
+        repo.path().join("audits/peer.toml"),
+        SIGNED_AUDITS.replace("10.0.0", "11.0.0"),
+    )
+    .unwrap();
+    git(repo.path(), &["commit", "--quiet", "-am", "Update audits"]);
+    let head_rev = git(repo.path(), &["rev-parse", "HEAD"]);
+    let url = format!(
+        "git+{}#path=audits/peer.toml",
+        reqwest::Url::from_directory_path(repo.path()).unwrap()
+    );
+
+    let cache_dir = tempfile::tempdir().unwrap();
+    let mut cfg = mock_cfg(&metadata);
+    cfg._rest.mock_cache = false;
+    cfg._rest.cache_dir = cache_dir.path().to_owned();
+
+    // `cargo vet import --update` would move the pin to the current commit.
+    let cache = crate::storage::Cache::acquire(&cfg).unwrap();
+    let revs = tokio::runtime::Handle::current()
+        .block_on(crate::storage::fetch_import_revs(
+            &cache,
+            FOREIGN,
+            std::slice::from_ref(&url),
+        ))
+        .unwrap();
+    assert_eq!(revs, vec![head_rev]);
+    drop(cache);
+
+    let (mut config, mut local_audits, imports) = builtin_files_full_audited(&cfg.metadata);
+    local_audits.audits.remove("third-party2");
+    config.imports.insert(
+        FOREIGN.to_owned(),
+        crate::format::RemoteImport {
+            url: vec![url],
+            rev: vec![pinned_rev],
+            ..Default::default()
+        },
+    );
+
+    let store = Store::mock_online(
+        &cfg,
+        config,
+        local_audits,
+        imports,
+        &Network::new_mock(),
+        false,
+    )
+    .unwrap();
+    let output = get_imports_file_changes_prune(&metadata, &store);
+    insta::assert_snapshot!("git_peer_import", output);
+}
+
+#[test]
+fn rev_pinned_url_import() {
+    // (Fail) Only git imports can be pinned to a rev.
+
+    let _enter = TEST_RUNTIME.enter();
+    let mock = MockMetadata::simple();
+
+    let metadata = mock.metadata();
+    let (mut config, mut local_audits, imports) = builtin_files_full_audited(&metadata);
+    local_audits.audits.remove("third-party2");
+    config.imports.insert(
+        FOREIGN.to_owned(),
+        crate::format::RemoteImport {
+            url: vec![SIGNED_AUDITS_URL.to_owned()],
+            rev: vec!["0f1e2d3c4b5a69788796a5b4c3d2e1f00f1e2d3c".to_owned()],
+            ..Default::default()
+        },
+    );
+
+    let cfg = mock_cfg(&metadata);
+    let mut network = Network::new_mock();
+    network.mock_serve(SIGNED_AUDITS_URL, SIGNED_AUDITS);
+
+    let error = match Store::mock_online(&cfg, config, local_audits, imports, &network, false) {
+        Ok(_) => panic!("expected store creation to fail due to the rev pin"),
+        Err(err) => miette::Report::from(err),
+    };
+    insta::assert_snapshot!(format!("{error:?}"));
+}
+
+#[test]
+fn git_peer_import_option_rev() {
+    // (Fail) A git import's rev can't be used to pass options to git.
+
+    let _enter = TEST_RUNTIME.enter();
+    let mock = MockMetadata::simple();
+
+    let metadata = mock.metadata();
+    let repo = tempfile::tempdir().unwrap();
+    fs::create_dir(repo.path().join("audits")).unwrap();
+    fs::write(repo.path().join("audits/peer.toml"), SIGNED_AUDITS).unwrap();
+    git(repo.path(), &["init", "--quiet"]);
+    git(repo.path(), &["add", "."]);
+    git(repo.path(), &["commit", "--quiet", "-m", "Add audits"]);
+    let marker = repo.path().join("marker");
+    let url = format!(
+        "git+{}#path=audits/peer.toml&rev=--upload-pack=touch%20{}",
+        reqwest::Url::from_directory_path(repo.path()).unwrap(),
+        marker.display()
+    );
+
+    let cache_dir = tempfile::tempdir().unwrap();
+    let mut cfg = mock_cfg(&metadata);
+    cfg._rest.mock_cache = false;
+    cfg._rest.cache_dir = cache_dir.path().to_owned();
+
+    let (mut config, mut local_audits, imports) = builtin_files_full_audited(&cfg.metadata);
+    local_audits.audits.remove("third-party2");
+    config.imports.insert(
+        FOREIGN.to_owned(),
+        crate::format::RemoteImport {
+            url: vec![url],
+            ..Default::default()
+        },
+    );
+
+    let error = match Store::mock_online(
+        &cfg,
+        config,
+        local_audits,
+        imports,
+        &Network::new_mock(),
+        false,
+    ) {
+        Ok(_) => panic!("expected store creation to fail due to the invalid rev"),
+        Err(err) => format!("{:?}", miette::Report::from(err)),
+    };
+    assert!(
+        error.contains("invalid revision '--upload-pack=touch"),
+        "unexpected error: {error}"
+    );
+    assert!(!marker.exists(), "git ran the rev as an option");
+}
+
+/// Helper for fetch failure tests. Sets up a locked import from a URL which
+/// the mock network doesn't serve.
+fn unreachable_import_store(
+    metadata: &Metadata,
+    on_fetch_failure: crate::format::FetchFailurePolicy,
+) -> Result<Store, crate::errors::StoreAcquireError> {
+    let (mut config, mut local_audits, mut imports) = builtin_files_full_audited(metadata);
+
+    local_audits.audits.remove("third-party2");
+
+    imports.audits.insert(
+        FOREIGN.to_owned(),
+        AuditsFile {
+            criteria: SortedMap::new(),
+            wildcard_audits: SortedMap::new(),
+            audits: [(
+                "third-party2".to_owned(),
+                vec![full_audit(ver(DEFAULT_VER), SAFE_TO_DEPLOY)],
+            )]
+            .into_iter()
+            .collect(),
+            trusted: SortedMap::new(),
+        },
+    );
+
+    config.on_fetch_failure = on_fetch_failure;
+    config.imports.insert(
+        FOREIGN.to_owned(),
+        crate::format::RemoteImport {
+            url: vec![FOREIGN_URL.to_owned()],
+            ..Default::default()
+        },
+    );
+
+    let cfg = mock_cfg(metadata);
+
+    Store::mock_online(
+        &cfg,
+        config,
+        local_audits,
+        imports,
+        &Network::new_mock(),
+        false,
+    )
+}
+
+#[test]
+fn unreachable_peer_import() {
+    // (Fail) By default, an import which can't be fetched is an error, even if
+    // it has audits in imports.lock.
+
+    let _enter = TEST_RUNTIME.enter();
+    let mock = MockMetadata::simple();
+
+    let metadata = mock.metadata();
+    let error = match unreachable_import_store(&metadata, Default::default()) {
+        Ok(_) => panic!("expected store creation to fail due to fetch failure"),
+        Err(err) => miette::Report::from(err),
+    };
+    insta::assert_snapshot!(format!("{error:?}"));
+}
+
+#[test]
+fn unreachable_peer_import_use_lock() {
+    // (Pass) With `on-fetch-failure = "use-lock"`, an import which can't be
+    // fetched falls back to the audits in imports.lock.
+
+    let _enter = TEST_RUNTIME.enter();
+    let mock = MockMetadata::simple();
+
+    let metadata = mock.metadata();
+    let store =
+        unreachable_import_store(&metadata, crate::format::FetchFailurePolicy::UseLock).unwrap();
+
+    let output = get_imports_file_changes_prune(&metadata, &store);
+    insta::assert_snapshot!(output);
+}
+
+#[test]
+fn peer_import_limits() {
+    // (Pass) Imports can be limited to audits by certain auditors, and can
+    // leave out wildcard audits and trusted entries.
+
+    let _enter = TEST_RUNTIME.enter();
+    let mock = MockMetadata::simple();
+
+    let metadata = mock.metadata();
+    let (mut config, audits, imports) = builtin_files_no_exemptions(&metadata);
+
+    let audit_by = |who: &str, package_version| {
+        let mut audit = full_audit(package_version, SAFE_TO_DEPLOY);
+        audit.who = vec![who.to_owned().into()];
+        audit
+    };
+    let new_foreign_audits = AuditsFile {
+        criteria: SortedMap::new(),
+        wildcard_audits: [(
+            "third-party2".to_owned(),
+            vec![wildcard_audit(1, SAFE_TO_DEPLOY)],
+        )]
+        .into_iter()
+        .collect(),
+        audits: [
+            (
+                "third-party1".to_owned(),
+                vec![audit_by("Bob <bob@example.com>", ver(DEFAULT_VER))],
+            ),
+            (
+                "third-party2".to_owned(),
+                vec![audit_by("Alice <alice@example.com>", ver(DEFAULT_VER))],
+            ),
+            (
+                "transitive-third-party1".to_owned(),
+                vec![audit_by("Carol", ver(DEFAULT_VER))],
+            ),
+        ]
+        .into_iter()
+        .collect(),
+        trusted: [(
+            "third-party1".to_owned(),
+            vec![trusted_entry(1, SAFE_TO_DEPLOY)],
+        )]
+        .into_iter()
+        .collect(),
+    };
+
+    config.imports.insert(
+        FOREIGN.to_owned(),
+        crate::format::RemoteImport {
+            url: vec![FOREIGN_URL.to_owned()],
+            auditors: vec!["alice@example.com".to_owned(), "Carol".to_owned()],
+            exclude_wildcard_audits: true,
+            exclude_trusted: true,
+            ..Default::default()
+        },
+    );
+
+    let cfg = mock_cfg(&metadata);
+
+    let mut network = Network::new_mock();
+    network.mock_serve_toml(FOREIGN_URL, &new_foreign_audits);
+
+    let store = Store::mock_online(&cfg, config, audits, imports, &network, true).unwrap();
+
+    let imported = &store.imported_audits()[FOREIGN];
+    assert_eq!(
+        imported.audits.keys().collect::<Vec<_>>(),
+        vec!["third-party2", "transitive-third-party1"]
+    );
+    assert!(imported.wildcard_audits.is_empty());
+    assert!(imported.trusted.is_empty());
+
+    let output = get_imports_file_changes_noprune(&metadata, &store);
+    insta::assert_snapshot!(output);
+}
+
+#[test]
+fn peer_import_auditors_keep_violations() {
+    // (Fail) Limiting which auditors are trusted doesn't drop the violations
+    // recorded by anyone else.
+
+    let _enter = TEST_RUNTIME.enter();
+    let mock = MockMetadata::simple();
+
+    let metadata = mock.metadata();
+    let (mut config, audits, imports) = builtin_files_full_audited(&metadata);
+
+    let mut bad_audit = violation(VersionReq::parse("*").unwrap(), SAFE_TO_DEPLOY);
+    bad_audit.who = vec!["Mallory <mallory@example.com>".to_owned().into()];
+    let new_foreign_audits = AuditsFile {
+        criteria: SortedMap::new(),
+        wildcard_audits: SortedMap::new(),
+        audits: [("third-party1".to_owned(), vec![bad_audit])]
+            .into_iter()
+            .collect(),
+        trusted: SortedMap::new(),
+    };
+
+    config.imports.insert(
+        FOREIGN.to_owned(),
+        crate::format::RemoteImport {
+            url: vec![FOREIGN_URL.to_owned()],
+            auditors: vec!["alice@example.com".to_owned()],
+            ..Default::default()
+        },
+    );
+
+    let cfg = mock_cfg(&metadata);
+
+    let mut network = Network::new_mock();
+    network.mock_serve_toml(FOREIGN_URL, &new_foreign_audits);
+
+    let store = Store::mock_online(&cfg, config, audits, imports, &network, true).unwrap();
+
+    let imported = &store.imported_audits()[FOREIGN];
+    assert_eq!(
+        imported.audits.keys().collect::<Vec<_>>(),
+        vec!["third-party1"]
+    );
+
+    let report = crate::resolver::resolve(&metadata, None, &store);
+    assert!(matches!(
+        report.conclusion,
+        crate::resolver::Conclusion::FailForViolationConflict(..)
+    ));
+}
@@ -0,0 +1,123 @@
+use super::*;
+
+fn get_junit(metadata: &Metadata, store: &Store) -> String {
+    let report = crate::resolver::resolve(metadata, None, store);
+    let output = BasicTestOutput::new();
+    report.print_junit(&output.clone().as_dyn(), store).unwrap();
+    output.to_string()
+}
+
+#[test]
+fn junit_vetted() {
+    // (Pass) Each passing test case names the entries which vetted it.
+
+    let _enter = TEST_RUNTIME.enter();
+    let mock = MockMetadata::simple();
+
+    let metadata = mock.metadata();
+    let (mut config, mut audits, imports) = builtin_files_full_audited(&metadata);
+
+    audits.audits.remove("third-party2");
+    config.exemptions.insert(
+        "third-party2".to_owned(),
+        vec![exemptions(ver(DEFAULT_VER), SAFE_TO_DEPLOY)],
+    );
+    audits.audits.insert(
+        "transitive-third-party1".to_owned(),
+        vec![
+            full_audit(ver(5), SAFE_TO_DEPLOY),
+            delta_audit(ver(5), ver(DEFAULT_VER), SAFE_TO_DEPLOY),
+        ],
+    );
+
+    let store = Store::mock(config, audits, imports);
+
+    insta::assert_snapshot!(get_junit(&metadata, &store));
+}
+
+#[test]
+fn junit_unvetted() {
+    // (Fail) Each failing test case lists the missing criteria.
+
+    let _enter = TEST_RUNTIME.enter();
+    let mock = MockMetadata::simple();
+
+    let metadata = mock.metadata();
+    let (config, mut audits, imports) = builtin_files_full_audited(&metadata);
+
+    audits.audits.remove("third-party1");
+
+    let store = Store::mock(config, audits, imports);
+
+    insta::assert_snapshot!(get_junit(&metadata, &store));
+}
+
+#[test]
+fn junit_violation() {
+    // (Fail) Packages with violation conflicts fail, describing the conflict.
+
+    let _enter = TEST_RUNTIME.enter();
+    let mock = MockMetadata::simple();
+
+    let metadata = mock.metadata();
+    let (config, mut audits, imports) = builtin_files_full_audited(&metadata);
+
+    let violation_ver = VersionReq::parse(&format!("={DEFAULT_VER}")).unwrap();
+    audits
+        .audits
+        .get_mut("third-party1")
+        .unwrap()
+        .push(violation(violation_ver, SAFE_TO_RUN));
+
+    let store = Store::mock(config, audits, imports);
+
+    insta::assert_snapshot!(get_junit(&metadata, &store));
+}
+
+#[test]
+fn junit_violation_and_unvetted() {
+    // (Fail) Unvetted packages still fail when the check also failed due to
+    // an unrelated violation conflict.
+
+    let _enter = TEST_RUNTIME.enter();
+    let mock = MockMetadata::simple();
+
+    let metadata = mock.metadata();
+    let (config, mut audits, imports) = builtin_files_full_audited(&metadata);
+
+    let violation_ver = VersionReq::parse(&format!("={DEFAULT_VER}")).unwrap();
+    audits
+        .audits
+        .get_mut("third-party1")
+        .unwrap()
+        .push(violation(violation_ver, SAFE_TO_RUN));
+    audits.audits.remove("third-party2");
+
+    let store = Store::mock(config, audits, imports);
+
+    insta::assert_snapshot!(get_junit(&metadata, &store));
+}
+
+#[test]
+fn junit_missing_origin() {
+    // (Error) Entries which vetted a package but can't be found in the store
+    // are an error, rather than being left out of the report.
+
+    let _enter = TEST_RUNTIME.enter();
+    let mock = MockMetadata::simple();
+
+    let metadata = mock.metadata();
+    let (config, audits, imports) = builtin_files_full_audited(&metadata);
+    let store = Store::mock(config, audits, imports);
+    let report = crate::resolver::resolve(&metadata, None, &store);
+
+    let (config, mut audits, imports) = builtin_files_full_audited(&metadata);
+    audits.audits.remove("third-party1");
+    let other_store = Store::mock(config, audits, imports);
+
+    let output = BasicTestOutput::new();
+    let error = report
+        .print_junit(&output.as_dyn(), &other_store)
+        .unwrap_err();
+    assert_eq!(error.package, "third-party1");
+}
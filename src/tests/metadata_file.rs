@@ -0,0 +1,117 @@
+use std::path::Path;
+
+use crate::cli::GraphFilter;
+use crate::format::CargoLockFile;
+use crate::resolver::DepGraph;
+
+use super::*;
+
+const LOCKFILE: &str = r#"
+version = 3
+
+[[package]]
+name = "my-app"
+version = "0.1.0"
+dependencies = [
+ "test-helper",
+ "third-party1",
+]
+
+[[package]]
+name = "test-helper"
+version = "10.0.0"
+source = "registry+https://github.com/rust-lang/crates.io-index"
+checksum = "0000000000000000000000000000000000000000000000000000000000000000"
+
+[[package]]
+name = "third-party1"
+version = "10.0.0"
+source = "registry+https://github.com/rust-lang/crates.io-index"
+checksum = "0000000000000000000000000000000000000000000000000000000000000000"
+"#;
+
+fn node_names(graph: &DepGraph<'_>) -> Vec<String> {
+    graph
+        .nodes
+        .iter()
+        .map(|node| format!("{}:{}", node.name, node.version))
+        .collect()
+}
+
+#[test]
+fn metadata_file_lockfile_all_roots() {
+    // (Fail) Without dependency information, every package in the Cargo.lock
+    // is a root, so even test-only packages need the criteria for roots.
+
+    let _enter = TEST_RUNTIME.enter();
+
+    let lockfile: CargoLockFile = toml::de::from_str(LOCKFILE).unwrap();
+    let metadata = crate::metadata_from_lockfile(&lockfile, Path::new("/fake/project"));
+
+    let graph = DepGraph::new(&metadata, None, None);
+    assert!(graph.nodes.iter().all(|node| node.is_root));
+
+    let (config, mut audits, imports) = builtin_files_full_audited(&metadata);
+    audits.audits.insert(
+        "test-helper".to_owned(),
+        vec![full_audit(ver(DEFAULT_VER), SAFE_TO_RUN)],
+    );
+
+    let store = Store::mock(config, audits, imports);
+
+    assert_report_snapshot!("metadata-file-lockfile-all-roots", metadata, store);
+}
+
+#[test]
+fn metadata_file_filtered_roundtrip() {
+    // A filtered `dump-graph --metadata` can be read back as the same graph.
+
+    let metadata = MockMetadata::simple().metadata();
+    let filters = vec!["exclude(name(third-party2))"
+        .parse::<GraphFilter>()
+        .unwrap()];
+    let graph = DepGraph::new(&metadata, Some(&filters), None);
+
+    let saved = serde_json::to_string(&crate::filter_metadata(&metadata, &graph)).unwrap();
+    let loaded: Metadata = serde_json::from_str(&saved).unwrap();
+    let loaded_graph = DepGraph::new(&loaded, None, None);
+
+    assert!(!node_names(&graph).contains(&"third-party2:10.0.0".to_owned()));
+    assert_eq!(node_names(&graph), node_names(&loaded_graph));
+}
+
+#[test]
+fn metadata_file_format_by_content() {
+    // The format of a metadata file is picked by its contents, so JSON
+    // metadata saved with a `.lock` extension (like Bazel's Cargo.Bazel.lock)
+    // isn't mistaken for a Cargo.lock, and vice versa.
+
+    let dir = tempfile::tempdir().unwrap();
+    let metadata = MockMetadata::simple().metadata();
+    let graph = DepGraph::new(&metadata, None, None);
+
+    let json = serde_json::to_string_pretty(&metadata).unwrap();
+    for name in ["metadata.json", "Cargo.Bazel.lock"] {
+        let path = dir.path().join(name);
+        std::fs::write(&path, &json).unwrap();
+        let loaded = crate::load_metadata_file(&path).unwrap();
+        assert_eq!(
+            node_names(&graph),
+            node_names(&DepGraph::new(&loaded, None, None)),
+            "{name}"
+        );
+    }
+
+    for name in ["Cargo.lock", "lockfile.toml"] {
+        let path = dir.path().join(name);
+        std::fs::write(&path, LOCKFILE).unwrap();
+        let loaded = crate::load_metadata_file(&path).unwrap();
+        let loaded_graph = DepGraph::new(&loaded, None, None);
+        assert_eq!(
+            node_names(&loaded_graph),
+            ["my-app:0.1.0", "test-helper:10.0.0", "third-party1:10.0.0"],
+            "{name}"
+        );
+        assert!(loaded_graph.nodes.iter().all(|node| node.is_root), "{name}");
+    }
+}
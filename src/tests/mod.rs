@@ -48,19 +48,36 @@ macro_rules! assert_report_snapshot {
 }
 
 mod aggregate;
+mod alternate_registries;
 mod audit_as_crates_io;
+mod baseline;
 mod certify;
 mod crate_policies;
+mod criteria;
+mod diff_viewer;
+mod explain;
+mod feature_configs;
 mod import;
+mod junit;
+mod metadata_file;
+mod network;
+mod platforms;
 mod regenerate_unaudited;
 mod registry;
 mod renew;
+mod risks;
+mod sarif;
+mod sbom;
+mod stale_audits;
+mod store_diff;
 mod store_parsing;
 mod trusted;
 mod unpublished;
 mod vet;
 mod violations;
+mod why;
 mod wildcard;
+mod workspaces;
 
 // Some room above and below
 const DEFAULT_VER: u64 = 10;
@@ -116,11 +133,15 @@ struct MockPackage {
     targets: Vec<&'static str>,
     is_workspace: bool,
     is_first_party: bool,
+    /// The alternate registry the package is from, if not crates.io.
+    registry: Option<&'static str>,
 }
 
 struct MockDependency {
     name: &'static str,
     version: VetVersion,
+    /// The platform the dependency is conditional on, if any.
+    target: Option<&'static str>,
 }
 
 impl Default for MockPackage {
@@ -134,6 +155,7 @@ impl Default for MockPackage {
             targets: vec!["lib"],
             is_workspace: false,
             is_first_party: false,
+            registry: None,
         }
     }
 }
@@ -159,6 +181,14 @@ fn dep_ver(name: &'static str, version: u64) -> MockDependency {
     MockDependency {
         name,
         version: ver(version),
+        target: None,
+    }
+}
+
+fn dep_target(name: &'static str, target: &'static str) -> MockDependency {
+    MockDependency {
+        target: Some(target),
+        ..dep(name)
     }
 }
 
@@ -169,6 +199,8 @@ fn default_exemptions(version: VetVersion, config: &ConfigFile) -> ExemptedDepen
         criteria: vec![config.default_criteria.clone().into()],
         notes: None,
         suggest: true,
+        end: None,
+        is_expired: false,
     }
 }
 fn exemptions(version: VetVersion, criteria: CriteriaStr) -> ExemptedDependency {
@@ -177,6 +209,8 @@ fn exemptions(version: VetVersion, criteria: CriteriaStr) -> ExemptedDependency
         criteria: vec![criteria.to_string().into()],
         notes: None,
         suggest: true,
+        end: None,
+        is_expired: false,
     }
 }
 
@@ -187,8 +221,10 @@ fn delta_audit(from: VetVersion, to: VetVersion, criteria: CriteriaStr) -> Audit
         criteria: vec![criteria.to_string().into()],
         kind: AuditKind::Delta { from, to },
         importable: true,
+        date: None,
         aggregated_from: vec![],
         is_fresh_import: false,
+        is_stale: false,
     }
 }
 
@@ -199,8 +235,10 @@ fn full_audit(version: VetVersion, criteria: CriteriaStr) -> AuditEntry {
         criteria: vec![criteria.to_string().into()],
         kind: AuditKind::Full { version },
         importable: true,
+        date: None,
         aggregated_from: vec![],
         is_fresh_import: false,
+        is_stale: false,
     }
 }
 
@@ -214,8 +252,10 @@ fn full_audit_m(
         criteria: criteria.into_iter().map(|s| s.into().into()).collect(),
         kind: AuditKind::Full { version },
         importable: true,
+        date: None,
         aggregated_from: vec![],
         is_fresh_import: false,
+        is_stale: false,
     }
 }
 
@@ -226,8 +266,10 @@ fn violation_hard(version: VersionReq) -> AuditEntry {
         criteria: vec![SAFE_TO_RUN.to_string().into()],
         kind: AuditKind::Violation { violation: version },
         importable: true,
+        date: None,
         aggregated_from: vec![],
         is_fresh_import: false,
+        is_stale: false,
     }
 }
 #[allow(dead_code)]
@@ -238,8 +280,10 @@ fn violation(version: VersionReq, criteria: CriteriaStr) -> AuditEntry {
         criteria: vec![criteria.to_string().into()],
         kind: AuditKind::Violation { violation: version },
         importable: true,
+        date: None,
         aggregated_from: vec![],
         is_fresh_import: false,
+        is_stale: false,
     }
 }
 #[allow(dead_code)]
@@ -253,8 +297,10 @@ fn violation_m(
         criteria: criteria.into_iter().map(|s| s.into().into()).collect(),
         kind: AuditKind::Violation { violation: version },
         importable: true,
+        date: None,
         aggregated_from: vec![],
         is_fresh_import: false,
+        is_stale: false,
     }
 }
 
@@ -470,6 +516,7 @@ impl MockMetadata {
                         version: "10.0.0@git:00112233445566778899aabbccddeeff00112233"
                             .parse()
                             .unwrap(),
+                        target: None,
                     },
                     dep("third-party2"),
                 ],
@@ -764,6 +811,11 @@ impl MockMetadata {
                     "{} {} (git+https://github.com/owner/{}#{})",
                     package.name, package.version.semver, package.name, git_rev
                 )
+            } else if let Some(registry) = package.registry {
+                format!(
+                    "{} {} (sparse+https://{}.example.com/index/)",
+                    package.name, package.version, registry
+                )
             } else {
                 format!(
                     "{} {} (registry+https://github.com/rust-lang/crates.io-index)",
@@ -807,6 +859,8 @@ impl MockMetadata {
             json!(null)
         } else if let Some(git_rev) = &package.version.git_rev {
             format!("git+https://github.com/owner/{}#{}", package.name, git_rev).into()
+        } else if let Some(registry) = package.registry {
+            format!("sparse+https://{registry}.example.com/index/").into()
         } else {
             json!("registry+https://github.com/rust-lang/crates.io-index")
         }
@@ -831,7 +885,7 @@ impl MockMetadata {
                     "optional": false,
                     "uses_default_features": true,
                     "features": [],
-                    "target": null,
+                    "target": dep.target,
                     "registry": null
                 })).collect::<Vec<_>>(),
                 "targets": package.targets.iter().map(|target| json!({
@@ -873,15 +927,15 @@ impl MockMetadata {
             }).collect::<Vec<_>>(),
             "resolve": {
                 "nodes": self.packages.iter().map(|package| {
-                    let mut all_deps = BTreeMap::<(PackageStr, &VetVersion), Vec<Option<&str>>>::new();
+                    let mut all_deps = BTreeMap::<(PackageStr, &VetVersion), Vec<(Option<&str>, Option<&str>)>>::new();
                     for dep in &package.deps {
-                        all_deps.entry((dep.name, &dep.version)).or_default().push(None);
+                        all_deps.entry((dep.name, &dep.version)).or_default().push((None, dep.target));
                     }
                     for dep in &package.build_deps {
-                        all_deps.entry((dep.name, &dep.version)).or_default().push(Some("build"));
+                        all_deps.entry((dep.name, &dep.version)).or_default().push((Some("build"), dep.target));
                     }
                     for dep in &package.dev_deps {
-                        all_deps.entry((dep.name, &dep.version)).or_default().push(Some("dev"));
+                        all_deps.entry((dep.name, &dep.version)).or_default().push((Some("dev"), dep.target));
                     }
                     json!({
                         "id": self.pkgid(package),
@@ -889,9 +943,9 @@ impl MockMetadata {
                         "deps": all_deps.iter().map(|((name, version), kinds)| json!({
                             "name": name,
                             "pkg": self.pkgid_by(name, version),
-                            "dep_kinds": kinds.iter().map(|kind| json!({
+                            "dep_kinds": kinds.iter().map(|(kind, target)| json!({
                                 "kind": kind,
-                                "target": null,
+                                "target": target,
                             })).collect::<Vec<_>>(),
                         })).collect::<Vec<_>>(),
                     })
@@ -915,8 +969,17 @@ fn init_files(
     let mut config = ConfigFile {
         cargo_vet: Default::default(),
         default_criteria: default_criteria.to_owned(),
+        on_fetch_failure: Default::default(),
+        diff_weights: Default::default(),
+        stale_audits: None,
         imports: Default::default(),
+        registries: Default::default(),
+        workspaces: Default::default(),
+        filter_platforms: Default::default(),
+        platform_criteria: Default::default(),
+        feature_configs: Default::default(),
         policy: Default::default(),
+        disputes: Default::default(),
         exemptions: Default::default(),
     };
     let audits = AuditsFile {
@@ -1109,6 +1172,8 @@ where
     Config {
         metacfg: MetaConfig(vec![]),
         metadata: metadata.clone(),
+        feature_metadata: SortedMap::new(),
+        target_cfgs: SortedMap::new(),
         _rest: PartialConfig {
             cli,
             now: mock_now(),
@@ -1204,6 +1269,33 @@ impl Out for BasicTestOutput {
         }
     }
 
+    /// Keys are read through `on_read_line`, using the names "enter", "esc",
+    /// "up", "down", "pageup" and "pagedown", or the character itself.
+    fn read_key(&self) -> io::Result<console::Key> {
+        use console::Key;
+
+        let Some(on_read_line) = &self.on_read_line else {
+            return Err(io::ErrorKind::Unsupported.into());
+        };
+        let response = on_read_line("")?;
+        writeln!(self, "<<<KEY {response}>>>");
+        Ok(match &response[..] {
+            "enter" => Key::Enter,
+            "esc" => Key::Escape,
+            "up" => Key::ArrowUp,
+            "down" => Key::ArrowDown,
+            "pageup" => Key::PageUp,
+            "pagedown" => Key::PageDown,
+            other => {
+                let mut chars = other.chars();
+                match (chars.next(), chars.next()) {
+                    (Some(c), None) => Key::Char(c),
+                    _ => panic!("unknown key {other:?}"),
+                }
+            }
+        })
+    }
+
     fn editor<'b>(&'b self, name: &'b str) -> io::Result<Editor<'b>> {
         if let Some(on_edit) = &self.on_edit {
             let mut editor = Editor::new(name)?;
@@ -1369,7 +1461,7 @@ impl MockRegistryBuilder {
             // Preserving case here matches how we currently construct the API
             // url internally, but may need to be changed in the future.
             network.mock_serve_json(
-                format!("https://crates.io/api/v1/crates/{name}"),
+                network.crates_io_api_url(&format!("v1/crates/{name}")),
                 &CratesAPICrate {
                     crate_data: pkg.metadata.clone(),
                     versions: pkg
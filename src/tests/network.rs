@@ -0,0 +1,220 @@
+use std::{
+    io::{BufRead, BufReader, Write},
+    net::TcpListener,
+    sync::mpsc,
+    thread,
+    time::Duration,
+};
+
+use reqwest::Url;
+
+use super::*;
+use crate::errors::DownloadError;
+
+/// Create a Network using only the cargo config written to `cargo_config`,
+/// ignoring the environment and `CARGO_HOME`.
+fn network_with_cargo_config(cargo_config: &str) -> Network {
+    let dir = tempfile::tempdir().unwrap();
+    fs::create_dir(dir.path().join(".cargo")).unwrap();
+    fs::write(dir.path().join(".cargo/config.toml"), cargo_config).unwrap();
+    let cargo_config = cargo_config2::Config::load_with_options(
+        dir.path(),
+        cargo_config2::ResolveOptions::default()
+            .env(std::iter::empty::<(String, String)>())
+            .cargo_home(None),
+    )
+    .unwrap();
+
+    let mock = MockMetadata::simple();
+    let cfg = mock_cfg(&mock.metadata());
+    Network::with_cargo_config(&cfg, Some(cargo_config))
+}
+
+/// Serve `ok` to each request on a local port, sending the head of each
+/// request (its request line and headers) over the returned channel.
+fn serve_ok() -> (u16, mpsc::Receiver<Vec<String>>) {
+    serve("200 OK")
+}
+
+/// Like `serve_ok`, but responding with the given status.
+fn serve(status: &'static str) -> (u16, mpsc::Receiver<Vec<String>>) {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let port = listener.local_addr().unwrap().port();
+    let (sender, receiver) = mpsc::channel();
+    thread::spawn(move || {
+        for stream in listener.incoming() {
+            let mut stream = stream.unwrap();
+            let head = BufReader::new(&stream)
+                .lines()
+                .map(Result::unwrap)
+                .take_while(|line| !line.is_empty())
+                .collect::<Vec<_>>();
+            // Report the request before responding, so that it has been
+            // received by the time the download completes.
+            if sender.send(head).is_err() {
+                break;
+            }
+            let response =
+                format!("HTTP/1.1 {status}\r\nContent-Length: 2\r\nConnection: close\r\n\r\nok");
+            stream.write_all(response.as_bytes()).unwrap();
+        }
+    });
+    (port, receiver)
+}
+
+fn download(network: &Network, url: &str) -> Result<Vec<u8>, DownloadError> {
+    tokio::runtime::Handle::current().block_on(network.download(Url::parse(url).unwrap()))
+}
+
+fn authorization(head: &[String]) -> Option<&str> {
+    head.iter()
+        .find_map(|line| line.strip_prefix("authorization: "))
+}
+
+#[test]
+fn network_token_scoped_to_index() {
+    // Registry tokens are only sent with requests under the registry's index
+    // URL, not to other URLs on the same host.
+    let _enter = TEST_RUNTIME.enter();
+
+    let (port, requests) = serve_ok();
+    let network = network_with_cargo_config(&format!(
+        r#"
+[registries.private]
+index = "sparse+http://127.0.0.1:{port}/index"
+token = "secret"
+"#
+    ));
+
+    download(
+        &network,
+        &format!("http://127.0.0.1:{port}/index/config.json"),
+    )
+    .unwrap();
+    let head = requests.recv().unwrap();
+    assert_eq!(authorization(&head), Some("secret"));
+
+    for path in ["index-other/config.json", "audits.toml"] {
+        download(&network, &format!("http://127.0.0.1:{port}/{path}")).unwrap();
+        let head = requests.recv().unwrap();
+        assert_eq!(authorization(&head), None, "token sent to /{path}");
+    }
+}
+
+#[test]
+fn network_proxy() {
+    // Requests go through the proxy in `http.proxy`.
+    let _enter = TEST_RUNTIME.enter();
+
+    let (port, requests) = serve_ok();
+    let network = network_with_cargo_config(&format!(
+        r#"
+[http]
+proxy = "http://127.0.0.1:{port}"
+"#
+    ));
+
+    let body = download(&network, "http://audits.example.invalid/audits.toml").unwrap();
+    assert_eq!(body, b"ok");
+    let head = requests.recv().unwrap();
+    assert_eq!(
+        head[0],
+        "GET http://audits.example.invalid/audits.toml HTTP/1.1"
+    );
+}
+
+#[test]
+fn network_timeout() {
+    // Requests which take longer than `http.timeout` fail with a timeout, and
+    // aren't retried more than `net.retry` times.
+    let _enter = TEST_RUNTIME.enter();
+
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let port = listener.local_addr().unwrap().port();
+    let (done, finished) = mpsc::channel::<()>();
+    let server = thread::spawn(move || {
+        // Accept the one connection the download makes, but never respond to
+        // it, holding it open until the test is done.
+        let (_stream, _) = listener.accept().unwrap();
+        let _ = finished.recv();
+    });
+    let network = network_with_cargo_config(
+        r#"
+[http]
+timeout = 1
+
+[net]
+retry = 0
+"#,
+    );
+
+    let start = std::time::Instant::now();
+    let error = download(&network, &format!("http://127.0.0.1:{port}/audits.toml")).unwrap_err();
+    match error {
+        DownloadError::FailedToStartDownload { error, .. } => assert!(error.is_timeout()),
+        error => panic!("unexpected error: {error:?}"),
+    }
+    assert!(start.elapsed() < Duration::from_secs(10));
+
+    drop(done);
+    server.join().unwrap();
+}
+
+#[test]
+fn network_retries_transient_errors_only() {
+    // 5xx and 429 responses are retried up to `net.retry` times, but other
+    // failed requests, like a 404 or a connection closed without a response,
+    // aren't.
+    let _enter = TEST_RUNTIME.enter();
+
+    let network = network_with_cargo_config(
+        r#"
+[http]
+timeout = 5
+
+[net]
+retry = 1
+"#,
+    );
+
+    for (status, attempts) in [
+        ("404 Not Found", 1),
+        ("503 Service Unavailable", 2),
+        ("429 Too Many Requests", 2),
+    ] {
+        let (port, requests) = serve(status);
+        let error =
+            download(&network, &format!("http://127.0.0.1:{port}/audits.toml")).unwrap_err();
+        match error {
+            DownloadError::FailedToStartDownload { error, .. } => {
+                assert!(error.status().is_some(), "{status}: {error:?}")
+            }
+            error => panic!("unexpected error: {error:?}"),
+        }
+        assert_eq!(requests.try_iter().count(), attempts, "{status}");
+    }
+
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let port = listener.local_addr().unwrap().port();
+    let server = {
+        let listener = listener.try_clone().unwrap();
+        thread::spawn(move || {
+            // Read the request, then close the connection without responding.
+            let (stream, _) = listener.accept().unwrap();
+            BufReader::new(&stream)
+                .lines()
+                .map(Result::unwrap)
+                .take_while(|line| !line.is_empty())
+                .for_each(drop);
+        })
+    };
+    let error = download(&network, &format!("http://127.0.0.1:{port}/audits.toml")).unwrap_err();
+    assert!(
+        matches!(error, DownloadError::FailedToStartDownload { .. }),
+        "unexpected error: {error:?}"
+    );
+    server.join().unwrap();
+    // A retry would have connected again, and be waiting to be accepted.
+    listener.set_nonblocking(true).unwrap();
+    assert!(listener.accept().is_err(), "retried a closed connection");
+}
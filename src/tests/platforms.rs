@@ -0,0 +1,257 @@
+use super::*;
+
+fn platform_metadata() -> MockMetadata {
+    //                                    Graph
+    // =======================================================================================
+    //
+    //                                 root-package
+    //                               /      |       \
+    //                      third-party1    |    (cfg(unix)) unix-only
+    //                                      |
+    //                             (cfg(windows)) windows-only
+    //                                      |
+    //                             windows-transitive
+    //
+    MockMetadata::new(vec![
+        MockPackage {
+            name: "root-package",
+            is_workspace: true,
+            is_first_party: true,
+            deps: vec![
+                dep("third-party1"),
+                dep_target("windows-only", "cfg(windows)"),
+                dep_target("unix-only", "cfg(unix)"),
+            ],
+            ..Default::default()
+        },
+        MockPackage {
+            name: "third-party1",
+            ..Default::default()
+        },
+        MockPackage {
+            name: "windows-only",
+            deps: vec![dep("windows-transitive")],
+            ..Default::default()
+        },
+        MockPackage {
+            name: "windows-transitive",
+            ..Default::default()
+        },
+        MockPackage {
+            name: "unix-only",
+            ..Default::default()
+        },
+    ])
+}
+
+fn platform_store(metadata: &Metadata) -> (ConfigFile, AuditsFile, ImportsFile) {
+    let (config, mut audits, imports) = builtin_files_full_audited(metadata);
+    for name in ["windows-only", "windows-transitive"] {
+        audits.audits.insert(
+            name.to_owned(),
+            vec![full_audit(ver(DEFAULT_VER), SAFE_TO_RUN)],
+        );
+    }
+    (config, audits, imports)
+}
+
+fn platform_criteria(
+    config: &mut ConfigFile,
+    platform: &str,
+    criteria: impl IntoIterator<Item = impl Into<CriteriaName>>,
+) {
+    config.platform_criteria.insert(
+        platform.to_owned().into(),
+        criteria.into_iter().map(|c| c.into().into()).collect(),
+    );
+}
+
+#[test]
+fn platform_deps_in_graph() {
+    // Only edges which are conditional on a platform are recorded as such.
+
+    let metadata = platform_metadata().metadata();
+    let graph = crate::resolver::DepGraph::new(&metadata, None, None);
+    let root = &graph.nodes[graph.interner_by_pkgid[&metadata.workspace_members[0]]];
+
+    let platform_deps = root
+        .platform_deps
+        .iter()
+        .map(|(&depidx, platforms)| (graph.nodes[depidx].name, platforms.clone()))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        platform_deps,
+        vec![
+            ("unix-only", vec!["cfg(unix)".to_owned()]),
+            ("windows-only", vec!["cfg(windows)".to_owned()]),
+        ]
+    );
+}
+
+#[test]
+fn platform_criteria_missing() {
+    // (Fail) Without platform-criteria, platform-specific deps need the full
+    // criteria of their dependents.
+
+    let _enter = TEST_RUNTIME.enter();
+    let metadata = platform_metadata().metadata();
+    let (config, audits, imports) = platform_store(&metadata);
+
+    let store = Store::mock(config, audits, imports);
+
+    assert_report_snapshot!("platform-criteria-missing", metadata, store);
+}
+
+#[test]
+fn platform_criteria_lowered() {
+    // (Pass) Deps which are only used on Windows, and their dependencies, only
+    // need the platform-criteria for Windows.
+
+    let _enter = TEST_RUNTIME.enter();
+    let metadata = platform_metadata().metadata();
+    let (mut config, audits, imports) = platform_store(&metadata);
+
+    platform_criteria(&mut config, "cfg(windows)", [SAFE_TO_RUN]);
+
+    let store = Store::mock(config, audits, imports);
+
+    assert_report_snapshot!("platform-criteria-lowered", metadata, store);
+}
+
+#[test]
+fn platform_criteria_also_unconditional() {
+    // (Fail) A dep which is also used unconditionally elsewhere in the graph
+    // still needs the full criteria along that edge.
+
+    let _enter = TEST_RUNTIME.enter();
+    let mut mock = platform_metadata();
+    mock.packages[1].deps.push(dep("windows-transitive"));
+    let metadata = mock.metadata();
+    let (mut config, audits, imports) = platform_store(&metadata);
+
+    platform_criteria(&mut config, "cfg(windows)", [SAFE_TO_RUN]);
+
+    let store = Store::mock(config, audits, imports);
+
+    assert_report_snapshot!("platform-criteria-also-unconditional", metadata, store);
+}
+
+#[test]
+fn platform_criteria_dependency_criteria_wins() {
+    // (Fail) A dependency-criteria policy entry takes precedence over the
+    // platform-criteria for the same edge.
+
+    let _enter = TEST_RUNTIME.enter();
+    let metadata = platform_metadata().metadata();
+    let (mut config, audits, imports) = platform_store(&metadata);
+
+    platform_criteria(&mut config, "cfg(windows)", [SAFE_TO_RUN]);
+    config.policy.insert(
+        "root-package".to_owned(),
+        dep_policy([("windows-only", [SAFE_TO_DEPLOY])]),
+    );
+
+    let store = Store::mock(config, audits, imports);
+
+    assert_report_snapshot!(
+        "platform-criteria-dependency-criteria-wins",
+        metadata,
+        store
+    );
+}
+
+#[test]
+fn platform_criteria_why() {
+    // The platform-criteria is reported as the origin of the requirement.
+
+    let _enter = TEST_RUNTIME.enter();
+    console::set_colors_enabled(false);
+    let metadata = platform_metadata().metadata();
+    let (mut config, audits, imports) = platform_store(&metadata);
+
+    platform_criteria(&mut config, "cfg(windows)", [SAFE_TO_RUN]);
+
+    let store = Store::mock(config, audits, imports);
+
+    let report = crate::resolver::resolve(&metadata, None, &store);
+    let why = report.compute_why("windows-transitive", None);
+    let output = BasicTestOutput::new();
+    crate::resolver::print_why_human(&output.clone().as_dyn(), &why).unwrap();
+
+    insta::assert_snapshot!(output.to_string());
+}
+
+/// The (abridged) cfg values of a few targets, as `rustc --print cfg` would
+/// report them.
+fn target_cfgs() -> crate::resolver::TargetCfgs {
+    [
+        ("x86_64-pc-windows-msvc", "windows", "msvc"),
+        ("x86_64-pc-windows-gnu", "windows", "gnu"),
+        ("x86_64-unknown-linux-gnu", "unix", "gnu"),
+    ]
+    .into_iter()
+    .map(|(target, family, env)| {
+        let os = if family == "windows" {
+            "windows"
+        } else {
+            "linux"
+        };
+        let cfgs = [
+            family.to_owned(),
+            format!("target_family=\"{family}\""),
+            format!("target_os=\"{os}\""),
+            format!("target_env=\"{env}\""),
+        ];
+        (
+            target.to_owned(),
+            cfgs.iter().map(|cfg| cfg.parse().unwrap()).collect(),
+        )
+    })
+    .collect()
+}
+
+fn assert_target_report_snapshot(name: &str, metadata: &Metadata, store: &Store) {
+    let report = crate::resolver::resolve_with_baseline(
+        metadata,
+        &SortedMap::new(),
+        &target_cfgs(),
+        None,
+        store,
+        None,
+    );
+    let (human, json) = get_reports(metadata, report, store, None);
+    insta::assert_snapshot!(name, human);
+    insta::assert_snapshot!(format!("{name}.json"), json);
+}
+
+#[test]
+fn platform_criteria_evaluated() {
+    // (Pass) With known targets, platform-criteria apply to the targets their
+    // cfg expressions match, however the dependency's platform is spelled.
+
+    let _enter = TEST_RUNTIME.enter();
+    let metadata = platform_metadata().metadata();
+    let (mut config, audits, imports) = platform_store(&metadata);
+
+    platform_criteria(&mut config, "cfg(target_os = \"windows\")", [SAFE_TO_RUN]);
+
+    let store = Store::mock(config, audits, imports);
+
+    assert_target_report_snapshot("platform-criteria-lowered", &metadata, &store);
+}
+
+#[test]
+fn platform_criteria_evaluated_uncovered_target() {
+    // (Fail) A dependency needs the full criteria of its dependents if one of
+    // the targets it's used on has no platform-criteria.
+
+    let _enter = TEST_RUNTIME.enter();
+    let metadata = platform_metadata().metadata();
+    let (mut config, audits, imports) = platform_store(&metadata);
+
+    platform_criteria(&mut config, "x86_64-pc-windows-msvc", [SAFE_TO_RUN]);
+
+    let store = Store::mock(config, audits, imports);
+
+    assert_target_report_snapshot("platform-criteria-missing", &metadata, &store);
+}
@@ -79,6 +79,102 @@ fn builtin_simple_deps_exemptions_overbroad_regenerate() {
     insta::assert_snapshot!("builtin-simple-unaudited-overbroad-regenerate", exemptions);
 }
 
+#[test]
+fn builtin_simple_deps_exemptions_overbroad_end_date_regenerate() {
+    // (Pass) the exemptions entry is needed but it's overbroad, and has an end
+    // date. Should downgrade from safe-to-deploy to safe-to-run, keeping the
+    // end date.
+
+    let _enter = TEST_RUNTIME.enter();
+    let mock = MockMetadata::simple_deps();
+
+    let metadata = mock.metadata();
+    let (mut config, mut audits, imports) = builtin_files_full_audited(&metadata);
+
+    audits.audits.insert("dev".to_string(), vec![]);
+
+    let end = mock_today() + chrono::Duration::weeks(4);
+    config.exemptions.insert(
+        "dev".to_string(),
+        vec![ExemptedDependency {
+            end: Some(end.into()),
+            ..exemptions(ver(DEFAULT_VER), SAFE_TO_DEPLOY)
+        }],
+    );
+
+    let mut store = Store::mock(config, audits, imports);
+    let cfg = mock_cfg(&metadata);
+    basic_regenerate(&cfg, &mut store);
+
+    let exemptions = &store.config.exemptions["dev"];
+    assert_eq!(exemptions.len(), 1);
+    assert_eq!(exemptions[0].criteria, vec![SAFE_TO_RUN.to_owned()]);
+    assert_eq!(exemptions[0].end.as_deref(), Some(&end));
+}
+
+fn expired_exemption_files(
+    metadata: &Metadata,
+) -> (ConfigFile, AuditsFile, ImportsFile, chrono::NaiveDate) {
+    let (mut config, mut audits, imports) = builtin_files_full_audited(metadata);
+
+    audits.audits.remove("third-party1");
+    let end = mock_today() - chrono::Duration::days(1);
+    config.exemptions.insert(
+        "third-party1".to_string(),
+        vec![ExemptedDependency {
+            end: Some(end.into()),
+            ..exemptions(ver(DEFAULT_VER), SAFE_TO_DEPLOY)
+        }],
+    );
+    (config, audits, imports, end)
+}
+
+#[test]
+fn builtin_simple_exemptions_expired_regenerate() {
+    // (Fail) the exemptions entry is needed but has expired. Regenerating it
+    // shouldn't make it permanent, so it keeps its end date.
+
+    let _enter = TEST_RUNTIME.enter();
+    let mock = MockMetadata::simple();
+
+    let metadata = mock.metadata();
+    let (config, audits, imports, end) = expired_exemption_files(&metadata);
+
+    let mut store = Store::mock(config, audits, imports);
+    store.mark_expired_exemptions(mock_today());
+    let cfg = mock_cfg(&metadata);
+    basic_regenerate(&cfg, &mut store);
+
+    let exemptions = &store.config.exemptions["third-party1"];
+    assert_eq!(exemptions.len(), 1);
+    assert_eq!(exemptions[0].version, ver(DEFAULT_VER));
+    assert_eq!(exemptions[0].criteria, vec![SAFE_TO_DEPLOY.to_owned()]);
+    assert_eq!(exemptions[0].end.as_deref(), Some(&end));
+    assert!(exemptions[0].is_expired);
+}
+
+#[test]
+fn builtin_simple_exemptions_expired_minimize() {
+    // (Fail) the exemptions entry is needed but has expired. Pruning shouldn't
+    // remove it, so that it's still reported as expired.
+
+    let _enter = TEST_RUNTIME.enter();
+    let mock = MockMetadata::simple();
+
+    let metadata = mock.metadata();
+    let (config, audits, imports, end) = expired_exemption_files(&metadata);
+
+    let mut store = Store::mock(config, audits, imports);
+    store.mark_expired_exemptions(mock_today());
+    let cfg = mock_cfg(&metadata);
+    basic_minimize(&cfg, &mut store);
+
+    let exemptions = &store.config.exemptions["third-party1"];
+    assert_eq!(exemptions.len(), 1);
+    assert_eq!(exemptions[0].end.as_deref(), Some(&end));
+    assert!(exemptions[0].is_expired);
+}
+
 #[test]
 fn builtin_complex_exemptions_twins_regenerate() {
     // (Pass) two versions of a crate exist and both are exemptions and they're needed
@@ -34,3 +34,30 @@ url = 10 # invalid!
 
     insta::assert_snapshot!(human_output.to_string());
 }
+
+#[test]
+fn test_registry_local_urls() {
+    // Registry entries which refer to local files are ignored, as only the
+    // local config may import them.
+    let _enter = TEST_RUNTIME.enter();
+
+    let mut network = Network::new_mock();
+    network.mock_serve(
+        crate::storage::REGISTRY_URL,
+        r#"
+[registry.remote]
+url = "https://example.com/supply-chain/audits.toml"
+
+[registry.local]
+url = "file:///etc/passwd"
+
+[registry.local-git]
+url = "git+file:///srv/audits#path=audits.toml"
+"#,
+    );
+
+    let registry = tokio::runtime::Handle::current()
+        .block_on(crate::storage::fetch_registry(&network))
+        .unwrap();
+    assert_eq!(registry.registry.keys().collect::<Vec<_>>(), vec!["remote"],);
+}
@@ -1,6 +1,6 @@
 use super::*;
 
-use crate::{do_cmd_renew, WildcardAuditRenewal};
+use crate::{do_cmd_renew, ExemptionRenewal, WildcardAuditRenewal};
 
 struct ExpireTest {
     today: chrono::NaiveDate,
@@ -284,6 +284,85 @@ fn renew_specific_selection_logic() {
     });
 }
 
+fn exemption_renewal_test(test_name: &str, args: &[&str]) -> Store {
+    let _enter = TEST_RUNTIME.enter();
+    let metadata = MockMetadata::simple().metadata();
+    let (mut config, audits, imports) = builtin_files_no_exemptions(&metadata);
+
+    let today = mock_today();
+    use chrono::Duration;
+    let expired = today - Duration::weeks(1);
+    let expiring = today + Duration::weeks(1);
+    let not_expiring = today + Duration::weeks(7);
+
+    let entry = |version: u64, end: Option<chrono::NaiveDate>| ExemptedDependency {
+        end: end.map(Into::into),
+        ..exemptions(ver(version), SAFE_TO_DEPLOY)
+    };
+
+    config.exemptions.insert(
+        "foo".into(),
+        vec![
+            entry(1, Some(expired)),
+            entry(2, Some(expiring)),
+            entry(3, Some(not_expiring)),
+            entry(4, None),
+        ],
+    );
+    config
+        .exemptions
+        .insert("bar".into(), vec![entry(1, Some(not_expiring))]);
+    config.exemptions.insert("baz".into(), vec![entry(1, None)]);
+
+    let mut store = Store::mock(config, audits, imports);
+    let cfg = mock_cfg_args(
+        &metadata,
+        ["cargo", "vet", "renew"]
+            .into_iter()
+            .chain(args.iter().copied()),
+    );
+    let sub_args = if let Some(crate::cli::Commands::Renew(sub_args)) = &cfg.cli.command {
+        sub_args
+    } else {
+        unreachable!();
+    };
+
+    let renewal = ExemptionRenewal::expiring(&cfg, &mut store.config.exemptions);
+    assert_eq!(renewal.expired_exemptions(), vec!["foo:1.0.0"]);
+    assert_eq!(renewal.expiring_exemptions(), vec!["foo:2.0.0"]);
+
+    let output = BasicTestOutput::new();
+    let before = store.mock_commit();
+    do_cmd_renew(&output.clone().as_dyn(), &cfg, &mut store, sub_args);
+    let after = store.mock_commit();
+    insta::assert_snapshot!(
+        test_name,
+        format!("{output}\n{}", diff_store_commits(&before, &after))
+    );
+    store
+}
+
+/// Only exemptions with an end date which has passed or will soon pass should
+/// be renewed with `--expiring`.
+#[test]
+fn renew_expiring_exemptions() {
+    exemption_renewal_test("renew-expiring-exemptions", &["--expiring"]);
+}
+
+/// Providing a crate name renews all of its exemptions with an end date.
+#[test]
+fn renew_specific_crate_exemptions() {
+    let store = exemption_renewal_test("renew-specific-crate-exemptions", &["foo"]);
+    let renew_date = mock_today() + chrono::Months::new(12);
+    assert_eq!(
+        store.config.exemptions["foo"]
+            .iter()
+            .map(|e| e.end.as_ref().map(|end| **end))
+            .collect::<Vec<_>>(),
+        vec![Some(renew_date), Some(renew_date), Some(renew_date), None]
+    );
+}
+
 enum ExpiringStatus {
     None,
     Expiring,
@@ -0,0 +1,152 @@
+use std::path::Path;
+
+use crate::format::{RiskIndicator, RiskKind};
+
+use super::*;
+
+fn write_files(root: &Path, files: &[(&str, &[u8])]) {
+    for (path, contents) in files {
+        let path = root.join(path);
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(path, contents).unwrap();
+    }
+}
+
+#[test]
+fn risk_indicators_in_delta() {
+    // Only newly added occurrences of each pattern are counted, and build
+    // script changes and binary files are flagged by path.
+
+    let _enter = TEST_RUNTIME.enter();
+    let mock = MockMetadata::simple();
+    let metadata = mock.metadata();
+    let cfg = mock_cfg(&metadata);
+    let cache = crate::storage::Cache::acquire(&cfg).unwrap();
+
+    let old = tempfile::tempdir().unwrap();
+    let new = tempfile::tempdir().unwrap();
+    write_files(
+        old.path(),
+        &[
+            ("Cargo.toml", b"[package]\nname = \"risky\"\n"),
+            ("src/lib.rs", b"pub unsafe fn old() {}\n"),
+            ("build.rs", b"fn main() {}\n"),
+        ],
+    );
+    write_files(
+        new.path(),
+        &[
+            (
+                "Cargo.toml",
+                b"[package]\nname = \"risky\"\nbuild = \"build/main.rs\"\n",
+            ),
+            (
+                "src/lib.rs",
+                b"pub unsafe fn old() {}\n\
+                  pub fn new() { unsafe { std::process::Command::new(\"sh\"); } }\n\
+                  use std::net::TcpStream;\n\
+                  extern \"C\" { fn ffi(); }\n\
+                  static DATA: &[u8] = include_bytes!(\"data.bin\");\n\
+                  // not_unsafe_at_all\n",
+            ),
+            ("build/main.rs", b"fn main() {}\n"),
+            ("build.rs", b"fn main() {}\n"),
+            ("src/data.bin", b"\0\x01\x02\x03"),
+        ],
+    );
+
+    let (diff, _) = TEST_RUNTIME
+        .block_on(cache.diffstat_package(old.path(), new.path(), false))
+        .unwrap();
+
+    let risk = |kind, occurrences, files: &[&str]| RiskIndicator {
+        kind,
+        occurrences,
+        files: files.iter().map(|f| f.to_string()).collect(),
+    };
+    assert_eq!(
+        diff.risks,
+        vec![
+            risk(RiskKind::Unsafe, 1, &["src/lib.rs"]),
+            risk(RiskKind::BuildScript, 1, &["build/main.rs"]),
+            risk(RiskKind::ProcessSpawn, 1, &["src/lib.rs"]),
+            risk(RiskKind::Network, 1, &["src/lib.rs"]),
+            risk(RiskKind::Ffi, 1, &["src/lib.rs"]),
+            risk(RiskKind::IncludeBytes, 1, &["src/lib.rs"]),
+            risk(RiskKind::Binary, 1, &["src/data.bin"]),
+        ]
+    );
+    assert_eq!(
+        diff.risks.iter().map(|r| r.to_string()).collect::<Vec<_>>(),
+        vec![
+            "unsafe code: 1 new line in src/lib.rs",
+            "build script: 1 changed line in build/main.rs",
+            "process spawning: 1 new line in src/lib.rs",
+            "networking: 1 new line in src/lib.rs",
+            "FFI: 1 new line in src/lib.rs",
+            "include_bytes!: 1 new line in src/lib.rs",
+            "binary files: 1 file in src/data.bin",
+        ]
+    );
+}
+
+#[test]
+fn risk_indicators_match_path_segments() {
+    // Process spawning and networking are found through grouped imports and
+    // bare type names, but not in identifiers which merely contain them.
+
+    let _enter = TEST_RUNTIME.enter();
+    let mock = MockMetadata::simple();
+    let metadata = mock.metadata();
+    let cfg = mock_cfg(&metadata);
+    let cache = crate::storage::Cache::acquire(&cfg).unwrap();
+
+    let old = tempfile::tempdir().unwrap();
+    let new = tempfile::tempdir().unwrap();
+    write_files(
+        old.path(),
+        &[
+            ("Cargo.toml", b"[package]\nname = \"risky\"\n"),
+            ("src/lib.rs", b""),
+        ],
+    );
+    write_files(
+        new.path(),
+        &[
+            ("Cargo.toml", b"[package]\nname = \"risky\"\n"),
+            (
+                "src/lib.rs",
+                b"use std::{fs, process::{Command, Stdio}};\n\
+                  use std::{\n    \
+                      net::TcpListener,\n\
+                  };\n\
+                  fn spawn() { Command::new(\"sh\").stdin(Stdio::null()); }\n\
+                  fn connect(stream: TcpStream) {}\n\
+                  use crate::internet::MyCommand;\n\
+                  struct NotATcpStreamAtAll;\n\
+                  fn commandeer(cmd: MyCommand) { cmd.run(); }\n",
+            ),
+        ],
+    );
+
+    let (diff, _) = TEST_RUNTIME
+        .block_on(cache.diffstat_package(old.path(), new.path(), false))
+        .unwrap();
+
+    let files = vec!["src/lib.rs".to_owned()];
+    assert_eq!(
+        diff.risks,
+        vec![
+            RiskIndicator {
+                kind: RiskKind::ProcessSpawn,
+                occurrences: 2,
+                files: files.clone(),
+            },
+            RiskIndicator {
+                kind: RiskKind::Network,
+                occurrences: 2,
+                files,
+            },
+        ]
+    );
+}
@@ -0,0 +1,158 @@
+use std::fmt::Write as _;
+
+use cargo_metadata::camino::Utf8PathBuf;
+
+use crate::errors::CratePolicyErrors;
+
+use super::*;
+
+/// Round-trip the store's files through TOML, such that their entries have
+/// spans within the store's sources.
+fn spanned_store(config: ConfigFile, audits: AuditsFile, imports: ImportsFile) -> Store {
+    fn to_toml(val: impl serde::Serialize) -> String {
+        crate::serialization::to_formatted_toml(val, None)
+            .unwrap()
+            .to_string()
+    }
+    Store::mock_acquire(
+        &to_toml(&config),
+        &to_toml(&audits),
+        &to_toml(&imports),
+        mock_now().date_naive(),
+        false,
+    )
+    .unwrap()
+}
+
+/// Get the SARIF output for the store, resolved in a workspace with a
+/// `Cargo.lock` listing every package.
+fn get_sarif(
+    metadata: &Metadata,
+    store: &Store,
+    policy_errors: Option<&CratePolicyErrors>,
+) -> String {
+    get_sarif_with_line_ending(metadata, store, policy_errors, "\n")
+}
+
+fn get_sarif_with_line_ending(
+    metadata: &Metadata,
+    store: &Store,
+    policy_errors: Option<&CratePolicyErrors>,
+    line_ending: &str,
+) -> String {
+    let workspace = tempfile::tempdir().unwrap();
+    let mut metadata = metadata.clone();
+    metadata.workspace_root = Utf8PathBuf::from_path_buf(workspace.path().to_owned()).unwrap();
+
+    let mut lockfile = String::from("version = 3\n");
+    for package in &metadata.packages {
+        write!(
+            lockfile,
+            "\n[[package]]\nname = \"{}\"\nversion = \"{}\"\n",
+            package.name, package.version
+        )
+        .unwrap();
+    }
+    fs::write(
+        workspace.path().join("Cargo.lock"),
+        lockfile.replace('\n', line_ending),
+    )
+    .unwrap();
+
+    let cfg = mock_cfg(&metadata);
+    let report = crate::resolver::resolve(&metadata, None, store);
+    let suggest = report.compute_suggest(&cfg, store, None).unwrap();
+
+    let output = BasicTestOutput::new();
+    report
+        .print_sarif(
+            &output.clone().as_dyn(),
+            &cfg,
+            &workspace.path().join("supply-chain"),
+            store,
+            suggest.as_ref(),
+            policy_errors,
+        )
+        .unwrap();
+    output
+        .to_string()
+        .replace(env!("CARGO_PKG_VERSION"), "[version]")
+}
+
+#[test]
+fn sarif_vet_failure() {
+    // (Fail) Unvetted packages are reported at their `Cargo.lock` entries.
+
+    let _enter = TEST_RUNTIME.enter();
+    let mock = MockMetadata::simple();
+
+    let metadata = mock.metadata();
+    let (config, audits, imports) = builtin_files_no_exemptions(&metadata);
+
+    let store = Store::mock(config, audits, imports);
+
+    insta::assert_snapshot!(get_sarif(&metadata, &store, None));
+}
+
+#[test]
+fn sarif_vet_failure_crlf_lockfile() {
+    // (Fail) `Cargo.lock` entries are found even if the lockfile has CRLF line
+    // endings, at the same positions as with LF line endings.
+
+    let _enter = TEST_RUNTIME.enter();
+    let mock = MockMetadata::simple();
+
+    let metadata = mock.metadata();
+    let (config, audits, imports) = builtin_files_no_exemptions(&metadata);
+
+    let store = Store::mock(config, audits, imports);
+
+    let crlf = get_sarif_with_line_ending(&metadata, &store, None, "\r\n");
+    assert!(crlf.contains("\"region\""));
+    assert_eq!(crlf, get_sarif(&metadata, &store, None));
+}
+
+#[test]
+fn sarif_violation_conflict() {
+    // (Fail) Violations are reported at their entry in audits.toml, along
+    // with the audit they conflict with.
+
+    let _enter = TEST_RUNTIME.enter();
+    let mock = MockMetadata::simple();
+
+    let metadata = mock.metadata();
+    let (config, mut audits, imports) = builtin_files_full_audited(&metadata);
+
+    let violation_ver = VersionReq::parse(&format!("={DEFAULT_VER}")).unwrap();
+    audits
+        .audits
+        .get_mut("third-party1")
+        .unwrap()
+        .push(violation(violation_ver, SAFE_TO_RUN));
+
+    let store = spanned_store(config, audits, imports);
+
+    insta::assert_snapshot!(get_sarif(&metadata, &store, None));
+}
+
+#[test]
+fn sarif_policy_errors() {
+    // (Fail) Crate policy errors are reported alongside the other results, at
+    // their entry in config.toml.
+
+    let _enter = TEST_RUNTIME.enter();
+    let mock = MockMetadata::simple();
+
+    let metadata = mock.metadata();
+    let (mut config, audits, imports) = builtin_files_full_audited(&metadata);
+
+    config.policy.insert(
+        "non-existent".into(),
+        PackagePolicyEntry::Unversioned(Default::default()),
+    );
+
+    let store = spanned_store(config, audits, imports);
+    let policy_errors = crate::check_crate_policies(&mock_cfg(&metadata), &store).unwrap_err();
+
+    insta::assert_snapshot!(get_sarif(&metadata, &store, Some(&policy_errors)));
+}
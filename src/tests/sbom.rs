@@ -0,0 +1,126 @@
+use crate::cli::SbomFormat;
+
+use super::*;
+
+fn get_sbom(metadata: &Metadata, store: &Store, format: SbomFormat) -> String {
+    let cfg = mock_cfg(metadata);
+    let report = crate::resolver::resolve(metadata, None, store);
+    let output = BasicTestOutput::new();
+    report
+        .print_sbom(&output.clone().as_dyn(), &cfg, store, format)
+        .unwrap();
+    output.to_string()
+}
+
+/// A store where each third-party package is vetted in a different way: by
+/// a local audit, an imported delta audit from an exemption, and a trusted
+/// publisher.
+fn mixed_store(metadata: &Metadata) -> Store {
+    let (mut config, mut audits, mut imports) = builtin_files_full_audited(metadata);
+
+    let mut audit = full_audit(ver(DEFAULT_VER), SAFE_TO_DEPLOY);
+    audit.who = vec!["Alice <alice@example.com>".to_owned().into()];
+    audits.audits.insert("third-party1".to_owned(), vec![audit]);
+
+    audits.audits.remove("third-party2");
+    imports.audits.insert(
+        FOREIGN.to_owned(),
+        AuditsFile {
+            criteria: SortedMap::new(),
+            wildcard_audits: SortedMap::new(),
+            audits: [(
+                "third-party2".to_owned(),
+                vec![delta_audit(ver(5), ver(DEFAULT_VER), SAFE_TO_DEPLOY)],
+            )]
+            .into_iter()
+            .collect(),
+            trusted: SortedMap::new(),
+        },
+    );
+    config.exemptions.insert(
+        "third-party2".to_owned(),
+        vec![exemptions(ver(5), SAFE_TO_DEPLOY)],
+    );
+
+    audits.audits.remove("transitive-third-party1");
+    audits.trusted.insert(
+        "transitive-third-party1".to_owned(),
+        vec![trusted_entry(1, SAFE_TO_DEPLOY)],
+    );
+    imports.publisher.insert(
+        "transitive-third-party1".to_owned(),
+        vec![publisher_entry(ver(DEFAULT_VER), 1)],
+    );
+
+    Store::mock(config, audits, imports)
+}
+
+#[test]
+fn sbom_cyclonedx() {
+    // (Pass) Components are annotated with their criteria, sources and auditors.
+
+    let _enter = TEST_RUNTIME.enter();
+    let mock = MockMetadata::simple();
+
+    let metadata = mock.metadata();
+    let store = mixed_store(&metadata);
+
+    insta::assert_snapshot!(get_sbom(&metadata, &store, SbomFormat::Cyclonedx));
+}
+
+#[test]
+fn sbom_spdx() {
+    // (Pass) Packages are annotated with their criteria, sources and auditors.
+
+    let _enter = TEST_RUNTIME.enter();
+    let mock = MockMetadata::simple();
+
+    let metadata = mock.metadata();
+    let store = mixed_store(&metadata);
+
+    insta::assert_snapshot!(get_sbom(&metadata, &store, SbomFormat::Spdx));
+}
+
+#[test]
+fn sbom_unvetted() {
+    // (Fail) Unvetted packages are still listed, with their missing criteria.
+
+    let _enter = TEST_RUNTIME.enter();
+    let mock = MockMetadata::simple();
+
+    let metadata = mock.metadata();
+    let (config, mut audits, imports) = builtin_files_full_audited(&metadata);
+
+    audits.audits.insert(
+        "third-party1".to_owned(),
+        vec![full_audit(ver(DEFAULT_VER), SAFE_TO_RUN)],
+    );
+
+    let store = Store::mock(config, audits, imports);
+
+    insta::assert_snapshot!(get_sbom(&metadata, &store, SbomFormat::Cyclonedx));
+}
+
+#[test]
+fn sbom_violation_and_unvetted() {
+    // (Fail) Unvetted packages are reported as such even when the check also
+    // failed due to an unrelated violation conflict.
+
+    let _enter = TEST_RUNTIME.enter();
+    let mock = MockMetadata::simple();
+
+    let metadata = mock.metadata();
+    let (config, mut audits, imports) = builtin_files_full_audited(&metadata);
+
+    let violation_ver = VersionReq::parse(&format!("={DEFAULT_VER}")).unwrap();
+    audits
+        .audits
+        .get_mut("third-party1")
+        .unwrap()
+        .push(violation(violation_ver, SAFE_TO_RUN));
+    audits.audits.remove("third-party2");
+
+    let store = Store::mock(config, audits, imports);
+
+    insta::assert_snapshot!(get_sbom(&metadata, &store, SbomFormat::Cyclonedx));
+}
@@ -0,0 +1,110 @@
+use crate::format::StaleAuditPolicy;
+
+use super::*;
+
+fn stale_audit_policy(max_age_years: u32) -> Option<StaleAuditPolicy> {
+    Some(StaleAuditPolicy {
+        max_age_years,
+        criteria: vec![SAFE_TO_DEPLOY.to_owned().into()],
+    })
+}
+
+fn dated(mut entry: AuditEntry, date: &str) -> AuditEntry {
+    entry.date = Some(date.parse().unwrap());
+    entry
+}
+
+fn stale_store(metadata: &Metadata, third_party1_audits: Vec<AuditEntry>) -> Store {
+    let (mut config, mut audits, imports) = builtin_files_full_audited(metadata);
+    config.stale_audits = stale_audit_policy(3);
+    audits
+        .audits
+        .insert("third-party1".to_owned(), third_party1_audits);
+
+    let mut store = Store::mock(config, audits, imports);
+    store.mark_stale_audits(metadata, mock_today());
+    store
+}
+
+#[test]
+fn stale_full_audit() {
+    // (Fail) The full audit of an old major version is too old to vouch for
+    // the version in use, so suggest proposes a refresh delta from it.
+
+    let _enter = TEST_RUNTIME.enter();
+    let mock = MockMetadata::simple();
+
+    let metadata = mock.metadata();
+    let store = stale_store(
+        &metadata,
+        vec![
+            dated(full_audit(ver(5), SAFE_TO_DEPLOY), "2018-01-01"),
+            delta_audit(ver(5), ver(DEFAULT_VER), SAFE_TO_DEPLOY),
+        ],
+    );
+
+    assert_report_snapshot!("stale-full-audit", metadata, store);
+}
+
+#[test]
+fn stale_full_audit_refreshed() {
+    // (Pass) A recent delta audit from the audited version refreshes it.
+
+    let _enter = TEST_RUNTIME.enter();
+    let mock = MockMetadata::simple();
+
+    let metadata = mock.metadata();
+    let store = stale_store(
+        &metadata,
+        vec![
+            dated(full_audit(ver(5), SAFE_TO_DEPLOY), "2018-01-01"),
+            dated(
+                delta_audit(ver(5), ver(DEFAULT_VER), SAFE_TO_DEPLOY),
+                "2022-06-01",
+            ),
+        ],
+    );
+
+    assert_report_snapshot!("stale-full-audit-refreshed", metadata, store);
+}
+
+#[test]
+fn old_full_audit_compatible() {
+    // (Pass) An old full audit of a semver-compatible version isn't stale.
+
+    let _enter = TEST_RUNTIME.enter();
+    let mock = MockMetadata::simple();
+
+    let metadata = mock.metadata();
+    let store = stale_store(
+        &metadata,
+        vec![dated(
+            full_audit(ver(DEFAULT_VER), SAFE_TO_DEPLOY),
+            "2018-01-01",
+        )],
+    );
+
+    assert!(store.audits.audits["third-party1"]
+        .iter()
+        .all(|entry| !entry.is_stale));
+    assert_report_snapshot!("old-full-audit-compatible", metadata, store);
+}
+
+#[test]
+fn undated_full_audit() {
+    // (Pass) Audits recorded without a date are never considered stale.
+
+    let _enter = TEST_RUNTIME.enter();
+    let mock = MockMetadata::simple();
+
+    let metadata = mock.metadata();
+    let store = stale_store(
+        &metadata,
+        vec![
+            full_audit(ver(5), SAFE_TO_DEPLOY),
+            delta_audit(ver(5), ver(DEFAULT_VER), SAFE_TO_DEPLOY),
+        ],
+    );
+
+    assert_report_snapshot!("undated-full-audit", metadata, store);
+}
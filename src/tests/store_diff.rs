@@ -0,0 +1,229 @@
+use super::*;
+
+fn get_store_diff(old: &Store, new: &Store) -> String {
+    let diff = crate::store_diff::diff_stores(old, new);
+
+    let human_output = BasicTestOutput::new();
+    crate::store_diff::print_store_diff(&human_output.clone().as_dyn(), &diff, false);
+    let markdown_output = BasicTestOutput::new();
+    crate::store_diff::print_store_diff(&markdown_output.clone().as_dyn(), &diff, true);
+    let json = serde_json::to_string_pretty(&diff).unwrap();
+    format!("{human_output}\n{markdown_output}\n{json}")
+}
+
+#[test]
+fn store_diff_unchanged() {
+    // Reformatting or reordering the store isn't a change to the supply-chain.
+
+    let _enter = TEST_RUNTIME.enter();
+    let mock = MockMetadata::simple();
+
+    let metadata = mock.metadata();
+    let (config, audits, imports) = builtin_files_full_audited(&metadata);
+
+    let old = Store::mock(config.clone(), audits.clone(), imports.clone());
+    let new = Store::mock(config, audits, imports);
+
+    insta::assert_snapshot!(get_store_diff(&old, &new));
+}
+
+#[test]
+fn store_diff_changes() {
+    // Every kind of change to the supply-chain is reported.
+
+    let _enter = TEST_RUNTIME.enter();
+    let mock = MockMetadata::simple();
+
+    let metadata = mock.metadata();
+    let (mut old_config, mut old_audits, imports) = builtin_files_full_audited(&metadata);
+    old_config.exemptions.insert(
+        "third-party1".to_owned(),
+        vec![exemptions(ver(5), SAFE_TO_RUN)],
+    );
+    old_config.exemptions.insert(
+        "third-party2".to_owned(),
+        vec![exemptions(ver(5), SAFE_TO_DEPLOY)],
+    );
+    old_config.exemptions.insert(
+        "transitive-third-party1".to_owned(),
+        vec![exemptions(ver(5), SAFE_TO_DEPLOY)],
+    );
+    old_audits.audits.insert(
+        "third-party2".to_owned(),
+        vec![delta_audit(ver(5), ver(DEFAULT_VER), SAFE_TO_DEPLOY)],
+    );
+
+    let (mut new_config, mut new_audits) = (old_config.clone(), old_audits.clone());
+
+    // Broadened, narrowed, added and removed exemptions.
+    new_config.exemptions.insert(
+        "third-party1".to_owned(),
+        vec![
+            exemptions(ver(5), SAFE_TO_DEPLOY),
+            exemptions(ver(6), SAFE_TO_RUN),
+        ],
+    );
+    new_config.exemptions.insert(
+        "third-party2".to_owned(),
+        vec![exemptions(ver(5), SAFE_TO_RUN)],
+    );
+    new_config.exemptions.remove("transitive-third-party1");
+
+    // A new import.
+    new_config.imports.insert(
+        FOREIGN.to_owned(),
+        crate::format::RemoteImport {
+            url: vec![FOREIGN_URL.to_owned()],
+            ..Default::default()
+        },
+    );
+
+    // Changed, added and removed policy criteria.
+    new_config
+        .policy
+        .insert("first-party".to_owned(), self_policy([SAFE_TO_RUN]));
+    new_config.policy.insert(
+        "root-package".to_owned(),
+        dep_policy([("third-party1", [SAFE_TO_RUN])]),
+    );
+
+    // An audit and a violation added, an audit removed, and a wildcard audit
+    // and trusted publisher added.
+    new_audits.audits.insert(
+        "third-party2".to_owned(),
+        vec![full_audit(ver(5), SAFE_TO_DEPLOY)],
+    );
+    new_audits
+        .audits
+        .entry("transitive-third-party1".to_owned())
+        .or_default()
+        .push(violation("<5".parse().unwrap(), SAFE_TO_RUN));
+    new_audits.wildcard_audits.insert(
+        "third-party1".to_owned(),
+        vec![wildcard_audit(1, SAFE_TO_DEPLOY)],
+    );
+    new_audits.trusted.insert(
+        "third-party2".to_owned(),
+        vec![trusted_entry(2, SAFE_TO_RUN)],
+    );
+
+    let old = Store::mock(old_config, old_audits, imports.clone());
+    let new = Store::mock(new_config, new_audits, imports);
+
+    insta::assert_snapshot!(get_store_diff(&old, &new));
+}
+
+#[test]
+fn store_diff_custom_criteria() {
+    // Custom criteria implications are taken into account when deciding
+    // whether an exemption was broadened.
+
+    let _enter = TEST_RUNTIME.enter();
+    let mock = MockMetadata::simple();
+
+    let metadata = mock.metadata();
+    let (mut old_config, mut audits, imports) = builtin_files_full_audited(&metadata);
+    audits.criteria.insert(
+        "strong-reviewed".to_owned(),
+        criteria_implies("strongly reviewed", ["reviewed"]),
+    );
+    audits
+        .criteria
+        .insert("reviewed".to_owned(), criteria("reviewed"));
+    old_config.exemptions.insert(
+        "third-party1".to_owned(),
+        vec![exemptions(ver(5), "reviewed")],
+    );
+    old_config.exemptions.insert(
+        "third-party2".to_owned(),
+        vec![exemptions(ver(5), "reviewed")],
+    );
+
+    let mut new_config = old_config.clone();
+    new_config.exemptions.insert(
+        "third-party1".to_owned(),
+        vec![exemptions(ver(5), "strong-reviewed")],
+    );
+    new_config.exemptions.insert(
+        "third-party2".to_owned(),
+        vec![exemptions(ver(5), SAFE_TO_RUN)],
+    );
+
+    let old = Store::mock(old_config, audits.clone(), imports.clone());
+    let new = Store::mock(new_config, audits, imports);
+
+    insta::assert_snapshot!(get_store_diff(&old, &new));
+}
+
+#[test]
+fn store_diff_imports() {
+    // Every field of an import is compared, and changes which make the import
+    // trusted for more are reported as broadened.
+
+    let _enter = TEST_RUNTIME.enter();
+    let mock = MockMetadata::simple();
+
+    let metadata = mock.metadata();
+    let (mut old_config, audits, imports) = builtin_files_full_audited(&metadata);
+    old_config.imports.insert(
+        FOREIGN.to_owned(),
+        crate::format::RemoteImport {
+            url: vec![FOREIGN_URL.to_owned()],
+            sha256: vec!["abc123".to_owned()],
+            exclude: vec!["third-party1".to_owned()],
+            criteria_map: [
+                ("fuzzed", SAFE_TO_RUN),
+                ("reviewed", SAFE_TO_RUN),
+                ("unsafe-free", SAFE_TO_RUN),
+            ]
+            .into_iter()
+            .map(|(foreign, local)| (foreign.to_string().into(), vec![local.to_string().into()]))
+            .collect(),
+            max_criteria: vec![SAFE_TO_RUN.to_string().into()],
+            auditors: vec!["alice@example.com".to_owned()],
+            public_key: vec!["key1".to_owned()],
+            exclude_trusted: true,
+            ..Default::default()
+        },
+    );
+    old_config.imports.insert(
+        OTHER_FOREIGN.to_owned(),
+        crate::format::RemoteImport {
+            url: vec![OTHER_FOREIGN_URL.to_owned()],
+            rev: vec!["aaaaaaa".to_owned()],
+            ..Default::default()
+        },
+    );
+
+    let mut new_config = old_config.clone();
+    new_config.imports.insert(
+        FOREIGN.to_owned(),
+        crate::format::RemoteImport {
+            url: vec![FOREIGN_URL.to_owned(), OTHER_FOREIGN_URL.to_owned()],
+            criteria_map: [
+                ("fuzzed", SAFE_TO_RUN),
+                ("reviewed", SAFE_TO_DEPLOY),
+                ("audited", SAFE_TO_DEPLOY),
+            ]
+            .into_iter()
+            .map(|(foreign, local)| (foreign.to_string().into(), vec![local.to_string().into()]))
+            .collect(),
+            auditors: vec!["alice@example.com".to_owned(), "bob@example.com".to_owned()],
+            exclude_wildcard_audits: true,
+            ..Default::default()
+        },
+    );
+    new_config.imports.insert(
+        OTHER_FOREIGN.to_owned(),
+        crate::format::RemoteImport {
+            url: vec![OTHER_FOREIGN_URL.to_owned()],
+            rev: vec!["bbbbbbb".to_owned()],
+            ..Default::default()
+        },
+    );
+
+    let old = Store::mock(old_config, audits.clone(), imports.clone());
+    let new = Store::mock(new_config, audits, imports);
+
+    insta::assert_snapshot!(get_store_diff(&old, &new));
+}
@@ -82,6 +82,86 @@ violation = "^5.0.0"
     insta::assert_snapshot!(acquire_errors);
 }
 
+#[test]
+fn test_shadowed_builtin_criteria() {
+    let audits = r#"
+# cargo-vet audits file
+
+[criteria.safe-to-run]
+description = "shadowed"
+
+[audits]
+"#;
+
+    let acquire_errors = get_valid_store(EMPTY_CONFIG, audits, EMPTY_IMPORTS);
+    insta::assert_snapshot!(acquire_errors);
+}
+
+#[test]
+fn test_criteria_implies_cycle() {
+    let audits = r#"
+# cargo-vet audits file
+
+[criteria.cycle-a]
+description = "first"
+implies = "cycle-b"
+
+[criteria.cycle-b]
+description = "second"
+implies = "cycle-a"
+
+[audits]
+"#;
+
+    let acquire_errors = get_valid_store(EMPTY_CONFIG, audits, EMPTY_IMPORTS);
+    insta::assert_snapshot!(acquire_errors);
+}
+
+#[test]
+fn test_diff_weights() {
+    let config = r#"
+# cargo-vet config file
+
+[cargo-vet]
+version = "1.0"
+
+[diff-weights]
+unsafe = 4.0
+proc-macro = 2.0
+
+[[diff-weights.files]]
+paths = ["tests/**", "benches/**"]
+weight = 0.0
+
+[[diff-weights.files]]
+extensions = ["md"]
+weight = 0.25
+"#;
+
+    let acquire_errors = get_valid_store(config, EMPTY_AUDITS, EMPTY_IMPORTS);
+    insta::assert_snapshot!(acquire_errors);
+}
+
+#[test]
+fn test_bad_diff_weights() {
+    let config = r#"
+# cargo-vet config file
+
+[cargo-vet]
+version = "1.0"
+
+[diff-weights]
+unsafe = -1.0
+
+[[diff-weights.files]]
+paths = ["tests/**"]
+weight = nan
+"#;
+
+    let acquire_errors = get_valid_store(config, EMPTY_AUDITS, EMPTY_IMPORTS);
+    insta::assert_snapshot!(acquire_errors);
+}
+
 #[test]
 fn test_many_bad_config() {
     let config = r#"
@@ -350,6 +430,180 @@ end = "2021-03-01"
     insta::assert_snapshot!(acquire_errors);
 }
 
+#[test]
+fn test_exemption_end_date() {
+    // Exemptions may have an end date, which is preserved when formatting.
+    let config = r#"
+# cargo-vet config file
+
+[cargo-vet]
+version = "1.0"
+
+[[exemptions.zzz]]
+version = "1.0.0"
+criteria = "safe-to-deploy"
+end = "2023-06-01"
+notes = "Temporary, until the next release is audited"
+"#;
+
+    let acquire_errors = get_valid_store(config, EMPTY_AUDITS, EMPTY_IMPORTS);
+    assert_eq!(acquire_errors, "");
+}
+
+#[test]
+fn test_distant_future_exemption_end_date() {
+    // NOTE: `get_valid_store` pretends that "today" is 2023-01-01, so this will
+    // always be over a year in the future.
+    let config = r#"
+# cargo-vet config file
+
+[cargo-vet]
+version = "1.0"
+
+[[exemptions.zzz]]
+version = "1.0.0"
+criteria = "safe-to-deploy"
+end = "2024-05-15"
+"#;
+
+    let acquire_errors = get_valid_store(config, EMPTY_AUDITS, EMPTY_IMPORTS);
+    insta::assert_snapshot!(acquire_errors);
+}
+
+#[test]
+fn test_audit_dates_and_stale_audits() {
+    // Audits may record when they were performed, and the config may specify
+    // a policy for when old full audits stop counting.
+    let config = r#"
+# cargo-vet config file
+
+[cargo-vet]
+version = "1.0"
+
+[stale-audits]
+max-age-years = 3
+criteria = "safe-to-deploy"
+"#;
+
+    let audits = r#"
+# cargo-vet audits file
+
+[[audits.zzz]]
+who = "Alex Example <alex@example.com>"
+criteria = "safe-to-deploy"
+version = "1.0.0"
+date = "2019-05-20"
+"#;
+
+    let acquire_errors = get_valid_store(config, audits, EMPTY_IMPORTS);
+    assert_eq!(acquire_errors, "");
+}
+
+#[test]
+fn test_stale_audits_bad_criteria() {
+    let config = r#"
+# cargo-vet config file
+
+[cargo-vet]
+version = "1.0"
+
+[stale-audits]
+max-age-years = 3
+criteria = ["safe-to-deploy", "oops"]
+"#;
+
+    let acquire_errors = get_valid_store(config, EMPTY_AUDITS, EMPTY_IMPORTS);
+    insta::assert_snapshot!(acquire_errors);
+}
+
+#[test]
+fn test_violation_disputes() {
+    // Disputes of a peer's violations are preserved when formatting.
+    let config = r#"
+# cargo-vet config file
+
+[cargo-vet]
+version = "1.0"
+
+[imports.peer-company]
+url = "https://peercompany.co.uk"
+
+[[disputes.zzz]]
+import = "peer-company"
+violation = "<2.0.0"
+notes = "The reported issue only affects big-endian targets"
+"#;
+
+    let imports = r#"
+# cargo-vet imports lock
+
+[audits.peer-company.audits]
+"#;
+
+    let acquire_errors = get_valid_store(config, EMPTY_AUDITS, imports);
+    assert_eq!(acquire_errors, "");
+}
+
+#[test]
+fn test_bad_violation_disputes() {
+    let config = r#"
+# cargo-vet config file
+
+[cargo-vet]
+version = "1.0"
+
+[imports.peer-company]
+url = "https://peercompany.co.uk"
+
+[[disputes.zzz]]
+import = "rival-company"
+violation = "<2.0.0"
+notes = "The reported issue only affects big-endian targets"
+
+[[disputes.zzz]]
+import = "peer-company"
+violation = "*"
+notes = ""
+"#;
+
+    let imports = r#"
+# cargo-vet imports lock
+
+[audits.peer-company.audits]
+"#;
+
+    let acquire_errors = get_valid_store(config, EMPTY_AUDITS, imports);
+    insta::assert_snapshot!(acquire_errors);
+}
+
+#[test]
+fn test_import_limits() {
+    // Limits on what is imported from a peer are preserved when formatting,
+    // and `max-criteria` must name local criteria.
+    let config = r#"
+# cargo-vet config file
+
+[cargo-vet]
+version = "1.0"
+
+[imports.peer-company]
+url = "https://peercompany.co.uk"
+max-criteria = ["safe-to-run", "oops"]
+auditors = ["alice@example.com", "Bob <bob@example.com>"]
+exclude-wildcard-audits = true
+exclude-trusted = true
+"#;
+
+    let imports = r#"
+# cargo-vet imports lock
+
+[audits.peer-company.audits]
+"#;
+
+    let acquire_errors = get_valid_store(config, EMPTY_AUDITS, imports);
+    insta::assert_snapshot!(acquire_errors);
+}
+
 #[test]
 fn test_invalid_formatting() {
     let config = r#"
@@ -139,6 +139,43 @@ fn trusted_suggest_local() {
     assert_report_snapshot!("trusted_suggest_local", metadata, store, Some(&network));
 }
 
+#[test]
+fn trusted_suggest_local_mirror() {
+    // (Fail) Publisher information is fetched from the configured mirror of
+    // the crates.io API, with the same results as from crates.io itself.
+
+    let _enter = TEST_RUNTIME.enter();
+    let mock = MockMetadata::simple();
+
+    let metadata = mock.metadata();
+    let (config, mut audits, imports) = builtin_files_full_audited(&metadata);
+    audits.audits.remove("transitive-third-party1");
+    audits.trusted.insert(
+        "other-crate".to_owned(),
+        vec![trusted_entry(1, SAFE_TO_DEPLOY)],
+    );
+
+    let mut network = Network::new_mock();
+    network.mock_crates_io_mirror("https://mirror.example.com/api/cargo/crates-io/");
+    MockRegistryBuilder::new()
+        .user(1, "testuser", "Test user")
+        .package(
+            "transitive-third-party1",
+            &[reg_published_by(
+                ver(DEFAULT_VER),
+                Some(1),
+                mock_weeks_ago(2),
+            )],
+        )
+        .serve(&mut network);
+
+    let cfg = mock_cfg(&metadata);
+
+    let store = Store::mock_online(&cfg, config, audits, imports, &network, true).unwrap();
+
+    assert_report_snapshot!("trusted_suggest_local", metadata, store, Some(&network));
+}
+
 #[test]
 fn trusted_suggest_import() {
     let _enter = TEST_RUNTIME.enter();
@@ -167,6 +167,38 @@ fn mock_simple_missing_leaves() {
     assert_report_snapshot!("mock-simple-missing-leaves", metadata, store);
 }
 
+#[test]
+fn mock_simple_missing_leaves_weighted() {
+    // (Fail) Missing all leaf audits, with the suggested audits weighted by
+    // the configured diff-weights.
+
+    let _enter = TEST_RUNTIME.enter();
+    let mock = MockMetadata::simple();
+
+    let metadata = mock.metadata();
+    let (mut config, mut audits, imports) = files_full_audited(&metadata);
+
+    config
+        .diff_weights
+        .files
+        .push(crate::format::DiffWeightRule {
+            paths: vec!["src/**".to_owned()],
+            extensions: vec![],
+            weight: 0.5,
+        });
+
+    audits.audits.get_mut("third-party2").unwrap().clear();
+    audits
+        .audits
+        .get_mut("transitive-third-party1")
+        .unwrap()
+        .clear();
+
+    let store = Store::mock(config, audits, imports);
+
+    assert_report_snapshot!("mock-simple-missing-leaves-weighted", metadata, store);
+}
+
 #[test]
 fn mock_simple_weaker_transitive_req() {
     // (Pass) A third-party dep with weaker requirements on a child dep
@@ -1537,6 +1569,41 @@ fn builtin_simple_exemptions_not_a_real_dep() {
     assert_report_snapshot!("builtin-simple-not-a-real-dep", metadata, store);
 }
 
+#[test]
+fn builtin_simple_exemptions_expired() {
+    // (Fail) an exemption whose end date has passed no longer vets the
+    // package, so it's reported as unvetted with a suggested audit, while an
+    // exemption which hasn't expired yet still applies.
+
+    let _enter = TEST_RUNTIME.enter();
+    let mock = MockMetadata::simple();
+
+    let metadata = mock.metadata();
+    let (mut config, mut audits, imports) = builtin_files_full_audited(&metadata);
+
+    audits.audits.remove("third-party1");
+    audits.audits.remove("third-party2");
+    config.exemptions.insert(
+        "third-party1".to_string(),
+        vec![ExemptedDependency {
+            end: Some((mock_today() - chrono::Duration::days(1)).into()),
+            ..exemptions(ver(DEFAULT_VER), SAFE_TO_DEPLOY)
+        }],
+    );
+    config.exemptions.insert(
+        "third-party2".to_string(),
+        vec![ExemptedDependency {
+            end: Some((mock_today() + chrono::Duration::days(1)).into()),
+            ..exemptions(ver(DEFAULT_VER), SAFE_TO_DEPLOY)
+        }],
+    );
+
+    let mut store = Store::mock(config, audits, imports);
+    store.mark_expired_exemptions(mock_today());
+
+    assert_report_snapshot!("builtin-simple-exemptions-expired", metadata, store);
+}
+
 #[test]
 fn builtin_simple_deps_exemptions_overbroad() {
     // (Pass) the exemptions entry is needed but it's overbroad
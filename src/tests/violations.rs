@@ -263,3 +263,87 @@ fn mock_simple_violation_hit_with_extra_junk() {
 
     assert_report_snapshot!("mock-simple-violation-hit-with-extra-junk", metadata, store);
 }
+
+fn imported_violation_store(
+    metadata: &Metadata,
+    disputes: Vec<crate::format::ViolationDispute>,
+) -> Store {
+    let (mut config, audits, mut imports) = builtin_files_full_audited(metadata);
+
+    let violation_ver = VersionReq::parse(&format!("={DEFAULT_VER}")).unwrap();
+    config.imports.insert(
+        FOREIGN.to_owned(),
+        crate::format::RemoteImport {
+            url: vec![FOREIGN_URL.to_owned()],
+            ..Default::default()
+        },
+    );
+    imports.audits.insert(
+        FOREIGN.to_owned(),
+        AuditsFile {
+            criteria: SortedMap::new(),
+            wildcard_audits: SortedMap::new(),
+            audits: [(
+                "third-party1".to_owned(),
+                vec![violation(violation_ver, SAFE_TO_DEPLOY)],
+            )]
+            .into_iter()
+            .collect(),
+            trusted: SortedMap::new(),
+        },
+    );
+    config.disputes.insert("third-party1".to_owned(), disputes);
+
+    Store::mock(config, audits, imports)
+}
+
+#[test]
+fn builtin_simple_imported_violation_disputed() {
+    // (Pass) A peer's violation conflicts with our audit, but we've disputed it.
+
+    let _enter = TEST_RUNTIME.enter();
+    let mock = MockMetadata::simple();
+
+    let metadata = mock.metadata();
+    let store = imported_violation_store(
+        &metadata,
+        vec![crate::format::ViolationDispute {
+            import: FOREIGN.to_owned().into(),
+            violation: VersionReq::parse(&format!("={DEFAULT_VER}")).unwrap(),
+            notes: "The reported issue only affects big-endian targets".to_owned(),
+        }],
+    );
+    assert!(store.unmatched_disputes().is_empty());
+
+    assert_report_snapshot!(
+        "builtin-simple-imported-violation-disputed",
+        metadata,
+        store
+    );
+}
+
+#[test]
+fn builtin_simple_imported_violation_dispute_mismatch() {
+    // (Fail) A dispute only suppresses the violation with the same version
+    // requirement.
+
+    let _enter = TEST_RUNTIME.enter();
+    let mock = MockMetadata::simple();
+
+    let metadata = mock.metadata();
+    let store = imported_violation_store(
+        &metadata,
+        vec![crate::format::ViolationDispute {
+            import: FOREIGN.to_owned().into(),
+            violation: VersionReq::parse("<5.0.0").unwrap(),
+            notes: "Fixed in 5.0.0".to_owned(),
+        }],
+    );
+    assert_eq!(store.unmatched_disputes().len(), 1);
+
+    assert_report_snapshot!(
+        "builtin-simple-imported-violation-dispute-mismatch",
+        metadata,
+        store
+    );
+}
@@ -0,0 +1,113 @@
+use super::*;
+
+fn get_why(metadata: &Metadata, store: &Store, package: &str) -> String {
+    console::set_colors_enabled(false);
+
+    let report = crate::resolver::resolve(metadata, None, store);
+    let why = report.compute_why(package, None);
+
+    let human_output = BasicTestOutput::new();
+    crate::resolver::print_why_human(&human_output.clone().as_dyn(), &why).unwrap();
+    let json = serde_json::to_string_pretty(&why).unwrap();
+    format!("{human_output}\n{json}")
+}
+
+#[test]
+fn why_root_path() {
+    // The requirement comes from the root, through the shortest path.
+
+    let _enter = TEST_RUNTIME.enter();
+    let mock = MockMetadata::simple();
+
+    let metadata = mock.metadata();
+    let (config, audits, imports) = builtin_files_full_audited(&metadata);
+
+    let store = Store::mock(config, audits, imports);
+
+    insta::assert_snapshot!(get_why(&metadata, &store, "transitive-third-party1"));
+}
+
+#[test]
+fn why_dependency_criteria() {
+    // A dependency-criteria policy entry introduces the requirement.
+
+    let _enter = TEST_RUNTIME.enter();
+    let mock = MockMetadata::simple();
+
+    let metadata = mock.metadata();
+    let (mut config, audits, imports) = builtin_files_full_audited(&metadata);
+
+    config.policy.insert(
+        "first-party".to_owned(),
+        dep_policy([("third-party1", [SAFE_TO_RUN])]),
+    );
+
+    let store = Store::mock(config, audits, imports);
+
+    insta::assert_snapshot!(get_why(&metadata, &store, "transitive-third-party1"));
+}
+
+#[test]
+fn why_dev_criteria() {
+    // Dev-dependencies require the default dev-criteria, or the policy's.
+
+    let _enter = TEST_RUNTIME.enter();
+    let mock = MockMetadata::dev_detection();
+
+    let metadata = mock.metadata();
+    let (mut config, audits, imports) = builtin_files_full_audited(&metadata);
+
+    let store = Store::mock(config.clone(), audits.clone(), imports.clone());
+    let default_output = get_why(&metadata, &store, "simple-dev-indirect");
+
+    config.policy.insert(
+        "root".to_owned(),
+        PackagePolicyEntry::Unversioned(PolicyEntry {
+            dev_criteria: Some(vec![SAFE_TO_DEPLOY.to_owned().into()]),
+            ..default_policy()
+        }),
+    );
+    let store = Store::mock(config, audits, imports);
+    let policy_output = get_why(&metadata, &store, "both");
+
+    insta::assert_snapshot!(format!("{default_output}\n\n{policy_output}"));
+}
+
+#[test]
+fn why_matches_requirements() {
+    // Every criteria `check` requires of a package is traced to an origin.
+
+    let _enter = TEST_RUNTIME.enter();
+    let mock = MockMetadata::dev_detection();
+
+    let metadata = mock.metadata();
+    let (mut config, audits, imports) = builtin_files_full_audited(&metadata);
+
+    config.policy.insert(
+        "root".to_owned(),
+        PackagePolicyEntry::Unversioned(PolicyEntry {
+            dev_criteria: Some(vec![SAFE_TO_DEPLOY.to_owned().into()]),
+            ..default_policy()
+        }),
+    );
+    let store = Store::mock(config, audits, imports);
+
+    let report = crate::resolver::resolve(&metadata, None, &store);
+    for (pkgidx, node) in report.graph.nodes.iter().enumerate() {
+        let why = report.compute_why(node.name, Some(&node.version));
+        let package = &why.packages[0];
+        let required: Vec<_> = report
+            .criteria_mapper
+            .criteria_names(&report.requirements[pkgidx])
+            .collect();
+        assert_eq!(package.required_criteria, required, "{}", node.name);
+        for criteria in &package.criteria {
+            assert!(
+                !criteria.origins.is_empty(),
+                "{} has no origin for {}",
+                node.name,
+                criteria.criteria
+            );
+        }
+    }
+}
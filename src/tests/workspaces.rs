@@ -0,0 +1,82 @@
+use super::*;
+
+fn other_workspace() -> MockMetadata {
+    // A second workspace sharing the store with `MockMetadata::simple`.
+    //
+    //                                    Graph
+    // =======================================================================================
+    //
+    //                                 other-root
+    //                                /          \
+    //                       third-party2      third-party3
+    //
+    MockMetadata::new(vec![
+        MockPackage {
+            name: "other-root",
+            is_workspace: true,
+            is_first_party: true,
+            deps: vec![dep("third-party2"), dep("third-party3")],
+            ..Default::default()
+        },
+        MockPackage {
+            name: "third-party2",
+            ..Default::default()
+        },
+        MockPackage {
+            name: "third-party3",
+            ..Default::default()
+        },
+    ])
+}
+
+fn merged_metadata() -> Metadata {
+    let mut metadata = MockMetadata::simple().metadata();
+    crate::merge_metadata(&mut metadata, other_workspace().metadata());
+    metadata
+}
+
+#[test]
+fn workspaces_merged_graph() {
+    // (Fail) The packages used only by the other workspace need to be vetted
+    // too, while the packages used by both only appear once.
+
+    let _enter = TEST_RUNTIME.enter();
+    let mock = MockMetadata::simple();
+
+    let (config, audits, imports) = builtin_files_full_audited(&mock.metadata());
+    let store = Store::mock(config, audits, imports);
+
+    let metadata = merged_metadata();
+    assert_eq!(
+        metadata
+            .packages
+            .iter()
+            .filter(|p| p.name == "third-party2")
+            .count(),
+        1
+    );
+
+    assert_report_snapshot!("workspaces-merged-graph", metadata, store);
+}
+
+#[test]
+fn workspaces_regenerate_keeps_shared_exemptions() {
+    // (Pass) Regenerating exemptions keeps the exemptions needed by the other
+    // workspace.
+
+    let _enter = TEST_RUNTIME.enter();
+
+    let metadata = merged_metadata();
+    let (config, audits, imports) = builtin_files_inited(&metadata);
+
+    let mut store = Store::mock(config, audits, imports);
+    let cfg = mock_cfg(&metadata);
+    crate::resolver::update_store(&cfg, &mut store, |_| crate::resolver::UpdateMode {
+        search_mode: crate::resolver::SearchMode::RegenerateExemptions,
+        prune_exemptions: true,
+        prune_non_importable_audits: true,
+        prune_imports: true,
+    });
+
+    insta::assert_snapshot!(toml_edit::ser::to_string_pretty(&store.config.exemptions).unwrap());
+}
@@ -233,6 +233,43 @@ fn test_project_dump_graph_full() {
     assert!(output.status.success(), "{}", output.status);
 }
 
+#[test]
+fn test_project_dump_graph_metadata_roundtrip() {
+    // The metadata printed by `dump-graph --metadata` can be read back with
+    // `--metadata-file`, even when saved under a `.lock` name like Bazel's
+    // Cargo.Bazel.lock, and describes the same graph. The graph is read from
+    // the project's Cargo.lock so that `cargo metadata` doesn't need to run.
+    let dump_graph = |metadata_file: &Path, args: &[&str]| {
+        let output = test_project_command(Some("dump-graph"))
+            .arg("--metadata-file")
+            .arg(metadata_file)
+            .args(args)
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .output()
+            .unwrap();
+        assert!(output.status.success(), "{}", format_outputs(&output));
+        output.stdout
+    };
+
+    let lockfile = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("tests")
+        .join("test-project")
+        .join("Cargo.lock");
+    let metadata = dump_graph(&lockfile, &["--metadata"]);
+
+    let dir = tempfile::tempdir().unwrap();
+    let metadata_file = dir.path().join("Cargo.Bazel.lock");
+    std::fs::write(&metadata_file, &metadata).unwrap();
+
+    assert_eq!(metadata, dump_graph(&metadata_file, &["--metadata"]));
+    let full_graph = ["--output-format=json", "--depth=full"];
+    assert_eq!(
+        dump_graph(&lockfile, &full_graph),
+        dump_graph(&metadata_file, &full_graph)
+    );
+}
+
 #[test]
 fn test_project_bad_certify_human() {
     let output = test_project_command(Some("certify"))